@@ -41,6 +41,8 @@ var surfaceView: WGPUSurfaceView? = null
 fun SurfaceCard() {
     var selected by remember { mutableStateOf("boids") }
     val toggleValues = listOf("boids", "MSAA line", "cube", "water", "shadow", "HDR ASTC")
+    // 与 toggleValues 一一对应；设备不支持的示例不在 getExamples 列表中，所以按 id 切换
+    val exampleIds = listOf("boids", "msaa_line", "cube", "water", "shadow", "hdr_image_view")
     val screenWidth = LocalConfiguration.current.screenWidthDp.dp
     Column(modifier = Modifier.fillMaxSize()) {
         Row(
@@ -69,7 +71,7 @@ fun SurfaceCard() {
 //                    selected = title
 //                    toggleValues.forEachIndexed { idx, v ->
 //                        if (v == title) {
-//                            surfaceView?.changeExample(idx)
+//                            surfaceView?.changeExample(exampleIds[idx])
 //                        }
 //                    }
 //                },
@@ -106,7 +108,7 @@ fun SurfaceCard() {
                     selected = title
                     toggleValues.forEachIndexed { idx, v ->
                         if (v == title) {
-                            surfaceView?.changeExample(idx)
+                            surfaceView?.changeExample(exampleIds[idx])
                         }
                     }
                 },
@@ -1,15 +1,80 @@
 package name.jinleili.wgpu
 
 import android.view.Surface
+import java.nio.ByteBuffer
+
+// 与 Rust 端 HostEventKind 的取值一致
+const val HOST_EVENT_READY = 0
+// code 为示例序号，payload 为示例名称
+const val HOST_EVENT_EXAMPLE_CHANGED = 1
+// code 为帧序号，payload 为 JSON 编码的帧统计
+const val HOST_EVENT_FRAME_RENDERED = 2
+const val HOST_EVENT_WARNING = 3
+const val HOST_EVENT_ERROR = 4
+const val HOST_EVENT_DEVICE_LOST = 5
+const val HOST_EVENT_DEVICE_RECOVERED = 6
+const val HOST_EVENT_SURFACE_LOST = 7
+const val HOST_EVENT_MASK_ALL = -1
+
+// 与 Rust 端 ParameterType 的取值一致
+const val PARAMETER_TYPE_FLOAT = 0
+const val PARAMETER_TYPE_INT = 1
+const val PARAMETER_TYPE_BOOL = 2
+const val PARAMETER_TYPE_COLOR = 3
+const val PARAMETER_TYPE_ENUM = 4
+
+// 在渲染线程上调用
+fun interface HostEventListener {
+    fun onHostEvent(kind: Int, code: Long, payload: String?)
+}
 
 class RustBridge {
+    companion object {
+        // 与 Rust 端 Touch 结构体的大小一致
+        const val TOUCH_SIZE = 56
+        // 与 Rust 端 ProfileEntry 结构体的大小一致：32 字节的标签，depth、cpuMs、gpuMs 各 4 字节
+        const val PROFILE_ENTRY_SIZE = 44
+    }
+
     init {
         System.loadLibrary("wgpu_in_app")
     }
 
-    external fun createWgpuCanvas(surface: Surface, idx: Int): Long
+    // libraryDir 用于持久化管线缓存等数据
+    external fun createWgpuCanvas(surface: Surface, idx: Int, libraryDir: String): Long
     external fun enterFrame(rustObj: Long)
+    // scaleFactor 为 DisplayMetrics.density
+    external fun resize(rustObj: Long, scaleFactor: Float)
+    // 安全区域的内边距，单位为 dp
+    external fun setSafeArea(rustObj: Long, top: Float, left: Float, bottom: Float, right: Float)
+    // 在 surfaceDestroyed 中调用，释放 surface 但保留 wgpu 对象与示例的状态
+    external fun suspendSurface(rustObj: Long)
+    // 在 surfaceCreated 中用新的 Surface 恢复渲染，失败时返回 false
+    external fun resumeSurface(rustObj: Long, surface: Surface): Boolean
+    // idx 为示例在 getExamples 列表中的位置
     external fun changeExample(rustObj: Long, idx: Int)
+    // id 为示例的标识（如 "boids"），示例未知、当前设备不支持或创建失败时返回 false
+    external fun changeExampleById(rustObj: Long, id: String): Boolean
+    // 当前设备能运行的示例列表的 JSON 描述：id、name 与 description
+    external fun getExamples(rustObj: Long): String?
+    // buffer 须为 native 字节序的 direct ByteBuffer，每个触摸点占 TOUCH_SIZE 字节，布局见 WGPUSurfaceView.putTouch
+    external fun touches(rustObj: Long, buffer: ByteBuffer, count: Int)
+    // mask 中对应每类事件的位为 (1 shl kind)，创建画布期间发出的事件会在注册时补发；listener 为 null 时取消注册
+    external fun registerHostEventListener(rustObj: Long, listener: HostEventListener?, mask: Int)
+    // 当前示例参数的 JSON 描述：name、label、type、取值范围、default 与当前的 value
+    external fun getParameters(rustObj: Long): String?
+    // [type, intValue, floatValues[0..4]]，FLOAT 存于 floatValues[0]，COLOR 为 RGBA，
+    // INT、BOOL（0 或 1）与 ENUM（选项序号）存于 intValue；没有此参数时为 null
+    external fun getParameter(rustObj: Long, name: String): FloatArray?
+    // 数值超出取值范围时会被限制在范围内，参数不存在或类型不符时返回 false
+    external fun setParameter(rustObj: Long, name: String, type: Int, intValue: Int, floatValues: FloatArray?): Boolean
+    // [fps, 平均帧时间, p50, p95, p99, 掉帧数, 总帧数]，时间单位为毫秒
+    external fun getFrameStats(rustObj: Long): FloatArray?
+    // 在画面上叠加帧率、适配器等调试信息
+    external fun setHudVisible(rustObj: Long, visible: Boolean)
+    external fun setProfilerEnabled(rustObj: Long, enabled: Boolean)
+    // buffer 须为 native 字节序的 direct ByteBuffer，返回全部的项数，大于 buffer 可容纳的项数时结果被截断
+    external fun getProfileEntries(rustObj: Long, buffer: ByteBuffer): Int
 
     external fun dropWgpuCanvas(rustObj: Long)
 }
\ No newline at end of file
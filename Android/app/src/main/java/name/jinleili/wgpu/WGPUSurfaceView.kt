@@ -5,13 +5,32 @@ import android.graphics.Canvas
 import android.graphics.PixelFormat
 import android.util.AttributeSet
 import android.util.Log
+import android.view.MotionEvent
 import android.view.SurfaceHolder
 import android.view.SurfaceView
+import androidx.core.graphics.Insets
+import androidx.core.view.ViewCompat
+import androidx.core.view.WindowInsetsCompat
+import java.nio.ByteBuffer
+import java.nio.ByteOrder
+
+// 与 Rust 端 TouchPhase 和 PointerKind 的取值一致
+private const val TOUCH_PHASE_STARTED = 0
+private const val TOUCH_PHASE_MOVED = 1
+private const val TOUCH_PHASE_ENDED = 2
+private const val TOUCH_PHASE_CANCELLED = 3
+private const val POINTER_KIND_FINGER = 0
+private const val POINTER_KIND_STYLUS = 1
+private const val POINTER_KIND_MOUSE = 2
 
 class WGPUSurfaceView : SurfaceView, SurfaceHolder.Callback2 {
     private var rustBrige = RustBridge()
     private var wgpuObj: Long = Long.MAX_VALUE
-    private var idx: Int = 0
+    // 宿主选中的示例，wgpu 对象重新创建后需要再次切换
+    private var exampleId: String? = null
+    private var touchBuffer: ByteBuffer = allocateTouchBuffer(4)
+    // 最近一次的安全区域，单位为像素；wgpu 对象重新创建后需要再次传给 Rust 端
+    private var safeArea = Insets.NONE
 
     constructor(context: Context) : super(context) {
     }
@@ -34,22 +53,83 @@ class WGPUSurfaceView : SurfaceView, SurfaceHolder.Callback2 {
         // 让系统 ui 能覆盖在 SurfaceView 之上
         this.setZOrderMediaOverlay(true)
         holder.setFormat(PixelFormat.TRANSPARENT)
+
+        // 系统栏与刘海区域改变时（比如旋转屏幕）更新安全区域
+        ViewCompat.setOnApplyWindowInsetsListener(this) { _, insets ->
+            safeArea = insets.getInsets(
+                WindowInsetsCompat.Type.systemBars() or WindowInsetsCompat.Type.displayCutout()
+            )
+            pushSafeArea()
+            insets
+        }
+    }
+
+    private fun pushSafeArea() {
+        if (wgpuObj == Long.MAX_VALUE) {
+            return
+        }
+        val density = resources.displayMetrics.density
+        rustBrige.setSafeArea(
+            wgpuObj,
+            safeArea.top / density,
+            safeArea.left / density,
+            safeArea.bottom / density,
+            safeArea.right / density
+        )
     }
 
     override fun surfaceChanged(holder: SurfaceHolder, format: Int, width: Int, height: Int) {
+        if (wgpuObj != Long.MAX_VALUE) {
+            rustBrige.resize(wgpuObj, resources.displayMetrics.density)
+        }
     }
 
-    // 绘制表面被创建后，创建/重新创建 wgpu 对象
+    // 绘制表面被创建后，创建 wgpu 对象；从后台返回时复用已有的 wgpu 对象，只重建 surface
     override fun surfaceCreated(holder: SurfaceHolder) {
+        if (wgpuObj != Long.MAX_VALUE) {
+            if (rustBrige.resumeSurface(wgpuObj, holder.surface)) {
+                rustBrige.resize(wgpuObj, resources.displayMetrics.density)
+                pushSafeArea()
+                setWillNotDraw(false)
+                return
+            }
+            Log.e("WGPUSurfaceView", "Failed to resume WgpuCanvas, recreating it")
+            rustBrige.dropWgpuCanvas(wgpuObj)
+            wgpuObj = Long.MAX_VALUE
+        }
         holder.let { h ->
-            wgpuObj = rustBrige.createWgpuCanvas(h.surface, this.idx)
+            val obj = rustBrige.createWgpuCanvas(h.surface, 0, context.codeCacheDir.absolutePath)
+            // 返回 0 表示 Rust 端创建 wgpu 对象失败
+            if (obj == 0L) {
+                Log.e("WGPUSurfaceView", "Failed to create WgpuCanvas")
+                return
+            }
+            wgpuObj = obj
+            exampleId?.let { rustBrige.changeExampleById(wgpuObj, it) }
+            // 不需要逐帧的通知，帧统计通过 getFrameStats 按需读取
+            rustBrige.registerHostEventListener(
+                wgpuObj,
+                ::onHostEvent,
+                HOST_EVENT_MASK_ALL and (1 shl HOST_EVENT_FRAME_RENDERED).inv()
+            )
+            // Rust 端无法从 ANativeWindow 获取屏幕密度
+            rustBrige.resize(wgpuObj, resources.displayMetrics.density)
+            pushSafeArea()
             // SurfaceView 默认不会自动开始绘制，setWillNotDraw(false) 用于通知 App 已经准备好开始绘制了。
             setWillNotDraw(false)
         }
     }
 
-    // 绘制表面被销毁后，也销毁 wgpu 对象
+    // 绘制表面被销毁后只释放 surface，device 与示例的状态保留到 surfaceCreated 时恢复
     override fun surfaceDestroyed(holder: SurfaceHolder) {
+        if (wgpuObj != Long.MAX_VALUE) {
+            rustBrige.suspendSurface(wgpuObj)
+        }
+    }
+
+    // 视图被移除后不会再有 surfaceCreated，销毁 wgpu 对象
+    override fun onDetachedFromWindow() {
+        super.onDetachedFromWindow()
         if (wgpuObj != Long.MAX_VALUE) {
             rustBrige.dropWgpuCanvas(wgpuObj)
             wgpuObj = Long.MAX_VALUE
@@ -59,6 +139,18 @@ class WGPUSurfaceView : SurfaceView, SurfaceHolder.Callback2 {
     override fun surfaceRedrawNeeded(holder: SurfaceHolder) {
     }
 
+    private fun onHostEvent(kind: Int, code: Long, payload: String?) {
+        when (kind) {
+            HOST_EVENT_READY -> Log.i("WGPUSurfaceView", "WgpuCanvas is ready")
+            HOST_EVENT_EXAMPLE_CHANGED -> Log.i("WGPUSurfaceView", "Example changed: $code $payload")
+            HOST_EVENT_WARNING -> Log.w("WGPUSurfaceView", payload ?: "")
+            HOST_EVENT_ERROR -> Log.e("WGPUSurfaceView", payload ?: "")
+            HOST_EVENT_DEVICE_LOST -> Log.w("WGPUSurfaceView", "GPU device lost, recovering...")
+            HOST_EVENT_DEVICE_RECOVERED -> Log.i("WGPUSurfaceView", "GPU device recovered")
+            HOST_EVENT_SURFACE_LOST -> Log.w("WGPUSurfaceView", "Surface lost")
+        }
+    }
+
     // API Level 26+
 //    override fun surfaceRedrawNeededAsync(holder: SurfaceHolder, drawingFinished: Runnable) {
 //        super.surfaceRedrawNeededAsync(holder, drawingFinished)
@@ -75,10 +167,62 @@ class WGPUSurfaceView : SurfaceView, SurfaceHolder.Callback2 {
         invalidate()
     }
 
-    fun changeExample(index: Int) {
-        if (wgpuObj != Long.MAX_VALUE && this.idx != index) {
-            rustBrige.changeExample(wgpuObj, index)
-            this.idx = index
+    override fun onTouchEvent(event: MotionEvent): Boolean {
+        if (wgpuObj == Long.MAX_VALUE) {
+            return super.onTouchEvent(event)
+        }
+        val phase = when (event.actionMasked) {
+            MotionEvent.ACTION_DOWN, MotionEvent.ACTION_POINTER_DOWN -> TOUCH_PHASE_STARTED
+            MotionEvent.ACTION_MOVE -> TOUCH_PHASE_MOVED
+            MotionEvent.ACTION_UP, MotionEvent.ACTION_POINTER_UP -> TOUCH_PHASE_ENDED
+            MotionEvent.ACTION_CANCEL -> TOUCH_PHASE_CANCELLED
+            else -> return super.onTouchEvent(event)
+        }
+        // 按下与抬起事件只涉及 actionIndex 对应的那根手指，移动与取消事件涉及所有手指
+        val indices = when (event.actionMasked) {
+            MotionEvent.ACTION_MOVE, MotionEvent.ACTION_CANCEL -> 0 until event.pointerCount
+            else -> event.actionIndex..event.actionIndex
+        }
+        if (touchBuffer.capacity() < indices.count() * RustBridge.TOUCH_SIZE) {
+            touchBuffer = allocateTouchBuffer(indices.count())
+        }
+        touchBuffer.clear()
+        for (i in indices) {
+            putTouch(event, i, phase)
+        }
+        rustBrige.touches(wgpuObj, touchBuffer, indices.count())
+        return true
+    }
+
+    // 按 Rust 端 Touch 的内存布局写入一个触摸点，位置与半径转换为逻辑像素
+    private fun putTouch(event: MotionEvent, index: Int, phase: Int) {
+        val density = resources.displayMetrics.density
+        val isStylus = event.getToolType(index) == MotionEvent.TOOL_TYPE_STYLUS
+        val kind = when (event.getToolType(index)) {
+            MotionEvent.TOOL_TYPE_STYLUS -> POINTER_KIND_STYLUS
+            MotionEvent.TOOL_TYPE_MOUSE -> POINTER_KIND_MOUSE
+            else -> POINTER_KIND_FINGER
+        }
+        touchBuffer.putLong(event.getPointerId(index).toLong())
+        touchBuffer.putDouble(event.eventTime / 1000.0)
+        touchBuffer.putInt(phase)
+        touchBuffer.putInt(kind)
+        touchBuffer.putFloat(event.getX(index) / density)
+        touchBuffer.putFloat(event.getY(index) / density)
+        touchBuffer.putFloat(if (isStylus) event.getAxisValue(MotionEvent.AXIS_ORIENTATION, index) else 0f)
+        touchBuffer.putFloat(if (isStylus) event.getAxisValue(MotionEvent.AXIS_TILT, index) else 0f)
+        touchBuffer.putFloat(event.getPressure(index))
+        touchBuffer.putFloat(event.getTouchMajor(index) / 2f / density)
+        touchBuffer.putFloat(0f)
+        touchBuffer.putInt(0)
+    }
+
+    private fun allocateTouchBuffer(count: Int): ByteBuffer =
+        ByteBuffer.allocateDirect(count * RustBridge.TOUCH_SIZE).order(ByteOrder.nativeOrder())
+
+    fun changeExample(id: String) {
+        if (wgpuObj != Long.MAX_VALUE && exampleId != id && rustBrige.changeExampleById(wgpuObj, id)) {
+            exampleId = id
         }
     }
 
@@ -50,12 +50,46 @@ class ViewController: UIViewController {
         self.displayLink.isPaused = true
     }
 
+    override func viewDidLayoutSubviews() {
+        super.viewDidLayoutSubviews()
+        // 尺寸、方向或所在屏幕改变后，Rust 端会重新读取 contentScaleFactor
+        guard let canvas = self.wgpuCanvas else { return }
+        resize(canvas)
+    }
+
+    override func viewSafeAreaInsetsDidChange() {
+        super.viewSafeAreaInsetsDidChange()
+        self.pushSafeArea()
+    }
+
+    private func pushSafeArea() {
+        guard let canvas = self.wgpuCanvas else { return }
+        let insets = self.metalV.safeAreaInsets
+        set_safe_area(
+            canvas,
+            edge_insets_t(
+                top: Float(insets.top),
+                left: Float(insets.left),
+                bottom: Float(insets.bottom),
+                right: Float(insets.right)
+            )
+        )
+    }
+
     @objc private func appWillEnterForeground() {
+        if let canvas = self.wgpuCanvas {
+            // 失败时 Rust 端会发出 HOST_EVENT_ERROR
+            _ = self.withViewObj { resume_surface(canvas, $0) }
+        }
         self.displayLink.isPaused = false
     }
 
     @objc private func appDidEnterBackground() {
         self.displayLink.isPaused = true
+        // 进入后台后进程随时可能被系统终止，suspend_surface 会先写回管线缓存
+        if let canvas = self.wgpuCanvas {
+            suspend_surface(canvas)
+        }
     }
     
     @objc private func enterFrame() {
@@ -67,37 +101,120 @@ class ViewController: UIViewController {
         enter_frame(canvas)
     }
     
+    // 与 storyboard 中分段控件的顺序一致；设备不支持的示例不在 copy_examples_json 列表中，所以按 id 切换
+    private let exampleIds = ["boids", "msaa_line", "hdr_image_view", "water", "shadow"]
+
     @IBAction private func changeExample(sender: UISegmentedControl) {
         guard let canvas = self.wgpuCanvas else { return }
-        let index = sender.selectedSegmentIndex == 2 ? 5 : sender.selectedSegmentIndex
-        change_example(canvas, Int32(index))
+        let id = exampleIds[sender.selectedSegmentIndex]
+        if !change_example_by_id(canvas, id) {
+            print("Failed to change example to \(id)")
+        }
+    }
+
+    override func touchesBegan(_ touches: Set<UITouch>, with event: UIEvent?) {
+        self.forwardTouches(touches, phase: TOUCH_PHASE_STARTED)
+    }
+
+    override func touchesMoved(_ touches: Set<UITouch>, with event: UIEvent?) {
+        self.forwardTouches(touches, phase: TOUCH_PHASE_MOVED)
+    }
+
+    override func touchesEnded(_ touches: Set<UITouch>, with event: UIEvent?) {
+        self.forwardTouches(touches, phase: TOUCH_PHASE_ENDED)
+    }
+
+    override func touchesCancelled(_ touches: Set<UITouch>, with event: UIEvent?) {
+        self.forwardTouches(touches, phase: TOUCH_PHASE_CANCELLED)
+    }
+
+    private func forwardTouches(_ uiTouches: Set<UITouch>, phase: touch_phase_t) {
+        guard let canvas = self.wgpuCanvas else { return }
+        let batch = uiTouches.map { touch -> touch_t in
+            let location = touch.location(in: self.metalV)
+            let isPencil = touch.type == .pencil
+            return touch_t(
+                // UITouch 对象在触摸的整个生命周期内保持不变，以其地址作为 id
+                id: UInt64(UInt(bitPattern: ObjectIdentifier(touch).hashValue)),
+                timestamp: touch.timestamp,
+                phase: phase,
+                kind: isPencil ? POINTER_KIND_STYLUS : POINTER_KIND_FINGER,
+                x: Float(location.x),
+                y: Float(location.y),
+                azimuth: isPencil ? Float(touch.azimuthAngle(in: self.metalV)) : 0,
+                altitude: isPencil ? Float(touch.altitudeAngle) : 0,
+                pressure: Float(touch.force),
+                major_radius: Float(touch.majorRadius),
+                interval: 0
+            )
+        }
+        batch.withUnsafeBufferPointer { buffer in
+            touches(canvas, buffer.baseAddress, UInt(buffer.count))
+        }
     }
 
     private func setupWGPUCanvasIfNeeded() {
         guard self.wgpuCanvas == nil else { return }
         
+        self.wgpuCanvas = self.withViewObj { create_wgpu_canvas($0) }
+        self.registerHostEvents()
+        self.pushSafeArea()
+    }
+
+    // viewObj 中的字符串指针只在 body 执行期间有效
+    private func withViewObj<T>(_ body: (ios_view_obj_t) -> T) -> T {
         let viewPointer = Unmanaged.passUnretained(self.metalV).toOpaque()
         let metalLayer = Unmanaged.passUnretained(self.metalV.layer).toOpaque()
         let maximumFrames = Int32(UIScreen.main.maximumFramesPerSecond)
         
-        let viewObj = ios_view_obj_t(
-            view: viewPointer,
-            metal_layer: metalLayer,
-            maximum_frames: maximumFrames,
-            callback_to_swift: callback_to_swift
-        )
-        
-        self.wgpuCanvas = create_wgpu_canvas(viewObj)
+        let libraryDirectory = NSSearchPathForDirectoriesInDomains(
+            .libraryDirectory, .userDomainMask, true
+        ).first ?? ""
+
+        return libraryDirectory.withCString { libraryDirectoryPtr in
+            let viewObj = ios_view_obj_t(
+                view: viewPointer,
+                metal_layer: metalLayer,
+                maximum_frames: maximumFrames,
+                library_directory: libraryDirectoryPtr
+            )
+            return body(viewObj)
+        }
+    }
+
+    private func registerHostEvents() {
+        guard let canvas = self.wgpuCanvas else { return }
+        // 画布由 ViewController 持有，不会比 ViewController 活得更久，无需 retain
+        let userData = Unmanaged.passUnretained(self).toOpaque()
+        // 不需要逐帧的通知，帧统计通过 get_frame_stats 按需读取
+        let mask = HOST_EVENT_MASK_ALL & ~(UInt32(1) << HOST_EVENT_FRAME_RENDERED.rawValue)
+        register_host_event_callback(canvas, { event, userData in
+            guard let event = event?.pointee, let userData else { return }
+            // payload 只在回调期间有效，先复制出来
+            let payload = event.payload.map { String(cString: $0) }
+            let controller = Unmanaged<ViewController>.fromOpaque(userData).takeUnretainedValue()
+            DispatchQueue.main.async {
+                controller.handleHostEvent(event.kind, code: event.code, payload: payload)
+            }
+        }, userData, mask, nil)
     }
-}
 
-func callback_to_swift(arg: Int32) {
-    DispatchQueue.main.async {
-        switch arg {
-        case 0:
+    private func handleHostEvent(_ kind: host_event_kind_t, code: Int64, payload: String?) {
+        switch kind {
+        case HOST_EVENT_READY:
             print("wgpu canvas created!")
-        case 1:
-            print("canvas enter frame")
+        case HOST_EVENT_EXAMPLE_CHANGED:
+            print("example changed: \(code) \(payload ?? "")")
+        case HOST_EVENT_WARNING:
+            print("warning: \(payload ?? "")")
+        case HOST_EVENT_ERROR:
+            print("error: \(payload ?? "")")
+        case HOST_EVENT_DEVICE_LOST:
+            print("GPU device lost, recovering...")
+        case HOST_EVENT_DEVICE_RECOVERED:
+            print("GPU device recovered")
+        case HOST_EVENT_SURFACE_LOST:
+            print("Surface lost")
         default:
             break
         }
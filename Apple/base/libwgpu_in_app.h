@@ -7,6 +7,7 @@
 #ifndef libwgpu_in_app_h
 #define libwgpu_in_app_h
 
+#include <stdbool.h>
 #include <stdint.h>
 
 // 这个不透明结构体用来指代 Rust 端的 WgpuCanvas 对象
@@ -16,11 +17,144 @@ typedef struct {
     void *view;
     void *metal_layer;  // CAMetalLayer
     int32_t maximum_frames;
-    void (*callback_to_swift)(int32_t arg);
+    // 管线缓存等持久化数据的存放目录（UTF-8），可以为 NULL；只在 create_wgpu_canvas 期间被读取
+    const char *library_directory;
 } ios_view_obj_t;
 
+// 帧时钟的统计快照，时间单位为毫秒
+typedef struct {
+    uint64_t frame_count;
+    uint64_t dropped_frames;
+    float delta_ms;
+    float average_fps;
+    float average_frame_time_ms;
+    float p50_frame_time_ms;
+    float p95_frame_time_ms;
+    float p99_frame_time_ms;
+} frame_stats_t;
+
+// 安全区域的内边距，单位为点
+typedef struct {
+    float top;
+    float left;
+    float bottom;
+    float right;
+} edge_insets_t;
+
+typedef enum {
+    TOUCH_PHASE_STARTED = 0,
+    TOUCH_PHASE_MOVED,
+    TOUCH_PHASE_ENDED,
+    TOUCH_PHASE_CANCELLED,
+} touch_phase_t;
+
+typedef enum {
+    POINTER_KIND_FINGER = 0,
+    POINTER_KIND_STYLUS,
+    POINTER_KIND_MOUSE,
+} pointer_kind_t;
+
+// 与 Rust 端的 Touch 内存布局一致，位置与半径的单位为逻辑像素（点）
+typedef struct {
+    uint64_t id;
+    double timestamp;  // 秒
+    touch_phase_t phase;
+    pointer_kind_t kind;
+    float x;
+    float y;
+    float azimuth;  // 只在 kind 为 POINTER_KIND_STYLUS 时有效
+    float altitude;
+    float pressure;
+    float major_radius;
+    float interval;
+} touch_t;
+
+// 与 Rust 端的 ProfileEntry 内存布局一致，时间单位为毫秒
+typedef struct {
+    char label[32];  // UTF-8，以 '\0' 结尾
+    uint32_t depth;  // 顶层作用域为 0
+    float cpu_ms;
+    float gpu_ms;  // 没有 GPU 时间时为 -1
+} profile_entry_t;
+
+typedef enum {
+    HOST_EVENT_READY = 0,
+    HOST_EVENT_EXAMPLE_CHANGED,  // code 为示例在 copy_examples_json 列表中的序号，payload 为示例名称
+    HOST_EVENT_FRAME_RENDERED,   // code 为帧序号，payload 为 JSON 编码的 frame_stats_t
+    HOST_EVENT_WARNING,          // payload 为消息
+    HOST_EVENT_ERROR,            // payload 为消息
+    HOST_EVENT_DEVICE_LOST,
+    HOST_EVENT_DEVICE_RECOVERED,
+    HOST_EVENT_SURFACE_LOST,
+} host_event_kind_t;
+
+// 注册回调时 mask 中对应每类事件的位为 (1u << kind)
+#define HOST_EVENT_MASK_ALL 0xffffffffu
+
+// 与 Rust 端的 RawHostEvent 内存布局一致，只在回调期间有效
+typedef struct {
+    host_event_kind_t kind;
+    int64_t code;
+    const char *payload;  // UTF-8，以 '\0' 结尾，没有负载时为 NULL
+    uintptr_t payload_len;  // 不含末尾的 '\0'
+} host_event_t;
+
+// 在渲染线程上调用
+typedef void (*host_event_callback_t)(const host_event_t *event, void *user_data);
+// 回调被替换或画布被释放时调用，用于释放 user_data
+typedef void (*host_event_release_t)(void *user_data);
+
+typedef enum {
+    PARAMETER_TYPE_FLOAT = 0,
+    PARAMETER_TYPE_INT,
+    PARAMETER_TYPE_BOOL,
+    PARAMETER_TYPE_COLOR,
+    PARAMETER_TYPE_ENUM,
+} parameter_type_t;
+
+// 与 Rust 端的 RawParameterValue 内存布局一致
+// FLOAT 存于 float_values[0]，COLOR 为 RGBA，INT、BOOL（0 或 1）与 ENUM（选项序号）存于 int_value
+// ty 为 parameter_type_t 的取值，Rust 端会检查，未知的类型使 set_parameter 返回 false
+typedef struct {
+    int32_t ty;
+    int32_t int_value;
+    float float_values[4];
+} parameter_value_t;
+
+// 创建失败时返回 NULL
 wgpu_canvas_t* create_wgpu_canvas(ios_view_obj_t object);
 void enter_frame(wgpu_canvas_t* canvas);
+// 视图尺寸或所在屏幕的缩放因子改变后调用
+void resize(wgpu_canvas_t* canvas);
+void set_safe_area(wgpu_canvas_t* canvas, edge_insets_t insets);
+// index 为示例在 copy_examples_json 列表中的位置
 void change_example(wgpu_canvas_t* canvas, int32_t index);
+// id 为示例的标识（如 "boids"），示例未知、当前设备不支持或创建失败时返回 false
+bool change_example_by_id(wgpu_canvas_t* canvas, const char *id);
+// 写入当前设备能运行的示例列表的 JSON 描述（id、name、description），写入规则与 copy_parameters_json 相同
+uintptr_t copy_examples_json(wgpu_canvas_t* canvas, char *buffer, uintptr_t capacity);
+// 一次传入同一时刻所有变化了的触摸点
+void touches(wgpu_canvas_t* canvas, const touch_t* touches, uintptr_t count);
+// App 进入后台时调用，把管线缓存写回磁盘
+void save_pipeline_cache(wgpu_canvas_t* canvas);
+// App 进入后台时调用，写回管线缓存并释放 surface，device 与示例的状态保持不变
+void suspend_surface(wgpu_canvas_t* canvas);
+// 回到前台后用 object 中的 CAMetalLayer 重建 surface，失败时返回 false
+bool resume_surface(wgpu_canvas_t* canvas, ios_view_obj_t object);
+// 创建画布期间发出的事件（如 HOST_EVENT_READY）会在注册时补发；callback 为 NULL 时取消注册，
+// release 可以为 NULL
+void register_host_event_callback(wgpu_canvas_t* canvas, host_event_callback_t callback,
+                                  void *user_data, uint32_t mask, host_event_release_t release);
+// 写入当前示例参数的 JSON 描述（以 '\0' 结尾），返回 JSON 的全部字节数，不小于 capacity 时结果被截断
+uintptr_t copy_parameters_json(wgpu_canvas_t* canvas, char *buffer, uintptr_t capacity);
+bool get_parameter(wgpu_canvas_t* canvas, const char *name, parameter_value_t *value);
+// 数值超出取值范围时会被限制在范围内，参数不存在或类型不符时返回 false
+bool set_parameter(wgpu_canvas_t* canvas, const char *name, parameter_value_t value);
+frame_stats_t get_frame_stats(wgpu_canvas_t* canvas);
+// 在画面上叠加帧率、适配器等调试信息
+void set_hud_visible(wgpu_canvas_t* canvas, bool visible);
+void set_profiler_enabled(wgpu_canvas_t* canvas, bool enabled);
+// 按深度优先的顺序写入最近一帧的耗时树，返回全部的项数，大于 capacity 时结果被截断
+uintptr_t get_profile_entries(wgpu_canvas_t* canvas, profile_entry_t* entries, uintptr_t capacity);
 
 #endif /* libwgpu_in_app_h */
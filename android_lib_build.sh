@@ -7,12 +7,13 @@ RELEASE_MODE=${1}
 LIB_FOLDER="debug"
 
 # build to Android target
+# App 没有把 HDRImageView 的纹理打包进 assets，需要嵌入 .so
 cd wgpu-in-app
 if [ "${RELEASE_MODE}" = "--release" ]; then
     LIB_FOLDER="release"
-    cargo so b --lib --target aarch64-linux-android ${RELEASE_MODE}
+    cargo so b --lib --target aarch64-linux-android --features embed-hdr-image ${RELEASE_MODE}
 else
-    RUST_BACKTRACE=full RUST_LOG=wgpu_hal=debug cargo so b --lib --target aarch64-linux-android
+    RUST_BACKTRACE=full RUST_LOG=wgpu_hal=debug cargo so b --lib --target aarch64-linux-android --features embed-hdr-image
 fi
 
 # copy .so files to jniLibs folder
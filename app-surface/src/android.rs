@@ -1,3 +1,4 @@
+use crate::{AppSurfaceError, DeviceOptions};
 use core::ffi::c_void;
 use jni::JNIEnv;
 use jni::sys::jobject;
@@ -7,15 +8,37 @@ use raw_window_handle::{
 };
 use std::sync::{Arc, Mutex};
 
+/// `AppSurface::resume` 用来重建 surface 的原生视图，由 `NativeWindow::new` 创建
+pub type NativeHandle = NativeWindow;
+
 pub struct AppSurface {
     pub native_window: Arc<NativeWindow>,
     pub scale_factor: f32,
+    /// 安全区域的内边距，单位为逻辑像素，由宿主 App 通过 `set_safe_area` 更新
+    pub safe_area: crate::EdgeInsets,
     pub ctx: crate::IASDQContext,
-    pub callback_to_app: Option<extern "C" fn(arg: i32)>,
+    /// 发给宿主 App 的事件，由宿主 App 通过 `register` 注册接收回调
+    pub host_events: crate::HostEventSink,
+    /// 持久化数据（比如管线缓存）的存放目录，为空时不做持久化
+    pub library_directory: String,
 }
 
 impl AppSurface {
+    /// 与 [`AppSurface::try_new`] 相同，但创建失败时直接 panic
     pub fn new(env: *mut JNIEnv, surface: jobject) -> Self {
+        Self::try_new(env, surface).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_new(env: *mut JNIEnv, surface: jobject) -> Result<Self, AppSurfaceError> {
+        Self::try_new_with_options(env, surface, &DeviceOptions::default())
+    }
+
+    /// 按 `options` 协商 Device 的特性与限制
+    pub fn try_new_with_options(
+        env: *mut JNIEnv,
+        surface: jobject,
+        options: &DeviceOptions,
+    ) -> Result<Self, AppSurfaceError> {
         let native_window = Arc::new(NativeWindow::new(env, surface));
         let backends = wgpu::Backends::VULKAN;
         let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
@@ -24,24 +47,86 @@ impl AppSurface {
         });
 
         let handle: Box<dyn wgpu::WindowHandle> = Box::new(native_window.clone());
-        let surface = instance
-            .create_surface(wgpu::SurfaceTarget::Window(handle))
-            .unwrap();
+        let surface = instance.create_surface(wgpu::SurfaceTarget::Window(handle))?;
 
         let ctx = futures_lite::future::block_on(crate::create_iasdq_context(
             instance,
             surface,
             (native_window.get_width(), native_window.get_height()),
-        ));
+            options,
+        ))?;
 
-        Self {
+        Ok(Self {
             native_window,
             scale_factor: 1.0,
+            safe_area: crate::EdgeInsets::ZERO,
             ctx,
-            callback_to_app: None,
+            host_events: crate::HostEventSink::default(),
+            library_directory: String::new(),
+        })
+    }
+
+    /// 复用 `ctx` 的 instance、adapter、device 与 queue 为另一个 SurfaceView 创建 AppSurface
+    pub fn try_new_shared(
+        env: *mut JNIEnv,
+        surface: jobject,
+        ctx: &crate::IASDQContext,
+    ) -> Result<Self, AppSurfaceError> {
+        let native_window = Arc::new(NativeWindow::new(env, surface));
+        let handle: Box<dyn wgpu::WindowHandle> = Box::new(native_window.clone());
+        let surface = ctx
+            .instance
+            .create_surface(wgpu::SurfaceTarget::Window(handle))?;
+
+        let ctx = crate::create_shared_iasdq_context(
+            ctx,
+            surface,
+            (native_window.get_width(), native_window.get_height()),
+        )?;
+
+        Ok(Self {
+            native_window,
+            scale_factor: 1.0,
+            safe_area: crate::EdgeInsets::ZERO,
+            ctx,
+            host_events: crate::HostEventSink::default(),
+            library_directory: String::new(),
+        })
+    }
+
+    /// 在 `SurfaceHolder.Callback.surfaceDestroyed` 中调用
+    ///
+    /// Vulkan 交换链必须在 `surfaceDestroyed` 返回前销毁，device 与其它 GPU 资源保持不变
+    pub fn suspend(&mut self) {
+        self.ctx.suspend_surface();
+    }
+
+    /// 在 `surfaceCreated` 中用新 Surface 对应的 `native_window` 重建 surface
+    pub fn resume(&mut self, native_window: NativeHandle) -> Result<(), AppSurfaceError> {
+        if !self.ctx.is_suspended() {
+            return Ok(());
         }
+        let native_window = Arc::new(native_window);
+        let handle: Box<dyn wgpu::WindowHandle> = Box::new(native_window.clone());
+        let surface = self
+            .ctx
+            .instance
+            .create_surface(wgpu::SurfaceTarget::Window(handle))?;
+        self.ctx.resume_surface(
+            surface,
+            (native_window.get_width(), native_window.get_height()),
+        )?;
+        self.native_window = native_window;
+        Ok(())
     }
 
+    /// ANativeWindow 不提供屏幕密度，需要由宿主 App 传入 `DisplayMetrics.density`
+    pub fn set_scale_factor(&mut self, scale_factor: f32) {
+        self.scale_factor = scale_factor;
+    }
+
+    pub(crate) fn sync_scale_factor(&mut self) {}
+
     pub fn get_view_size(&self) -> (u32, u32) {
         (
             self.native_window.get_width(),
@@ -61,7 +146,8 @@ impl std::fmt::Debug for NativeWindow {
 }
 
 impl NativeWindow {
-    fn new(env: *mut JNIEnv, surface: jobject) -> Self {
+    /// 获取与 Java 端 `android.view.Surface` 关联的 ANativeWindow
+    pub fn new(env: *mut JNIEnv, surface: jobject) -> Self {
         let a_native_window = unsafe {
             // 获取与安卓端 surface 对象关联的 ANativeWindow，以便能通过 Rust 与之交互。
             // 此函数在返回 ANativeWindow 的同时会自动将其引用计数 +1，以防止该对象在安卓端被意外释放。
@@ -1,14 +1,22 @@
+use crate::{AppSurfaceError, DeviceOptions};
 use std::sync::Arc;
 use winit::window::Window;
 
+/// `AppSurface::resume` 用来重建 surface 的原生视图
+pub type NativeHandle = Arc<Window>;
+
 pub struct AppSurface {
     pub view: Option<Arc<Window>>,
     pub scale_factor: f32,
+    /// 安全区域的内边距，单位为逻辑像素，由宿主 App 通过 `set_safe_area` 更新
+    pub safe_area: crate::EdgeInsets,
     pub maximum_frames: i32,
     pub ctx: crate::IASDQContext,
-    pub callback_to_app: Option<extern "C" fn(arg: i32)>,
-    pub temporary_directory: &'static str,
-    pub library_directory: &'static str,
+    /// 发给宿主 App 的事件，由宿主 App 通过 `register` 注册接收回调
+    pub host_events: crate::HostEventSink,
+    pub temporary_directory: String,
+    /// 持久化数据（比如管线缓存）的存放目录，为空时不做持久化
+    pub library_directory: String,
 }
 
 #[derive(Default)]
@@ -19,8 +27,23 @@ struct ViewSetting {
 }
 
 impl AppSurface {
-    #[allow(clippy::needless_update)]
+    /// 与 [`AppSurface::try_new`] 相同，但创建失败时直接 panic
     pub async fn new(view: Arc<Window>) -> Self {
+        Self::try_new(view)
+            .await
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub async fn try_new(view: Arc<Window>) -> Result<Self, AppSurfaceError> {
+        Self::try_new_with_options(view, &DeviceOptions::default()).await
+    }
+
+    /// 按 `options` 协商 Device 的特性与限制
+    #[allow(clippy::needless_update)]
+    pub async fn try_new_with_options(
+        view: Arc<Window>,
+        options: &DeviceOptions,
+    ) -> Result<Self, AppSurfaceError> {
         let scale_factor = view.scale_factor() as f32;
         let mut physical_size = view.inner_size();
         physical_size.width = physical_size.width.max(1);
@@ -32,15 +55,132 @@ impl AppSurface {
             ..Default::default()
         };
 
-        Self::create(view_setting).await
+        Self::create(view_setting, options).await
+    }
+
+    /// 复用 `ctx` 的 instance、adapter、device 与 queue 为另一个窗口创建 AppSurface
+    ///
+    /// 多个窗口共享同一个 device 时，GPU 资源可以在它们之间直接共用
+    pub fn try_new_shared(
+        view: Arc<Window>,
+        ctx: &crate::IASDQContext,
+    ) -> Result<Self, AppSurfaceError> {
+        let scale_factor = view.scale_factor() as f32;
+        let physical_size = view.inner_size();
+        let surface = ctx.instance.create_surface(view.clone())?;
+        let ctx = crate::create_shared_iasdq_context(
+            ctx,
+            surface,
+            (physical_size.width.max(1), physical_size.height.max(1)),
+        )?;
+
+        Ok(AppSurface {
+            view: Some(view),
+            scale_factor,
+            safe_area: crate::EdgeInsets::ZERO,
+            maximum_frames: 60,
+            ctx,
+            host_events: crate::HostEventSink::default(),
+            temporary_directory: String::new(),
+            library_directory: String::new(),
+        })
+    }
+
+    /// 与 [`AppSurface::try_new_offscreen`] 相同，但创建失败时直接 panic
+    pub async fn new_offscreen(physical_size: (u32, u32)) -> Self {
+        Self::try_new_offscreen(physical_size)
+            .await
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// 创建不依赖窗口的离屏 AppSurface，帧缓冲是一张尺寸为 `physical_size` 的渲染目标纹理
+    ///
+    /// 可用于 CI 或服务端的缩略图生成等没有窗口（甚至没有 GPU）的环境
+    pub async fn try_new_offscreen(physical_size: (u32, u32)) -> Result<Self, AppSurfaceError> {
+        Self::try_new_offscreen_with_options(physical_size, &DeviceOptions::default()).await
+    }
+
+    pub async fn try_new_offscreen_with_options(
+        physical_size: (u32, u32),
+        options: &DeviceOptions,
+    ) -> Result<Self, AppSurfaceError> {
+        let default_backends = if cfg!(feature = "webgl") {
+            wgpu::Backends::GL
+        } else {
+            // 没有 GPU 的环境里，软件实现通常只能通过 GL 后端（如 llvmpipe）访问
+            wgpu::Backends::PRIMARY | wgpu::Backends::GL
+        };
+        let instance = Self::create_instance(options.backends, default_backends);
+        let ctx = crate::create_offscreen_iasdq_context(instance, physical_size, options).await?;
+
+        Ok(AppSurface {
+            view: None,
+            scale_factor: 1.0,
+            safe_area: crate::EdgeInsets::ZERO,
+            maximum_frames: 60,
+            ctx,
+            host_events: crate::HostEventSink::default(),
+            temporary_directory: String::new(),
+            library_directory: String::new(),
+        })
+    }
+
+    /// 收到 `ApplicationHandler::suspended` 时调用，释放 surface 但保留 device
+    pub fn suspend(&mut self) {
+        self.ctx.suspend_surface();
+    }
+
+    /// 用 `view` 重建 surface，`view` 可以是恢复后新创建的窗口
+    pub fn resume(&mut self, view: NativeHandle) -> Result<(), AppSurfaceError> {
+        if !self.ctx.is_suspended() {
+            return Ok(());
+        }
+        let surface = self.ctx.instance.create_surface(view.clone())?;
+        let physical_size = view.inner_size();
+        self.ctx
+            .resume_surface(surface, (physical_size.width, physical_size.height))?;
+        self.scale_factor = view.scale_factor() as f32;
+        self.view = Some(view);
+        Ok(())
+    }
+
+    /// 窗口移到 DPI 不同的显示器上时，由 `WindowEvent::ScaleFactorChanged` 更新
+    pub fn set_scale_factor(&mut self, scale_factor: f32) {
+        self.scale_factor = scale_factor;
+    }
+
+    /// 从窗口重新读取缩放因子，离屏模式下保持不变
+    pub(crate) fn sync_scale_factor(&mut self) {
+        if let Some(ref view) = self.view {
+            self.scale_factor = view.scale_factor() as f32;
+        }
     }
 
     pub fn get_view(&self) -> &Window {
         self.view.as_ref().unwrap()
     }
 
+    fn create_instance(
+        backends: Option<wgpu::Backends>,
+        default_backends: wgpu::Backends,
+    ) -> wgpu::Instance {
+        let backends = backends
+            .or_else(wgpu::Backends::from_env)
+            .unwrap_or(default_backends);
+        log::info!("{:?}", backends);
+        wgpu::Instance::new(wgpu::InstanceDescriptor {
+            backends,
+            flags: wgpu::InstanceFlags::default(),
+            backend_options: wgpu::BackendOptions::default(),
+            ..wgpu::InstanceDescriptor::new_without_display_handle()
+        })
+    }
+
     #[allow(unused_variables)]
-    async fn create(view_setting: ViewSetting) -> Self {
+    async fn create(
+        view_setting: ViewSetting,
+        options: &DeviceOptions,
+    ) -> Result<Self, AppSurfaceError> {
         let view = view_setting.view.unwrap();
 
         let scale_factor = view_setting.scale_factor;
@@ -49,14 +189,7 @@ impl AppSurface {
         } else {
             wgpu::Backends::PRIMARY
         };
-        log::info!("{:?}", default_backends);
-        let backends = wgpu::Backends::from_env().unwrap_or(default_backends);
-        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
-            backends,
-            flags: wgpu::InstanceFlags::default(),
-            backend_options: wgpu::BackendOptions::default(),
-            ..wgpu::InstanceDescriptor::new_without_display_handle()
-        });
+        let instance = Self::create_instance(options.backends, default_backends);
 
         cfg_if::cfg_if! {
             if #[cfg(target_arch = "wasm32")] {
@@ -65,36 +198,44 @@ impl AppSurface {
                 let surface = instance.create_surface(view.clone());
             }
         }
-        let surface = match surface {
-            Ok(surface) => surface,
-            Err(e) => {
-                panic!("Failed to create surface: {e:?}");
-            }
-        };
+        let surface = surface?;
 
-        let ctx = crate::create_iasdq_context(instance, surface, view_setting.physical_size).await;
+        let ctx =
+            crate::create_iasdq_context(instance, surface, view_setting.physical_size, options)
+                .await?;
 
-        AppSurface {
+        Ok(AppSurface {
             view: Some(view),
             scale_factor,
+            safe_area: crate::EdgeInsets::ZERO,
             maximum_frames: 60,
             ctx,
-            callback_to_app: None,
-            temporary_directory: "",
-            library_directory: "",
-        }
+            host_events: crate::HostEventSink::default(),
+            temporary_directory: String::new(),
+            library_directory: String::new(),
+        })
     }
 
     pub fn get_view_size(&self) -> (u32, u32) {
-        let physical = self.get_view().inner_size();
-        (physical.width.max(1), physical.height.max(1))
+        match self.view {
+            Some(ref view) => {
+                let physical = view.inner_size();
+                (physical.width.max(1), physical.height.max(1))
+            }
+            // 离屏模式下，渲染目标的尺寸就是视图尺寸
+            None => (self.ctx.config.width, self.ctx.config.height),
+        }
     }
 
     pub fn request_redraw(&self) {
-        self.view.as_ref().unwrap().request_redraw();
+        if let Some(ref view) = self.view {
+            view.request_redraw();
+        }
     }
 
     pub fn pre_present_notify(&self) {
-        self.view.as_ref().unwrap().pre_present_notify();
+        if let Some(ref view) = self.view {
+            view.pre_present_notify();
+        }
     }
 }
@@ -0,0 +1,156 @@
+use crate::AssetError;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// 按相对路径加载资源（着色器、纹理等）的来源
+///
+/// 路径使用 `/` 分隔，比如 `"wgsl_shader/cube.wgsl"`
+pub trait AssetSource {
+    fn load(&self, path: &str) -> Result<Cow<'_, [u8]>, AssetError>;
+
+    fn load_string(&self, path: &str) -> Result<Cow<'_, str>, AssetError> {
+        let to_utf8_error = |source| AssetError::InvalidUtf8 {
+            path: path.to_string(),
+            source,
+        };
+        match self.load(path)? {
+            Cow::Borrowed(bytes) => std::str::from_utf8(bytes)
+                .map(Cow::Borrowed)
+                .map_err(to_utf8_error),
+            Cow::Owned(bytes) => String::from_utf8(bytes)
+                .map(Cow::Owned)
+                .map_err(|err| to_utf8_error(err.utf8_error())),
+        }
+    }
+}
+
+/// 编译期嵌入到程序中的资源，通常与 `include_bytes!` 配合使用
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EmbeddedAssets {
+    files: &'static [(&'static str, &'static [u8])],
+}
+
+impl EmbeddedAssets {
+    pub const fn new(files: &'static [(&'static str, &'static [u8])]) -> Self {
+        Self { files }
+    }
+}
+
+impl AssetSource for EmbeddedAssets {
+    fn load(&self, path: &str) -> Result<Cow<'_, [u8]>, AssetError> {
+        self.files
+            .iter()
+            .find(|(name, _)| *name == path)
+            .map(|(_, bytes)| Cow::Borrowed(*bytes))
+            .ok_or_else(|| AssetError::NotFound(path.to_string()))
+    }
+}
+
+/// 运行时读取的文件系统资源，路径相对于 `root`（通常是 `library_directory`）
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Clone, Debug)]
+pub struct FileSystemAssets {
+    root: std::path::PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FileSystemAssets {
+    pub fn new(root: impl Into<std::path::PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn root(&self) -> &std::path::Path {
+        &self.root
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl AssetSource for FileSystemAssets {
+    fn load(&self, path: &str) -> Result<Cow<'_, [u8]>, AssetError> {
+        use std::path::Component;
+
+        let relative = std::path::Path::new(path);
+        let is_contained = !path.is_empty()
+            && relative
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)));
+        if !is_contained {
+            return Err(AssetError::InvalidPath(path.to_string()));
+        }
+        match std::fs::read(self.root.join(relative)) {
+            Ok(bytes) => Ok(Cow::Owned(bytes)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                Err(AssetError::NotFound(path.to_string()))
+            }
+            Err(source) => Err(AssetError::Io {
+                path: path.to_string(),
+                source,
+            }),
+        }
+    }
+}
+
+/// 保存在内存中的资源，适合宿主 App 下载或解包后直接传入，也便于测试
+#[derive(Clone, Debug, Default)]
+pub struct MemoryAssets {
+    files: HashMap<String, Vec<u8>>,
+}
+
+impl MemoryAssets {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 同一路径已存在时替换原有内容
+    pub fn insert(&mut self, path: impl Into<String>, bytes: impl Into<Vec<u8>>) {
+        self.files.insert(path.into(), bytes.into());
+    }
+
+    pub fn remove(&mut self, path: &str) -> Option<Vec<u8>> {
+        self.files.remove(path)
+    }
+}
+
+impl AssetSource for MemoryAssets {
+    fn load(&self, path: &str) -> Result<Cow<'_, [u8]>, AssetError> {
+        self.files
+            .get(path)
+            .map(|bytes| Cow::Borrowed(bytes.as_slice()))
+            .ok_or_else(|| AssetError::NotFound(path.to_string()))
+    }
+}
+
+/// 按顺序查找多个资源源，返回第一个找到的资源
+///
+/// 把文件系统放在嵌入资源之前，就可以在不重新编译的情况下更新资源
+#[derive(Default)]
+pub struct AssetChain {
+    sources: Vec<Box<dyn AssetSource>>,
+}
+
+impl AssetChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, source: impl AssetSource + 'static) -> Self {
+        self.push(source);
+        self
+    }
+
+    pub fn push(&mut self, source: impl AssetSource + 'static) {
+        self.sources.push(Box::new(source));
+    }
+}
+
+impl AssetSource for AssetChain {
+    fn load(&self, path: &str) -> Result<Cow<'_, [u8]>, AssetError> {
+        for source in &self.sources {
+            match source.load(path) {
+                Err(AssetError::NotFound(_)) => continue,
+                result => return result,
+            }
+        }
+        Err(AssetError::NotFound(path.to_string()))
+    }
+}
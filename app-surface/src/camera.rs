@@ -0,0 +1,472 @@
+use crate::{
+    Gesture, GestureRecognizer, InputEvent, Key, Modifiers, NamedKey, ParameterError,
+    ParameterInfo, ParameterValue, Touch, TouchPhase,
+};
+use glam::{Mat4, Vec2, Vec3};
+
+/// 相机的交互方式
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CameraMode {
+    /// 围绕目标点旋转，拖动改变视角，缩放改变与目标点的距离
+    #[default]
+    Orbit,
+    /// 相机位置不动，拖动转动视线，缩放与 WASD/QE 键沿视线方向移动
+    Fly,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Projection {
+    #[default]
+    Perspective,
+    /// 可见高度与透视投影在目标点处的高度相同，切换投影时画面大小不变
+    Orthographic,
+}
+
+/// 相机的姿态：目标点，加上相机相对目标点的方位角、仰角与距离
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Pose {
+    target: Vec3,
+    yaw: f32,
+    pitch: f32,
+    distance: f32,
+}
+
+impl Pose {
+    fn lerp(&self, other: &Pose, t: f32) -> Pose {
+        Pose {
+            target: self.target.lerp(other.target, t),
+            yaw: self.yaw + (other.yaw - self.yaw) * t,
+            pitch: self.pitch + (other.pitch - self.pitch) * t,
+            distance: self.distance + (other.distance - self.distance) * t,
+        }
+    }
+}
+
+/// 可由鼠标、触摸与键盘控制的相机
+///
+/// - 单指或鼠标拖动：环绕模式下旋转视角，飞行模式下转动视线
+/// - 双指拖动或按住 Shift/Ctrl 拖动鼠标：平移
+/// - 双指缩放或滚轮：环绕模式下拉近拉远，飞行模式下前后移动
+/// - 双击或 R/Home 键：回到初始位置；F 键切换模式，O 键切换投影
+///
+/// 输入只改变目标姿态，每帧调用 `update` 后当前姿态才会平滑地趋近目标姿态
+#[derive(Clone, Debug)]
+pub struct Camera {
+    pub mode: CameraMode,
+    pub projection: Projection,
+    /// 世界空间的上方向，须为坐标轴方向
+    pub up: Vec3,
+    /// 透视投影的垂直视场角，单位为弧度
+    pub fov_y: f32,
+    pub near: f32,
+    pub far: f32,
+    /// 拖动每个逻辑像素转过的弧度
+    pub rotate_speed: f32,
+    /// 滚轮每滚动一个逻辑像素的缩放指数
+    pub zoom_speed: f32,
+    /// 飞行模式下按键移动的速度，单位为每秒的世界空间距离
+    pub move_speed: f32,
+    pub min_distance: f32,
+    pub max_distance: f32,
+    /// 仰角的范围，单位为弧度，不能超出 (-π/2, π/2)
+    pub min_pitch: f32,
+    pub max_pitch: f32,
+    /// 平滑的时间常数，单位为秒；为 0 时输入立即生效
+    pub smoothing: f32,
+    home: Pose,
+    goal: Pose,
+    current: Pose,
+    /// 逻辑像素的视口尺寸，用于计算平移的距离与投影的宽高比
+    viewport: Vec2,
+    /// 视口或投影改变后，下一次 `update` 需要返回 `true`
+    dirty: bool,
+    /// 按下的指针及其上一次的位置，按按下的先后排列
+    pointers: Vec<(u64, Vec2)>,
+    modifiers: Modifiers,
+    /// 飞行模式下按住的移动键：前、后、左、右、下、上
+    move_keys: [bool; 6],
+    gestures: GestureRecognizer,
+}
+
+impl Camera {
+    /// 宿主 App 切换相机模式的参数，与 `CameraMode` 的顺序一致
+    pub const MODE_PARAMETER: ParameterInfo =
+        ParameterInfo::enumeration("camera_mode", "Camera", 0, &["Orbit", "Fly"]);
+    /// 宿主 App 切换投影的参数，与 `Projection` 的顺序一致
+    pub const PROJECTION_PARAMETER: ParameterInfo = ParameterInfo::enumeration(
+        "camera_projection",
+        "Projection",
+        0,
+        &["Perspective", "Orthographic"],
+    );
+
+    /// 位于 `eye`、看向 `target` 的透视相机，这也是重置后回到的位置
+    pub fn look_at(eye: Vec3, target: Vec3, up: Vec3) -> Self {
+        let up = up.normalize();
+        let (right, forward) = horizontal_axes(up);
+        let offset = eye - target;
+        let distance = offset.length();
+        let home = Pose {
+            target,
+            yaw: offset.dot(forward).atan2(offset.dot(right)),
+            pitch: (offset.dot(up) / distance).clamp(-1.0, 1.0).asin(),
+            distance,
+        };
+        Self {
+            mode: CameraMode::Orbit,
+            projection: Projection::Perspective,
+            up,
+            fov_y: core::f32::consts::FRAC_PI_4,
+            near: 1.0,
+            far: 100.0,
+            rotate_speed: 0.01,
+            zoom_speed: 0.005,
+            move_speed: distance,
+            min_distance: distance * 0.1,
+            max_distance: distance * 10.0,
+            min_pitch: -MAX_PITCH,
+            max_pitch: MAX_PITCH,
+            smoothing: 0.08,
+            home,
+            goal: home,
+            current: home,
+            viewport: Vec2::ONE,
+            dirty: true,
+            pointers: vec![],
+            modifiers: Modifiers::default(),
+            move_keys: [false; 6],
+            gestures: GestureRecognizer::default(),
+        }
+    }
+
+    pub fn with_depth_range(mut self, near: f32, far: f32) -> Self {
+        self.near = near;
+        self.far = far;
+        self
+    }
+
+    pub fn with_distance_range(mut self, min: f32, max: f32) -> Self {
+        self.min_distance = min;
+        self.max_distance = max;
+        self
+    }
+
+    pub fn with_pitch_range(mut self, min: f32, max: f32) -> Self {
+        self.min_pitch = min.max(-MAX_PITCH);
+        self.max_pitch = max.min(MAX_PITCH);
+        self
+    }
+
+    /// 视图尺寸改变后调用，单位为逻辑像素
+    pub fn set_viewport(&mut self, logical_size: Vec2) {
+        self.viewport = logical_size.max(Vec2::ONE);
+        self.dirty = true;
+    }
+
+    pub fn set_mode(&mut self, mode: CameraMode) {
+        self.mode = mode;
+        self.move_keys = [false; 6];
+    }
+
+    pub fn set_projection(&mut self, projection: Projection) {
+        self.projection = projection;
+        self.dirty = true;
+    }
+
+    /// 回到创建时的位置，模式与投影保持不变
+    pub fn reset(&mut self) {
+        self.goal = self.home;
+    }
+
+    /// 让当前姿态立即到达目标姿态，跳过平滑过渡
+    pub fn snap(&mut self) {
+        self.current = self.goal;
+        self.dirty = true;
+    }
+
+    /// 每帧调用一次，`dt` 为距上一帧的秒数；返回 `true` 表示矩阵有变化，需要更新 uniform
+    pub fn update(&mut self, dt: f32) -> bool {
+        self.apply_move_keys(dt);
+        let previous = self.current;
+        let t = if self.smoothing > 0.0 {
+            1.0 - (-dt.max(0.0) / self.smoothing).exp()
+        } else {
+            1.0
+        };
+        self.current = self.current.lerp(&self.goal, t);
+        // 足够接近时直接到位，避免无限逼近导致每帧都要更新 uniform
+        let close = self.current.target.distance(self.goal.target) < self.goal.distance * 1e-4
+            && (self.current.yaw - self.goal.yaw).abs() < 1e-4
+            && (self.current.pitch - self.goal.pitch).abs() < 1e-4
+            && (self.current.distance - self.goal.distance).abs() < self.goal.distance * 1e-4;
+        if close {
+            self.current = self.goal;
+        }
+        let changed = self.dirty || self.current != previous;
+        self.dirty = false;
+        changed
+    }
+
+    pub fn eye(&self) -> Vec3 {
+        pose_eye(&self.current, self.up)
+    }
+
+    pub fn target(&self) -> Vec3 {
+        self.current.target
+    }
+
+    pub fn distance(&self) -> f32 {
+        self.current.distance
+    }
+
+    pub fn aspect_ratio(&self) -> f32 {
+        self.viewport.x / self.viewport.y
+    }
+
+    pub fn view_matrix(&self) -> Mat4 {
+        Mat4::look_at_rh(self.eye(), self.current.target, self.up)
+    }
+
+    pub fn projection_matrix(&self) -> Mat4 {
+        let aspect = self.aspect_ratio();
+        match self.projection {
+            Projection::Perspective => {
+                Mat4::perspective_rh(self.fov_y, aspect, self.near, self.far)
+            }
+            Projection::Orthographic => {
+                let half_height = self.current.distance * (self.fov_y * 0.5).tan();
+                let half_width = half_height * aspect;
+                Mat4::orthographic_rh(
+                    -half_width,
+                    half_width,
+                    -half_height,
+                    half_height,
+                    self.near,
+                    self.far,
+                )
+            }
+        }
+    }
+
+    pub fn view_projection(&self) -> Mat4 {
+        self.projection_matrix() * self.view_matrix()
+    }
+
+    /// 处理输入事件，返回 `true` 表示事件被相机使用
+    pub fn handle_input(&mut self, event: &InputEvent) -> bool {
+        match event {
+            InputEvent::Pointer(touch) => self.handle_touch(touch),
+            InputEvent::Wheel { delta, .. } => {
+                self.zoom((delta.y * self.zoom_speed).exp());
+                true
+            }
+            InputEvent::Key {
+                key,
+                pressed,
+                repeat,
+                modifiers,
+            } => self.handle_key(key, *pressed, *repeat, *modifiers),
+            InputEvent::Text(_) => false,
+        }
+    }
+
+    /// 处理单个指针的触摸事件，返回 `true` 表示事件被相机使用
+    pub fn handle_touch(&mut self, touch: &Touch) -> bool {
+        for gesture in self.gestures.handle_touch(touch) {
+            if let Gesture::DoubleTap { .. } = gesture {
+                self.reset();
+            }
+        }
+        let index = self.pointers.iter().position(|(id, _)| *id == touch.id);
+        match (touch.phase, index) {
+            (TouchPhase::Started, _) => {
+                self.pointers.retain(|(id, _)| *id != touch.id);
+                self.pointers.push((touch.id, touch.position));
+            }
+            (TouchPhase::Moved, Some(index)) => {
+                let last = self.pointers[index].1;
+                self.pointers[index].1 = touch.position;
+                match (self.pointers.len(), index) {
+                    (1, _) if self.modifiers.shift || self.modifiers.control => {
+                        self.pan(touch.position - last);
+                    }
+                    (1, _) => self.rotate(touch.position - last),
+                    // 只有前两个指针参与双指手势
+                    (_, 0 | 1) => {
+                        let other = self.pointers[1 - index].1;
+                        let last_span = last.distance(other);
+                        if last_span > 0.0 {
+                            self.zoom(touch.position.distance(other) / last_span);
+                        }
+                        // 只有一个指针移动，双指中点的位移为它的一半
+                        self.pan((touch.position - last) * 0.5);
+                    }
+                    _ => {}
+                }
+            }
+            (TouchPhase::Ended | TouchPhase::Cancelled, Some(index)) => {
+                self.pointers.remove(index);
+            }
+            // 没有按下的指针（比如悬停的鼠标）
+            (_, None) => return false,
+        }
+        true
+    }
+
+    /// 环绕模式下绕目标点旋转，飞行模式下绕相机位置转动视线
+    pub fn rotate(&mut self, delta: Vec2) {
+        let eye = pose_eye(&self.goal, self.up);
+        self.goal.yaw -= delta.x * self.rotate_speed;
+        self.goal.pitch =
+            (self.goal.pitch + delta.y * self.rotate_speed).clamp(self.min_pitch, self.max_pitch);
+        if self.mode == CameraMode::Fly {
+            self.goal.target = eye - self.offset_direction(&self.goal) * self.goal.distance;
+        }
+    }
+
+    /// 在屏幕平面内平移，`delta` 为逻辑像素，目标点处的画面跟随指针移动
+    pub fn pan(&mut self, delta: Vec2) {
+        let world_per_pixel = 2.0 * self.goal.distance * (self.fov_y * 0.5).tan() / self.viewport.y;
+        let back = self.offset_direction(&self.goal);
+        let right = self.up.cross(back).normalize();
+        let screen_up = back.cross(right);
+        self.goal.target += (screen_up * delta.y - right * delta.x) * world_per_pixel;
+    }
+
+    /// `factor` 大于 1 时拉近（飞行模式下前进）
+    pub fn zoom(&mut self, factor: f32) {
+        if !(factor.is_finite() && factor > 0.0) {
+            return;
+        }
+        match self.mode {
+            CameraMode::Orbit => {
+                self.goal.distance =
+                    (self.goal.distance / factor).clamp(self.min_distance, self.max_distance);
+            }
+            CameraMode::Fly => {
+                let step = (1.0 - 1.0 / factor) * self.goal.distance;
+                self.goal.target -= self.offset_direction(&self.goal) * step;
+            }
+        }
+    }
+
+    /// 宿主 App 调节的相机参数，不是相机参数时返回 `None`
+    pub fn parameter(&self, name: &str) -> Option<ParameterValue> {
+        match name {
+            "camera_mode" => Some(ParameterValue::Enum(self.mode as u32)),
+            "camera_projection" => Some(ParameterValue::Enum(self.projection as u32)),
+            _ => None,
+        }
+    }
+
+    pub fn set_parameter(
+        &mut self,
+        name: &str,
+        value: ParameterValue,
+    ) -> Result<(), ParameterError> {
+        match (name, value) {
+            ("camera_mode", ParameterValue::Enum(mode)) => self.set_mode(if mode == 0 {
+                CameraMode::Orbit
+            } else {
+                CameraMode::Fly
+            }),
+            ("camera_projection", ParameterValue::Enum(projection)) => {
+                self.set_projection(if projection == 0 {
+                    Projection::Perspective
+                } else {
+                    Projection::Orthographic
+                })
+            }
+            _ => return Err(ParameterError::Unknown(name.to_string())),
+        }
+        Ok(())
+    }
+
+    fn handle_key(&mut self, key: &Key, pressed: bool, repeat: bool, modifiers: Modifiers) -> bool {
+        self.modifiers = modifiers;
+        // 修饰键自身的按键事件可能早于修饰键状态的更新
+        match key {
+            Key::Named(NamedKey::Shift) => self.modifiers.shift = pressed,
+            Key::Named(NamedKey::Control) => self.modifiers.control = pressed,
+            _ => {}
+        }
+        if let Some(index) = move_key_index(key) {
+            if self.mode != CameraMode::Fly {
+                return false;
+            }
+            self.move_keys[index] = pressed;
+            return true;
+        }
+        if !pressed || repeat {
+            return false;
+        }
+        match key {
+            Key::Named(NamedKey::Home) => self.reset(),
+            Key::Character(c) if c.eq_ignore_ascii_case("r") => self.reset(),
+            Key::Character(c) if c.eq_ignore_ascii_case("f") => self.set_mode(match self.mode {
+                CameraMode::Orbit => CameraMode::Fly,
+                CameraMode::Fly => CameraMode::Orbit,
+            }),
+            Key::Character(c) if c.eq_ignore_ascii_case("o") => {
+                self.set_projection(match self.projection {
+                    Projection::Perspective => Projection::Orthographic,
+                    Projection::Orthographic => Projection::Perspective,
+                })
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    fn apply_move_keys(&mut self, dt: f32) {
+        if self.mode != CameraMode::Fly || !self.move_keys.contains(&true) {
+            return;
+        }
+        let axis = |positive: usize, negative: usize| {
+            self.move_keys[positive] as i32 as f32 - self.move_keys[negative] as i32 as f32
+        };
+        let back = self.offset_direction(&self.goal);
+        let right = self.up.cross(back).normalize();
+        let direction = -back * axis(0, 1) + right * axis(3, 2) + self.up * axis(5, 4);
+        self.goal.target += direction.normalize_or_zero() * self.move_speed * dt.max(0.0);
+    }
+
+    /// 从目标点指向相机的单位向量
+    fn offset_direction(&self, pose: &Pose) -> Vec3 {
+        offset_direction(pose, self.up)
+    }
+}
+
+/// 仰角不能到达 ±π/2，否则视线与上方向平行，`look_at` 无法确定朝向
+const MAX_PITCH: f32 = core::f32::consts::FRAC_PI_2 - 0.01;
+
+/// 与上方向垂直的两条轴，方位角从第一条轴转向第二条轴时递增
+fn horizontal_axes(up: Vec3) -> (Vec3, Vec3) {
+    let right = up.any_orthonormal_vector();
+    (right, up.cross(right))
+}
+
+fn offset_direction(pose: &Pose, up: Vec3) -> Vec3 {
+    let (right, forward) = horizontal_axes(up);
+    let (sin_yaw, cos_yaw) = pose.yaw.sin_cos();
+    let (sin_pitch, cos_pitch) = pose.pitch.sin_cos();
+    (right * cos_yaw + forward * sin_yaw) * cos_pitch + up * sin_pitch
+}
+
+fn pose_eye(pose: &Pose, up: Vec3) -> Vec3 {
+    pose.target + offset_direction(pose, up) * pose.distance
+}
+
+/// 飞行模式的移动键：W/↑ 前，S/↓ 后，A/← 左，D/→ 右，Q 下，E 上
+fn move_key_index(key: &Key) -> Option<usize> {
+    match key {
+        Key::Named(NamedKey::ArrowUp) => Some(0),
+        Key::Named(NamedKey::ArrowDown) => Some(1),
+        Key::Named(NamedKey::ArrowLeft) => Some(2),
+        Key::Named(NamedKey::ArrowRight) => Some(3),
+        Key::Character(c) => ["w", "s", "a", "d", "q", "e"]
+            .iter()
+            .position(|k| c.eq_ignore_ascii_case(k)),
+        _ => None,
+    }
+}
@@ -0,0 +1,363 @@
+use crate::{CaptureError, IASDQContext, SurfaceConfigError};
+use std::sync::{Arc, Mutex};
+use wgpu::TextureFormat;
+
+/// 读回 CPU 的图像，像素为紧密排列（无行对齐填充）的 RGBA8
+///
+/// 8 位与 10 位整数格式的颜色沿用源纹理的编码，只换算通道顺序与位深；
+/// 只有浮点格式的线性颜色会编码为 sRGB
+#[derive(Clone, Debug)]
+pub struct CapturedImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl CapturedImage {
+    #[cfg(feature = "png")]
+    pub fn encode_png(&self) -> Result<Vec<u8>, CaptureError> {
+        let mut data = vec![];
+        {
+            let mut encoder = png::Encoder::new(&mut data, self.width, self.height);
+            encoder.set_color(png::ColorType::Rgba);
+            encoder.set_depth(png::BitDepth::Eight);
+            encoder.set_source_srgb(png::SrgbRenderingIntent::Perceptual);
+            let mut writer = encoder.write_header().map_err(CaptureError::Png)?;
+            writer
+                .write_image_data(&self.pixels)
+                .map_err(CaptureError::Png)?;
+        }
+        Ok(data)
+    }
+
+    #[cfg(feature = "png")]
+    pub fn save_png(&self, path: impl AsRef<std::path::Path>) -> Result<(), CaptureError> {
+        let data = self.encode_png()?;
+        std::fs::write(path, data).map_err(CaptureError::Io)
+    }
+}
+
+/// 把任意纹理的第 0 层 mip 读回 CPU
+///
+/// 纹理必须带有 `COPY_SRC` 用途，此函数会阻塞直到 GPU 完成拷贝
+pub fn capture_texture(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    texture: &wgpu::Texture,
+) -> Result<CapturedImage, CaptureError> {
+    Readback::submit(device, queue, texture)?.finish(device)
+}
+
+/// 下一帧捕获的进度，在 `FrameTexture::present` 中推进
+#[derive(Debug, Default)]
+pub(crate) enum FrameCaptureState {
+    #[default]
+    Idle,
+    Requested,
+    Captured(Result<CapturedImage, CaptureError>),
+}
+
+/// 挂在请求了捕获的那一帧上，呈现前拷贝帧缓冲
+pub(crate) struct FrameCapture {
+    pub(crate) device: wgpu::Device,
+    pub(crate) queue: wgpu::Queue,
+    pub(crate) state: Arc<Mutex<FrameCaptureState>>,
+}
+
+impl FrameCapture {
+    /// 提交拷贝命令，需在呈现之前调用
+    pub(crate) fn submit(&self, texture: &wgpu::Texture) -> Result<Readback, CaptureError> {
+        Readback::submit(&self.device, &self.queue, texture)
+    }
+
+    pub(crate) fn finish(self, readback: Result<Readback, CaptureError>) {
+        let result = readback.and_then(|readback| readback.finish(&self.device));
+        *self.state.lock().unwrap() = FrameCaptureState::Captured(result);
+    }
+}
+
+impl IASDQContext {
+    /// 帧捕获需要 surface 纹理带有 `COPY_SRC` 用途
+    ///
+    /// 离屏模式的渲染目标始终可以被捕获
+    pub fn set_capture_enabled(&mut self, enabled: bool) -> Result<(), SurfaceConfigError> {
+        let usage = if enabled {
+            if !self
+                .surface_capabilities()
+                .usages
+                .contains(wgpu::TextureUsages::COPY_SRC)
+            {
+                return Err(SurfaceConfigError::UnsupportedUsage(
+                    wgpu::TextureUsages::COPY_SRC,
+                ));
+            }
+            self.config.usage | wgpu::TextureUsages::COPY_SRC
+        } else if self.is_offscreen() {
+            self.config.usage
+        } else {
+            self.config.usage - wgpu::TextureUsages::COPY_SRC
+        };
+        if usage != self.config.usage {
+            self.config.usage = usage;
+            self.configure_surface();
+        }
+        Ok(())
+    }
+
+    /// 请求捕获下一个呈现的帧，完成后通过 `take_captured_frame` 取回
+    pub fn request_frame_capture(&self) {
+        *self.frame_capture.lock().unwrap() = FrameCaptureState::Requested;
+    }
+
+    /// 取回已完成的帧捕获结果，尚未完成时返回 `None`
+    pub fn take_captured_frame(&self) -> Option<Result<CapturedImage, CaptureError>> {
+        let mut state = self.frame_capture.lock().unwrap();
+        match core::mem::take(&mut *state) {
+            FrameCaptureState::Captured(result) => Some(result),
+            other => {
+                *state = other;
+                None
+            }
+        }
+    }
+
+    pub(crate) fn pending_frame_capture(&self) -> Option<FrameCapture> {
+        let state = self.frame_capture.lock().unwrap();
+        matches!(*state, FrameCaptureState::Requested).then(|| FrameCapture {
+            device: self.device.clone(),
+            queue: self.queue.clone(),
+            state: self.frame_capture.clone(),
+        })
+    }
+}
+
+/// 已提交的纹理到缓冲的拷贝
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+pub(crate) struct Readback {
+    buffer: wgpu::Buffer,
+    format: TextureFormat,
+    width: u32,
+    height: u32,
+    padded_bytes_per_row: u32,
+}
+
+impl Readback {
+    fn submit(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        texture: &wgpu::Texture,
+    ) -> Result<Self, CaptureError> {
+        if !texture.usage().contains(wgpu::TextureUsages::COPY_SRC) {
+            return Err(CaptureError::MissingCopySrc);
+        }
+        let format = texture.format();
+        let bytes_per_pixel = match format {
+            TextureFormat::Rgba8Unorm
+            | TextureFormat::Rgba8UnormSrgb
+            | TextureFormat::Bgra8Unorm
+            | TextureFormat::Bgra8UnormSrgb
+            | TextureFormat::Rgb10a2Unorm => 4,
+            TextureFormat::Rgba16Float => 8,
+            _ => return Err(CaptureError::UnsupportedFormat(format)),
+        };
+
+        let (width, height) = (texture.width(), texture.height());
+        // 拷贝到缓冲时每行的字节数必须按 COPY_BYTES_PER_ROW_ALIGNMENT 对齐
+        let padded_bytes_per_row =
+            (width * bytes_per_pixel).next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("capture readback buffer"),
+            size: padded_bytes_per_row as u64 * height as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("capture encoder"),
+        });
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_bytes_per_row),
+                    rows_per_image: Some(height),
+                },
+            },
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+        queue.submit(Some(encoder.finish()));
+
+        Ok(Self {
+            buffer,
+            format,
+            width,
+            height,
+            padded_bytes_per_row,
+        })
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn finish(self, _device: &wgpu::Device) -> Result<CapturedImage, CaptureError> {
+        Err(CaptureError::Unsupported)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn finish(self, device: &wgpu::Device) -> Result<CapturedImage, CaptureError> {
+        let slice = self.buffer.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            _ = sender.send(result);
+        });
+        device
+            .poll(wgpu::PollType::wait_indefinitely())
+            .map_err(CaptureError::Poll)?;
+        receiver
+            .recv()
+            .expect("map_async callback should be invoked after polling")
+            .map_err(CaptureError::BufferMap)?;
+
+        let mapped = slice.get_mapped_range();
+        let pixels = convert_rows(
+            self.format,
+            &mapped,
+            self.padded_bytes_per_row as usize,
+            self.width as usize,
+        );
+        drop(mapped);
+        self.buffer.unmap();
+
+        Ok(CapturedImage {
+            width: self.width,
+            height: self.height,
+            pixels,
+        })
+    }
+}
+
+/// 把按 `padded_bytes_per_row` 对齐的多行像素转换为紧密排列的 RGBA8
+#[cfg(not(target_arch = "wasm32"))]
+fn convert_rows(
+    format: TextureFormat,
+    data: &[u8],
+    padded_bytes_per_row: usize,
+    width: usize,
+) -> Vec<u8> {
+    let mut pixels = Vec::with_capacity(data.len() / padded_bytes_per_row * width * 4);
+    for row in data.chunks_exact(padded_bytes_per_row) {
+        convert_row(format, row, width, &mut pixels);
+    }
+    pixels
+}
+
+/// 把一行像素转换为 RGBA8 追加到 `out`，`row` 末尾可能带有对齐填充
+#[cfg(not(target_arch = "wasm32"))]
+fn convert_row(format: TextureFormat, row: &[u8], width: usize, out: &mut Vec<u8>) {
+    match format {
+        TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => {
+            out.extend_from_slice(&row[..width * 4]);
+        }
+        TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb => {
+            for bgra in row[..width * 4].chunks_exact(4) {
+                out.extend_from_slice(&[bgra[2], bgra[1], bgra[0], bgra[3]]);
+            }
+        }
+        TextureFormat::Rgb10a2Unorm => {
+            for texel in row[..width * 4].chunks_exact(4) {
+                let bits = u32::from_le_bytes([texel[0], texel[1], texel[2], texel[3]]);
+                let channel = |shift: u32| ((bits >> shift) & 0x3ff) as f32 / 1023.0;
+                out.extend_from_slice(&[
+                    unorm_to_u8(channel(0)),
+                    unorm_to_u8(channel(10)),
+                    unorm_to_u8(channel(20)),
+                    unorm_to_u8((bits >> 30) as f32 / 3.0),
+                ]);
+            }
+        }
+        TextureFormat::Rgba16Float => {
+            // 浮点帧缓冲存储的是线性颜色，需要编码为 sRGB，超出 [0, 1] 的 HDR 部分被截断
+            for texel in row[..width * 8].chunks_exact(8) {
+                let channel =
+                    |i: usize| half::f16::from_le_bytes([texel[i * 2], texel[i * 2 + 1]]).to_f32();
+                out.extend_from_slice(&[
+                    unorm_to_u8(linear_to_srgb(channel(0))),
+                    unorm_to_u8(linear_to_srgb(channel(1))),
+                    unorm_to_u8(linear_to_srgb(channel(2))),
+                    unorm_to_u8(channel(3)),
+                ]);
+            }
+        }
+        _ => unreachable!("format is checked before the copy"),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn unorm_to_u8(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn linear_to_srgb(value: f32) -> f32 {
+    let value = value.clamp(0.0, 1.0);
+    if value <= 0.003_130_8 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn padded_rows_are_trimmed() {
+        // 2x2 的 RGBA8，每行 8 字节后跟 4 字节填充
+        let data = [
+            1, 2, 3, 4, 5, 6, 7, 8, 0xee, 0xee, 0xee, 0xee, //
+            9, 10, 11, 12, 13, 14, 15, 16, 0xee, 0xee, 0xee, 0xee,
+        ];
+        let pixels = convert_rows(TextureFormat::Rgba8Unorm, &data, 12, 2);
+        assert_eq!(pixels, (1..=16).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn bgra_is_swizzled() {
+        let data = [10, 20, 30, 40, 50, 60, 70, 80];
+        let pixels = convert_rows(TextureFormat::Bgra8UnormSrgb, &data, 8, 2);
+        assert_eq!(pixels, [30, 20, 10, 40, 70, 60, 50, 80]);
+    }
+
+    #[test]
+    fn rgb10a2_is_scaled() {
+        let texel =
+            |r: u32, g: u32, b: u32, a: u32| (r | (g << 10) | (b << 20) | (a << 30)).to_le_bytes();
+        let data = [texel(1023, 0, 512, 3), texel(0, 1023, 0, 1)].concat();
+        let pixels = convert_rows(TextureFormat::Rgb10a2Unorm, &data, 8, 2);
+        assert_eq!(pixels, [255, 0, 128, 255, 0, 255, 0, 85]);
+    }
+
+    #[test]
+    fn f16_is_srgb_encoded_and_clamped() {
+        let texel = |values: [f32; 4]| {
+            values
+                .iter()
+                .flat_map(|v| half::f16::from_f32(*v).to_le_bytes())
+                .collect::<Vec<u8>>()
+        };
+        let data = [
+            texel([0.0, 0.5, 1.0, 0.5]),
+            // HDR 与负值被截断到 [0, 1]
+            texel([4.0, -1.0, 0.002, 2.0]),
+        ]
+        .concat();
+        let pixels = convert_rows(TextureFormat::Rgba16Float, &data, 16, 2);
+        // 线性 0.5 编码为 sRGB 约 0.7354，线性 0.002 落在线性段，0.002 * 12.92 * 255 ≈ 6.6
+        assert_eq!(pixels, [0, 188, 255, 128, 255, 0, 7, 255]);
+    }
+}
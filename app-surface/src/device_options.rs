@@ -0,0 +1,105 @@
+use crate::AppSurfaceError;
+
+/// 创建 Device 时的特性与限制协商选项
+///
+/// 默认不请求任何特性，限制按平台的 downlevel 默认值校验
+#[derive(Clone, Debug)]
+pub struct DeviceOptions {
+    /// 必须支持的特性，适配器缺少其中任何一项都会导致创建失败
+    pub required_features: wgpu::Features,
+    /// 可选特性，适配器不支持的部分会被静默丢弃
+    pub optional_features: wgpu::Features,
+    /// 最低限制要求，适配器达不到时创建失败
+    pub required_limits: wgpu::Limits,
+    /// 为 `true` 时在满足最低要求的前提下请求适配器所能提供的最佳限制，
+    /// 比如窗口尺寸相关的 `max_texture_dimension_2d`
+    pub prefer_adapter_limits: bool,
+    pub memory_hints: wgpu::MemoryHints,
+    pub power_preference: wgpu::PowerPreference,
+    /// 只用于 winit 后端：为 `None` 时先读取 `WGPU_BACKEND` 环境变量，再回退到平台的默认后端
+    pub backends: Option<wgpu::Backends>,
+}
+
+impl Default for DeviceOptions {
+    fn default() -> Self {
+        let required_limits = if cfg!(feature = "webgl") {
+            wgpu::Limits::downlevel_webgl2_defaults()
+        } else {
+            wgpu::Limits::downlevel_defaults()
+        };
+        Self {
+            required_features: wgpu::Features::empty(),
+            optional_features: wgpu::Features::empty(),
+            required_limits,
+            prefer_adapter_limits: true,
+            memory_hints: wgpu::MemoryHints::Performance,
+            power_preference: wgpu::PowerPreference::from_env()
+                .unwrap_or(wgpu::PowerPreference::HighPerformance),
+            backends: None,
+        }
+    }
+}
+
+impl DeviceOptions {
+    pub fn with_required_features(mut self, features: wgpu::Features) -> Self {
+        self.required_features |= features;
+        self
+    }
+
+    pub fn with_optional_features(mut self, features: wgpu::Features) -> Self {
+        self.optional_features |= features;
+        self
+    }
+
+    pub fn with_required_limits(mut self, limits: wgpu::Limits) -> Self {
+        self.required_limits = limits;
+        self
+    }
+
+    pub fn with_backends(mut self, backends: wgpu::Backends) -> Self {
+        self.backends = Some(backends);
+        self
+    }
+
+    pub fn with_memory_hints(mut self, memory_hints: wgpu::MemoryHints) -> Self {
+        self.memory_hints = memory_hints;
+        self
+    }
+
+    /// 根据适配器的能力计算最终要请求的特性与限制
+    pub fn negotiate(
+        &self,
+        adapter: &wgpu::Adapter,
+    ) -> Result<(wgpu::Features, wgpu::Limits), AppSurfaceError> {
+        let adapter_features = adapter.features();
+        let missing = self.required_features - adapter_features;
+        if !missing.is_empty() {
+            return Err(AppSurfaceError::MissingFeatures {
+                adapter_info: Box::new(adapter.get_info()),
+                missing,
+            });
+        }
+
+        let adapter_limits = adapter.limits();
+        let mut failed_limits = vec![];
+        self.required_limits.check_limits_with_fail_fn(
+            &adapter_limits,
+            false,
+            |name, required, supported| failed_limits.push((name, required, supported)),
+        );
+        if !failed_limits.is_empty() {
+            return Err(AppSurfaceError::UnsupportedLimits {
+                adapter_info: Box::new(adapter.get_info()),
+                failed_limits,
+            });
+        }
+
+        let features = self.required_features | (self.optional_features & adapter_features);
+        let limits = if self.prefer_adapter_limits {
+            adapter_limits
+        } else {
+            self.required_limits.clone()
+        };
+        Ok((features, limits))
+    }
+}
@@ -0,0 +1,282 @@
+use crate::{ParameterType, ParameterValue};
+use core::fmt;
+
+/// 创建 AppSurface 过程中可能出现的错误
+///
+/// `AdapterInfo` 体积较大，装箱以免 `Result<AppSurface, AppSurfaceError>` 过度膨胀
+#[derive(Debug)]
+pub enum AppSurfaceError {
+    /// 找不到满足条件的适配器
+    Adapter(wgpu::RequestAdapterError),
+    /// 适配器创建 Device 失败
+    Device {
+        adapter_info: Box<wgpu::AdapterInfo>,
+        source: wgpu::RequestDeviceError,
+    },
+    /// 适配器缺少 `DeviceOptions::required_features` 中的特性
+    MissingFeatures {
+        adapter_info: Box<wgpu::AdapterInfo>,
+        missing: wgpu::Features,
+    },
+    /// 适配器达不到 `DeviceOptions::required_limits`，元素为（限制名, 要求值, 支持值）
+    UnsupportedLimits {
+        adapter_info: Box<wgpu::AdapterInfo>,
+        failed_limits: Vec<(&'static str, u64, u64)>,
+    },
+    /// 无法用原生视图/图层创建 `wgpu::Surface`
+    SurfaceCreation(wgpu::CreateSurfaceError),
+    /// Surface 与所选适配器不兼容，拿不到可用的格式与配置
+    UnsupportedSurface {
+        adapter_info: Box<wgpu::AdapterInfo>,
+    },
+}
+
+impl fmt::Display for AppSurfaceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppSurfaceError::Adapter(err) => {
+                write!(f, "No suitable GPU adapters found on the system: {err}")
+            }
+            AppSurfaceError::Device {
+                adapter_info,
+                source,
+            } => write!(
+                f,
+                "request_device failed on {} ({:?}): {source}",
+                adapter_info.name, adapter_info.backend
+            ),
+            AppSurfaceError::MissingFeatures {
+                adapter_info,
+                missing,
+            } => write!(
+                f,
+                "{} ({:?}) is missing required features: {missing:?}",
+                adapter_info.name, adapter_info.backend
+            ),
+            AppSurfaceError::UnsupportedLimits {
+                adapter_info,
+                failed_limits,
+            } => {
+                write!(
+                    f,
+                    "{} ({:?}) doesn't meet the required limits:",
+                    adapter_info.name, adapter_info.backend
+                )?;
+                for (name, required, supported) in failed_limits {
+                    write!(f, " {name} (required {required}, supported {supported})")?;
+                }
+                Ok(())
+            }
+            AppSurfaceError::SurfaceCreation(err) => write!(f, "Failed to create surface: {err}"),
+            AppSurfaceError::UnsupportedSurface { adapter_info } => write!(
+                f,
+                "Surface isn't supported by the adapter {} ({:?})",
+                adapter_info.name, adapter_info.backend
+            ),
+        }
+    }
+}
+
+impl std::error::Error for AppSurfaceError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AppSurfaceError::Adapter(err) => Some(err),
+            AppSurfaceError::Device { source, .. } => Some(source),
+            AppSurfaceError::SurfaceCreation(err) => Some(err),
+            AppSurfaceError::MissingFeatures { .. }
+            | AppSurfaceError::UnsupportedLimits { .. }
+            | AppSurfaceError::UnsupportedSurface { .. } => None,
+        }
+    }
+}
+
+impl From<wgpu::CreateSurfaceError> for AppSurfaceError {
+    fn from(err: wgpu::CreateSurfaceError) -> Self {
+        AppSurfaceError::SurfaceCreation(err)
+    }
+}
+
+/// 运行时修改 surface 配置时，请求的参数不被 surface 支持
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SurfaceConfigError {
+    UnsupportedPresentMode {
+        requested: wgpu::PresentMode,
+        supported: Vec<wgpu::PresentMode>,
+    },
+    UnsupportedAlphaMode {
+        requested: wgpu::CompositeAlphaMode,
+        supported: Vec<wgpu::CompositeAlphaMode>,
+    },
+    /// `desired_maximum_frame_latency` 至少为 1
+    InvalidFrameLatency(u32),
+    /// surface 不支持的纹理用途，比如开启帧捕获所需的 `COPY_SRC`
+    UnsupportedUsage(wgpu::TextureUsages),
+}
+
+impl fmt::Display for SurfaceConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SurfaceConfigError::UnsupportedPresentMode {
+                requested,
+                supported,
+            } => write!(
+                f,
+                "Present mode {requested:?} isn't supported by the surface, supported: {supported:?}"
+            ),
+            SurfaceConfigError::UnsupportedAlphaMode {
+                requested,
+                supported,
+            } => write!(
+                f,
+                "Alpha mode {requested:?} isn't supported by the surface, supported: {supported:?}"
+            ),
+            SurfaceConfigError::InvalidFrameLatency(latency) => {
+                write!(f, "Invalid desired maximum frame latency: {latency}")
+            }
+            SurfaceConfigError::UnsupportedUsage(usage) => {
+                write!(f, "Texture usage {usage:?} isn't supported by the surface")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SurfaceConfigError {}
+
+/// 把帧缓冲或纹理读回 CPU 时可能出现的错误
+#[derive(Debug)]
+pub enum CaptureError {
+    /// 纹理没有 `COPY_SRC` 用途，surface 需先调用 `set_capture_enabled(true)`
+    MissingCopySrc,
+    /// 无法转换为 RGBA8 的纹理格式
+    UnsupportedFormat(wgpu::TextureFormat),
+    Poll(wgpu::PollError),
+    BufferMap(wgpu::BufferAsyncError),
+    /// WebGPU 上无法同步等待缓冲映射
+    Unsupported,
+    #[cfg(feature = "png")]
+    Png(png::EncodingError),
+    Io(std::io::Error),
+}
+
+impl fmt::Display for CaptureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CaptureError::MissingCopySrc => {
+                write!(f, "The texture to capture doesn't have COPY_SRC usage")
+            }
+            CaptureError::UnsupportedFormat(format) => {
+                write!(f, "Capturing {format:?} textures isn't supported")
+            }
+            CaptureError::Poll(err) => write!(f, "Failed to wait for the copy: {err}"),
+            CaptureError::BufferMap(err) => write!(f, "Failed to map the readback buffer: {err}"),
+            CaptureError::Unsupported => {
+                write!(
+                    f,
+                    "Synchronous frame capture isn't supported on this platform"
+                )
+            }
+            #[cfg(feature = "png")]
+            CaptureError::Png(err) => write!(f, "Failed to encode PNG: {err}"),
+            CaptureError::Io(err) => write!(f, "Failed to write the captured image: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for CaptureError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CaptureError::Poll(err) => Some(err),
+            CaptureError::BufferMap(err) => Some(err),
+            #[cfg(feature = "png")]
+            CaptureError::Png(err) => Some(err),
+            CaptureError::Io(err) => Some(err),
+            CaptureError::MissingCopySrc
+            | CaptureError::UnsupportedFormat(_)
+            | CaptureError::Unsupported => None,
+        }
+    }
+}
+
+/// 通过 `AssetSource` 加载资源时可能出现的错误
+#[derive(Debug)]
+pub enum AssetError {
+    /// 所有资源源中都没有此路径
+    NotFound(String),
+    /// 路径为空、是绝对路径或包含 `..`，不能离开资源源的根目录
+    InvalidPath(String),
+    Io {
+        path: String,
+        source: std::io::Error,
+    },
+    /// 作为文本（比如 WGSL）加载的资源不是合法的 UTF-8
+    InvalidUtf8 {
+        path: String,
+        source: std::str::Utf8Error,
+    },
+    /// 资源内容不符合使用方的要求，比如纹理数据的长度不对
+    InvalidData { path: String, reason: String },
+}
+
+impl fmt::Display for AssetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssetError::NotFound(path) => write!(f, "Asset not found: {path}"),
+            AssetError::InvalidPath(path) => write!(f, "Invalid asset path: {path:?}"),
+            AssetError::Io { path, source } => write!(f, "Failed to read asset {path}: {source}"),
+            AssetError::InvalidUtf8 { path, source } => {
+                write!(f, "Asset {path} isn't valid UTF-8: {source}")
+            }
+            AssetError::InvalidData { path, reason } => {
+                write!(f, "Asset {path} is invalid: {reason}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for AssetError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AssetError::Io { source, .. } => Some(source),
+            AssetError::InvalidUtf8 { source, .. } => Some(source),
+            AssetError::NotFound(_)
+            | AssetError::InvalidPath(_)
+            | AssetError::InvalidData { .. } => None,
+        }
+    }
+}
+
+/// 宿主 App 修改示例参数时可能出现的错误
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParameterError {
+    /// 当前示例没有此参数
+    Unknown(String),
+    /// 值的类型与参数声明的类型不符
+    TypeMismatch {
+        name: String,
+        expected: ParameterType,
+        found: ParameterType,
+    },
+    /// 枚举的序号超出选项个数，或浮点数为 NaN
+    InvalidValue { name: String, value: ParameterValue },
+}
+
+impl fmt::Display for ParameterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParameterError::Unknown(name) => write!(f, "Unknown parameter: {name}"),
+            ParameterError::TypeMismatch {
+                name,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Parameter {name} expects a {expected:?} value, found {found:?}"
+            ),
+            ParameterError::InvalidValue { name, value } => {
+                write!(f, "Invalid value for parameter {name}: {value:?}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParameterError {}
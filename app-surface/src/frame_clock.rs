@@ -0,0 +1,184 @@
+use std::collections::VecDeque;
+
+/// 参与滚动平均与百分位统计的最近帧数
+const SAMPLE_COUNT: usize = 120;
+
+/// 帧间隔超过目标间隔的倍数时，视为出现了掉帧
+const DROPPED_FRAME_THRESHOLD: f32 = 1.5;
+
+/// 帧时钟
+///
+/// 每帧开始时调用一次 `tick`，记录帧间隔并统计帧率、帧时间百分位与掉帧数
+#[derive(Clone, Debug)]
+pub struct FrameClock {
+    #[cfg(not(target_arch = "wasm32"))]
+    start: std::time::Instant,
+    /// 上一帧的时间戳，单位为毫秒
+    last_frame: Option<f64>,
+    delta_ms: f32,
+    elapsed_ms: f64,
+    frame_count: u64,
+    dropped_frames: u64,
+    target_frame_time_ms: f32,
+    /// 最近 `SAMPLE_COUNT` 帧的帧时间，单位为毫秒
+    samples: VecDeque<f32>,
+}
+
+/// 帧时钟的统计快照，可直接传递给宿主 App
+///
+/// 时间单位均为毫秒
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FrameStats {
+    pub frame_count: u64,
+    pub dropped_frames: u64,
+    pub delta_ms: f32,
+    pub average_fps: f32,
+    pub average_frame_time_ms: f32,
+    pub p50_frame_time_ms: f32,
+    pub p95_frame_time_ms: f32,
+    pub p99_frame_time_ms: f32,
+}
+
+impl Default for FrameClock {
+    fn default() -> Self {
+        Self::new(60)
+    }
+}
+
+impl FrameClock {
+    /// `target_fps` 为屏幕的最大刷新率，用于判断是否掉帧
+    pub fn new(target_fps: i32) -> Self {
+        Self {
+            #[cfg(not(target_arch = "wasm32"))]
+            start: std::time::Instant::now(),
+            last_frame: None,
+            delta_ms: 0.0,
+            elapsed_ms: 0.0,
+            frame_count: 0,
+            dropped_frames: 0,
+            target_frame_time_ms: 1000.0 / target_fps.max(1) as f32,
+            samples: VecDeque::with_capacity(SAMPLE_COUNT),
+        }
+    }
+
+    pub fn set_target_fps(&mut self, target_fps: i32) {
+        self.target_frame_time_ms = 1000.0 / target_fps.max(1) as f32;
+    }
+
+    /// 标记新的一帧开始
+    pub fn tick(&mut self) {
+        let now = self.now_ms();
+        self.tick_at(now);
+    }
+
+    /// 以 `now` 为时间戳标记新的一帧开始，单位为毫秒，须单调递增
+    ///
+    /// `tick` 用当前时间调用此函数；测试或回放时可以传入自己的时间戳
+    pub fn tick_at(&mut self, now: f64) {
+        self.frame_count += 1;
+        let Some(last_frame) = self.last_frame.replace(now) else {
+            return;
+        };
+
+        self.delta_ms = (now - last_frame) as f32;
+        self.elapsed_ms += self.delta_ms as f64;
+        if self.samples.len() == SAMPLE_COUNT {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(self.delta_ms);
+
+        if self.delta_ms > self.target_frame_time_ms * DROPPED_FRAME_THRESHOLD {
+            self.dropped_frames += (self.delta_ms / self.target_frame_time_ms).round() as u64 - 1;
+        }
+    }
+
+    /// App 从后台恢复或暂停渲染后，避免把这段间隔计入帧时间
+    pub fn reset_delta(&mut self) {
+        self.last_frame = None;
+        self.delta_ms = 0.0;
+    }
+
+    /// 与上一帧的间隔，单位为秒
+    pub fn delta_seconds(&self) -> f32 {
+        self.delta_ms / 1000.0
+    }
+
+    /// 累计的帧间隔之和（不含被 `reset_delta` 跳过的时间），单位为秒
+    pub fn elapsed_seconds(&self) -> f32 {
+        (self.elapsed_ms / 1000.0) as f32
+    }
+
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    pub fn dropped_frames(&self) -> u64 {
+        self.dropped_frames
+    }
+
+    pub fn average_frame_time_ms(&self) -> f32 {
+        if self.samples.is_empty() {
+            return 0.0;
+        }
+        self.samples.iter().sum::<f32>() / self.samples.len() as f32
+    }
+
+    pub fn average_fps(&self) -> f32 {
+        let frame_time = self.average_frame_time_ms();
+        if frame_time > 0.0 {
+            1000.0 / frame_time
+        } else {
+            0.0
+        }
+    }
+
+    /// 最近若干帧中帧时间的百分位值，`percentile` 的取值范围为 [0, 100]
+    pub fn frame_time_percentile(&self, percentile: f32) -> f32 {
+        let mut sorted: Vec<f32> = self.samples.iter().copied().collect();
+        sorted.sort_by(f32::total_cmp);
+        percentile_of_sorted(&sorted, percentile)
+    }
+
+    /// 最近若干帧的帧时间，按时间先后排列
+    pub fn samples(&self) -> impl Iterator<Item = f32> + '_ {
+        self.samples.iter().copied()
+    }
+
+    pub fn stats(&self) -> FrameStats {
+        let mut sorted: Vec<f32> = self.samples.iter().copied().collect();
+        sorted.sort_by(f32::total_cmp);
+        FrameStats {
+            frame_count: self.frame_count,
+            dropped_frames: self.dropped_frames,
+            delta_ms: self.delta_ms,
+            average_fps: self.average_fps(),
+            average_frame_time_ms: self.average_frame_time_ms(),
+            p50_frame_time_ms: percentile_of_sorted(&sorted, 50.0),
+            p95_frame_time_ms: percentile_of_sorted(&sorted, 95.0),
+            p99_frame_time_ms: percentile_of_sorted(&sorted, 99.0),
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn now_ms(&self) -> f64 {
+        self.start.elapsed().as_secs_f64() * 1000.0
+    }
+
+    // wasm32-unknown-unknown 上没有 std::time::Instant 的实现
+    #[cfg(target_arch = "wasm32")]
+    fn now_ms(&self) -> f64 {
+        web_sys::window()
+            .and_then(|window| window.performance())
+            .map(|performance| performance.now())
+            .unwrap_or(0.0)
+    }
+}
+
+fn percentile_of_sorted(sorted: &[f32], percentile: f32) -> f32 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = (percentile.clamp(0.0, 100.0) / 100.0 * (sorted.len() - 1) as f32).round();
+    sorted[rank as usize]
+}
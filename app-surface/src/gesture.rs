@@ -0,0 +1,437 @@
+use crate::{Touch, TouchPhase};
+use core::f32::consts::PI;
+use glam::Vec2;
+
+/// 手势识别的阈值，距离单位与 `Touch::position` 一致，时间单位为秒
+#[derive(Clone, Copy, Debug)]
+pub struct GestureConfig {
+    /// 按下到抬起不超过此时长才算点击
+    pub tap_max_duration: f32,
+    /// 点击与长按允许的最大移动距离
+    pub tap_slop: f32,
+    /// 两次点击的最大间隔
+    pub double_tap_interval: f32,
+    /// 两次点击位置的最大距离
+    pub double_tap_slop: f32,
+    /// 按住不动超过此时长触发长按
+    pub long_press_duration: f32,
+    /// 移动超过此距离开始拖动
+    pub pan_threshold: f32,
+    /// 两指间距的变化超过此距离开始缩放
+    pub pinch_threshold: f32,
+    /// 两指连线转过的角度超过此弧度开始旋转
+    pub rotation_threshold: f32,
+}
+
+impl Default for GestureConfig {
+    fn default() -> Self {
+        Self {
+            tap_max_duration: 0.3,
+            tap_slop: 10.0,
+            double_tap_interval: 0.3,
+            double_tap_slop: 40.0,
+            long_press_duration: 0.5,
+            pan_threshold: 10.0,
+            pinch_threshold: 8.0,
+            rotation_threshold: 0.1,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GesturePhase {
+    Began,
+    Changed,
+    Ended,
+    Cancelled,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Gesture {
+    Tap {
+        position: Vec2,
+    },
+    DoubleTap {
+        position: Vec2,
+    },
+    LongPress {
+        position: Vec2,
+    },
+    /// `delta` 为与上一次拖动事件的位移，`velocity` 为每秒的位移
+    Pan {
+        phase: GesturePhase,
+        position: Vec2,
+        delta: Vec2,
+        velocity: Vec2,
+    },
+    /// `scale` 为相对于手势开始时两指间距的缩放比，`delta_scale` 为相对于上一次事件的缩放比
+    Pinch {
+        phase: GesturePhase,
+        focal_point: Vec2,
+        scale: f32,
+        delta_scale: f32,
+    },
+    /// 顺时针为正（屏幕坐标 y 轴向下），单位为弧度
+    Rotate {
+        phase: GesturePhase,
+        focal_point: Vec2,
+        rotation: f32,
+        delta_rotation: f32,
+    },
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Pointer {
+    id: u64,
+    position: Vec2,
+}
+
+/// 单指按下后的状态，用于识别点击、长按与拖动
+#[derive(Clone, Copy, Debug)]
+struct SingleTouch {
+    start_position: Vec2,
+    start_time: f64,
+    last_time: f64,
+    velocity: Vec2,
+    panning: bool,
+    long_pressed: bool,
+    /// 期间出现过第二根手指时，抬起后不再算作点击
+    tap_cancelled: bool,
+}
+
+/// 双指按下后的状态，用于识别缩放与旋转
+#[derive(Clone, Copy, Debug)]
+struct TwoTouch {
+    start_distance: f32,
+    last_distance: f32,
+    start_angle: f32,
+    last_angle: f32,
+    pinching: bool,
+    rotating: bool,
+}
+
+/// 把 `Touch` 事件流识别为点击、双击、长按、拖动、缩放与旋转手势
+///
+/// 每个触摸事件都需要附带手指的 id 与时间戳；长按依赖计时，需每帧调用 `update`
+#[derive(Clone, Debug, Default)]
+pub struct GestureRecognizer {
+    pub config: GestureConfig,
+    pointers: Vec<Pointer>,
+    single: Option<SingleTouch>,
+    two: Option<TwoTouch>,
+    /// 上一次点击的位置与时间，用于识别双击
+    last_tap: Option<(Vec2, f64)>,
+}
+
+impl GestureRecognizer {
+    pub fn new(config: GestureConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// 以 `Touch::id` 区分手指，以 `Touch::timestamp` 计时
+    pub fn handle_touch(&mut self, touch: &Touch) -> Vec<Gesture> {
+        let (id, time) = (touch.id, touch.timestamp);
+        let mut gestures = vec![];
+        match touch.phase {
+            TouchPhase::Started => self.touch_started(id, touch.position, time, &mut gestures),
+            TouchPhase::Moved => self.touch_moved(id, touch.position, time, &mut gestures),
+            TouchPhase::Ended => self.touch_ended(id, touch.position, time, &mut gestures),
+            TouchPhase::Cancelled => self.cancel(&mut gestures),
+        }
+        gestures
+    }
+
+    /// 检查是否到了长按的时间，`time` 与 `Touch::timestamp` 使用同一时间基准
+    pub fn update(&mut self, time: f64) -> Option<Gesture> {
+        let single = self.single.as_mut()?;
+        if single.panning
+            || single.long_pressed
+            || single.tap_cancelled
+            || ((time - single.start_time) as f32) < self.config.long_press_duration
+        {
+            return None;
+        }
+        single.long_pressed = true;
+        Some(Gesture::LongPress {
+            position: self.pointers[0].position,
+        })
+    }
+
+    /// 取消正在进行的所有手势，比如 App 进入后台时
+    pub fn cancel(&mut self, gestures: &mut Vec<Gesture>) {
+        if let Some(single) = self.single.take()
+            && single.panning
+        {
+            gestures.push(Gesture::Pan {
+                phase: GesturePhase::Cancelled,
+                position: self.pointers[0].position,
+                delta: Vec2::ZERO,
+                velocity: Vec2::ZERO,
+            });
+        }
+        self.end_two_touch(GesturePhase::Cancelled, gestures);
+        self.pointers.clear();
+        self.last_tap = None;
+    }
+
+    fn touch_started(&mut self, id: u64, position: Vec2, time: f64, gestures: &mut Vec<Gesture>) {
+        self.pointers.retain(|p| p.id != id);
+        self.pointers.push(Pointer { id, position });
+
+        match self.pointers.len() {
+            1 => {
+                self.single = Some(SingleTouch {
+                    start_position: position,
+                    start_time: time,
+                    last_time: time,
+                    velocity: Vec2::ZERO,
+                    panning: false,
+                    long_pressed: false,
+                    tap_cancelled: false,
+                });
+            }
+            2 => {
+                if let Some(single) = self.single.as_mut() {
+                    if single.panning {
+                        gestures.push(Gesture::Pan {
+                            phase: GesturePhase::Ended,
+                            position: self.pointers[0].position,
+                            delta: Vec2::ZERO,
+                            velocity: single.velocity,
+                        });
+                    }
+                    single.panning = false;
+                    single.tap_cancelled = true;
+                }
+                let (distance, angle, _) = self.two_touch_metrics();
+                self.two = Some(TwoTouch {
+                    start_distance: distance,
+                    last_distance: distance,
+                    start_angle: angle,
+                    last_angle: angle,
+                    pinching: false,
+                    rotating: false,
+                });
+            }
+            // 超过两根手指时只跟踪前两根
+            _ => {}
+        }
+    }
+
+    fn touch_moved(&mut self, id: u64, position: Vec2, time: f64, gestures: &mut Vec<Gesture>) {
+        let Some(index) = self.pointers.iter().position(|p| p.id == id) else {
+            return;
+        };
+        let last_position = self.pointers[index].position;
+        self.pointers[index].position = position;
+
+        if self.pointers.len() == 1 {
+            self.single_moved(last_position, position, time, gestures);
+        } else if index < 2 {
+            self.two_moved(gestures);
+        }
+    }
+
+    fn single_moved(
+        &mut self,
+        last_position: Vec2,
+        position: Vec2,
+        time: f64,
+        gestures: &mut Vec<Gesture>,
+    ) {
+        let Some(single) = self.single.as_mut() else {
+            return;
+        };
+        let dt = (time - single.last_time) as f32;
+        single.last_time = time;
+        let delta = position - last_position;
+        if dt > 0.0 {
+            // 平滑瞬时速度，避免单个事件的抖动
+            single.velocity = single.velocity.lerp(delta / dt, 0.5);
+        }
+
+        if single.panning {
+            gestures.push(Gesture::Pan {
+                phase: GesturePhase::Changed,
+                position,
+                delta,
+                velocity: single.velocity,
+            });
+        } else if position.distance(single.start_position) > self.config.pan_threshold {
+            single.panning = true;
+            gestures.push(Gesture::Pan {
+                phase: GesturePhase::Began,
+                position,
+                delta: position - single.start_position,
+                velocity: single.velocity,
+            });
+        }
+    }
+
+    fn two_moved(&mut self, gestures: &mut Vec<Gesture>) {
+        let (distance, angle, focal_point) = self.two_touch_metrics();
+        let config = self.config;
+        let Some(two) = self.two.as_mut() else {
+            return;
+        };
+
+        if !two.pinching && (distance - two.start_distance).abs() > config.pinch_threshold {
+            two.pinching = true;
+            two.last_distance = two.start_distance;
+            gestures.push(Gesture::Pinch {
+                phase: GesturePhase::Began,
+                focal_point,
+                scale: 1.0,
+                delta_scale: 1.0,
+            });
+        }
+        if two.pinching && two.start_distance > 0.0 && two.last_distance > 0.0 {
+            gestures.push(Gesture::Pinch {
+                phase: GesturePhase::Changed,
+                focal_point,
+                scale: distance / two.start_distance,
+                delta_scale: distance / two.last_distance,
+            });
+        }
+        two.last_distance = distance;
+
+        if !two.rotating
+            && normalize_angle(angle - two.start_angle).abs() > config.rotation_threshold
+        {
+            two.rotating = true;
+            two.last_angle = two.start_angle;
+            gestures.push(Gesture::Rotate {
+                phase: GesturePhase::Began,
+                focal_point,
+                rotation: 0.0,
+                delta_rotation: 0.0,
+            });
+        }
+        if two.rotating {
+            gestures.push(Gesture::Rotate {
+                phase: GesturePhase::Changed,
+                focal_point,
+                rotation: normalize_angle(angle - two.start_angle),
+                delta_rotation: normalize_angle(angle - two.last_angle),
+            });
+        }
+        two.last_angle = angle;
+    }
+
+    fn touch_ended(&mut self, id: u64, position: Vec2, time: f64, gestures: &mut Vec<Gesture>) {
+        let Some(index) = self.pointers.iter().position(|p| p.id == id) else {
+            return;
+        };
+        self.pointers[index].position = position;
+
+        if self.pointers.len() == 1 {
+            self.single_ended(position, time, gestures);
+        } else if index < 2 {
+            self.end_two_touch(GesturePhase::Ended, gestures);
+        }
+        self.pointers.remove(index);
+
+        // 双指变单指时，以剩下手指的当前位置重新开始拖动识别
+        if self.pointers.len() == 1
+            && let Some(single) = self.single.as_mut()
+        {
+            single.start_position = self.pointers[0].position;
+            single.last_time = time;
+            single.velocity = Vec2::ZERO;
+        } else if self.pointers.len() >= 2 && index < 2 {
+            let (distance, angle, _) = self.two_touch_metrics();
+            self.two = Some(TwoTouch {
+                start_distance: distance,
+                last_distance: distance,
+                start_angle: angle,
+                last_angle: angle,
+                pinching: false,
+                rotating: false,
+            });
+        }
+    }
+
+    fn single_ended(&mut self, position: Vec2, time: f64, gestures: &mut Vec<Gesture>) {
+        let Some(single) = self.single.take() else {
+            return;
+        };
+        if single.panning {
+            gestures.push(Gesture::Pan {
+                phase: GesturePhase::Ended,
+                position,
+                delta: Vec2::ZERO,
+                velocity: single.velocity,
+            });
+            return;
+        }
+        let is_tap = !single.long_pressed
+            && !single.tap_cancelled
+            && (time - single.start_time) as f32 <= self.config.tap_max_duration
+            && position.distance(single.start_position) <= self.config.tap_slop;
+        if !is_tap {
+            return;
+        }
+
+        match self.last_tap.take() {
+            Some((last_position, last_time))
+                if (time - last_time) as f32 <= self.config.double_tap_interval
+                    && position.distance(last_position) <= self.config.double_tap_slop =>
+            {
+                gestures.push(Gesture::DoubleTap { position });
+            }
+            _ => {
+                gestures.push(Gesture::Tap { position });
+                self.last_tap = Some((position, time));
+            }
+        }
+    }
+
+    fn end_two_touch(&mut self, phase: GesturePhase, gestures: &mut Vec<Gesture>) {
+        let Some(two) = self.two.take() else {
+            return;
+        };
+        let focal_point = if self.pointers.len() >= 2 {
+            self.two_touch_metrics().2
+        } else {
+            Vec2::ZERO
+        };
+        if two.pinching {
+            gestures.push(Gesture::Pinch {
+                phase,
+                focal_point,
+                scale: two.last_distance / two.start_distance.max(f32::EPSILON),
+                delta_scale: 1.0,
+            });
+        }
+        if two.rotating {
+            gestures.push(Gesture::Rotate {
+                phase,
+                focal_point,
+                rotation: normalize_angle(two.last_angle - two.start_angle),
+                delta_rotation: 0.0,
+            });
+        }
+    }
+
+    /// 前两根手指的（间距, 连线角度, 中点）
+    fn two_touch_metrics(&self) -> (f32, f32, Vec2) {
+        let (p0, p1) = (self.pointers[0].position, self.pointers[1].position);
+        let v = p1 - p0;
+        (v.length(), v.y.atan2(v.x), (p0 + p1) * 0.5)
+    }
+}
+
+/// 把角度差规范到 (-π, π]
+fn normalize_angle(angle: f32) -> f32 {
+    let mut angle = angle % (2.0 * PI);
+    if angle > PI {
+        angle -= 2.0 * PI;
+    } else if angle <= -PI {
+        angle += 2.0 * PI;
+    }
+    angle
+}
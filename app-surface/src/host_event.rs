@@ -0,0 +1,239 @@
+use crate::FrameStats;
+use core::ffi::{c_char, c_void};
+use std::collections::VecDeque;
+use std::ffi::CString;
+use std::fmt::Write;
+
+/// 注册回调之前最多缓存的事件数，超出后丢弃最早的事件
+const MAX_PENDING_EVENTS: usize = 32;
+
+/// 接收所有类型的事件
+pub const HOST_EVENT_MASK_ALL: u32 = u32::MAX;
+
+/// 发给宿主 App 的事件
+#[derive(Clone, Debug)]
+pub enum HostEvent {
+    /// 画布与首个示例已创建完成
+    Ready,
+    /// 切换了示例，`index` 为示例在当前设备能运行的示例列表中的位置，
+    /// 回退到空示例时 `name` 为 `"Empty"`
+    ExampleChanged {
+        index: i32,
+        name: String,
+    },
+    /// 一帧渲染完成
+    FrameRendered(FrameStats),
+    Warning(String),
+    Error(String),
+    /// Device 丢失，正在尝试恢复
+    DeviceLost,
+    /// Device 已恢复，所有 GPU 资源均已重建
+    DeviceRecovered,
+    /// Surface 已丢失，重新配置也无法恢复，需要宿主 App 重建视图
+    SurfaceLost,
+}
+
+/// `HostEvent` 的类型，在 C 端是一个 32 位的枚举
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HostEventKind {
+    Ready = 0,
+    ExampleChanged,
+    FrameRendered,
+    Warning,
+    Error,
+    DeviceLost,
+    DeviceRecovered,
+    SurfaceLost,
+}
+
+impl HostEventKind {
+    /// 在注册回调时的 `mask` 中对应的位
+    pub const fn mask(self) -> u32 {
+        1 << self as u32
+    }
+}
+
+impl HostEvent {
+    pub fn kind(&self) -> HostEventKind {
+        match self {
+            HostEvent::Ready => HostEventKind::Ready,
+            HostEvent::ExampleChanged { .. } => HostEventKind::ExampleChanged,
+            HostEvent::FrameRendered(_) => HostEventKind::FrameRendered,
+            HostEvent::Warning(_) => HostEventKind::Warning,
+            HostEvent::Error(_) => HostEventKind::Error,
+            HostEvent::DeviceLost => HostEventKind::DeviceLost,
+            HostEvent::DeviceRecovered => HostEventKind::DeviceRecovered,
+            HostEvent::SurfaceLost => HostEventKind::SurfaceLost,
+        }
+    }
+
+    /// `ExampleChanged` 为示例在当前设备能运行的示例列表中的序号，`FrameRendered` 为帧序号，其余为 0
+    pub fn code(&self) -> i64 {
+        match self {
+            HostEvent::ExampleChanged { index, .. } => *index as i64,
+            HostEvent::FrameRendered(stats) => stats.frame_count as i64,
+            _ => 0,
+        }
+    }
+
+    /// `ExampleChanged` 为示例名称，`Warning` 与 `Error` 为消息，
+    /// `FrameRendered` 为 JSON 编码的帧统计（字段名与 `FrameStats` 一致），其余没有负载
+    pub fn payload(&self) -> Option<String> {
+        match self {
+            HostEvent::ExampleChanged { name, .. } => Some(name.clone()),
+            HostEvent::Warning(message) | HostEvent::Error(message) => Some(message.clone()),
+            HostEvent::FrameRendered(stats) => Some(frame_stats_json(stats)),
+            _ => None,
+        }
+    }
+}
+
+fn frame_stats_json(stats: &FrameStats) -> String {
+    let mut json = String::with_capacity(192);
+    _ = write!(
+        json,
+        "{{\"frame_count\":{},\"dropped_frames\":{},\"delta_ms\":{},\"average_fps\":{},\
+         \"average_frame_time_ms\":{},\"p50_frame_time_ms\":{},\"p95_frame_time_ms\":{},\
+         \"p99_frame_time_ms\":{}}}",
+        stats.frame_count,
+        stats.dropped_frames,
+        stats.delta_ms,
+        stats.average_fps,
+        stats.average_frame_time_ms,
+        stats.p50_frame_time_ms,
+        stats.p95_frame_time_ms,
+        stats.p99_frame_time_ms,
+    );
+    json
+}
+
+/// `HostEvent` 的 C 兼容编码，只在回调期间有效
+#[repr(C)]
+#[derive(Debug)]
+pub struct RawHostEvent {
+    pub kind: HostEventKind,
+    pub code: i64,
+    /// UTF-8 编码并以 `\0` 结尾，没有负载时为空指针
+    pub payload: *const c_char,
+    /// 负载的字节数，不含末尾的 `\0`
+    pub payload_len: usize,
+}
+
+impl RawHostEvent {
+    /// # Safety
+    ///
+    /// 只能在回调期间调用
+    pub unsafe fn payload_str(&self) -> Option<&str> {
+        if self.payload.is_null() {
+            return None;
+        }
+        let bytes =
+            unsafe { core::slice::from_raw_parts(self.payload.cast::<u8>(), self.payload_len) };
+        core::str::from_utf8(bytes).ok()
+    }
+}
+
+/// 在渲染线程上调用，`user_data` 为注册时传入的指针
+pub type HostEventCallback = extern "C" fn(event: *const RawHostEvent, user_data: *mut c_void);
+
+/// 回调被替换或 `AppSurface` 被释放时调用，用于释放 `user_data`
+pub type HostEventRelease = extern "C" fn(user_data: *mut c_void);
+
+/// 把 `HostEvent` 转发给宿主 App 注册的回调
+///
+/// 注册之前发出的事件（比如创建画布时的 `Ready`）会被缓存，注册后立即补发；
+/// 每帧发出的 `FrameRendered` 不缓存
+#[derive(Default)]
+pub struct HostEventSink {
+    callback: Option<HostEventCallback>,
+    user_data: UserData,
+    mask: u32,
+    release: Option<HostEventRelease>,
+    pending: VecDeque<HostEvent>,
+}
+
+/// 宿主 App 需保证 `user_data` 可以在渲染线程上访问
+struct UserData(*mut c_void);
+
+impl Default for UserData {
+    fn default() -> Self {
+        Self(core::ptr::null_mut())
+    }
+}
+
+unsafe impl Send for UserData {}
+unsafe impl Sync for UserData {}
+
+impl HostEventSink {
+    /// 注册回调，`mask` 为 `HostEventKind::mask` 的组合；传入 `None` 时取消注册
+    ///
+    /// 之前注册的 `user_data` 会通过之前的 `release` 释放
+    pub fn register(
+        &mut self,
+        callback: Option<HostEventCallback>,
+        user_data: *mut c_void,
+        mask: u32,
+        release: Option<HostEventRelease>,
+    ) {
+        self.release_user_data();
+        self.callback = callback;
+        self.user_data = UserData(user_data);
+        self.mask = mask;
+        self.release = release;
+
+        if self.callback.is_some() {
+            for event in core::mem::take(&mut self.pending) {
+                self.emit(&event);
+            }
+        }
+    }
+
+    /// 是否有回调接收这类事件，用于跳过构造开销较大的事件
+    pub fn wants(&self, kind: HostEventKind) -> bool {
+        self.callback.is_some() && self.mask & kind.mask() != 0
+    }
+
+    pub fn emit(&mut self, event: &HostEvent) {
+        let Some(callback) = self.callback else {
+            if event.kind() != HostEventKind::FrameRendered {
+                if self.pending.len() == MAX_PENDING_EVENTS {
+                    self.pending.pop_front();
+                }
+                self.pending.push_back(event.clone());
+            }
+            return;
+        };
+        if !self.wants(event.kind()) {
+            return;
+        }
+        // 负载中的 `\0` 会截断 C 字符串，替换掉
+        let payload = event
+            .payload()
+            .map(|payload| CString::new(payload.replace('\0', " ")).unwrap_or_default());
+        let raw = RawHostEvent {
+            kind: event.kind(),
+            code: event.code(),
+            payload: payload
+                .as_ref()
+                .map_or(core::ptr::null(), |payload| payload.as_ptr()),
+            payload_len: payload
+                .as_ref()
+                .map_or(0, |payload| payload.as_bytes().len()),
+        };
+        callback(&raw, self.user_data.0);
+    }
+
+    fn release_user_data(&mut self) {
+        if let Some(release) = self.release.take() {
+            release(self.user_data.0);
+        }
+        self.user_data = UserData::default();
+    }
+}
+
+impl Drop for HostEventSink {
+    fn drop(&mut self) {
+        self.release_user_data();
+    }
+}
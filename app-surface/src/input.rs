@@ -0,0 +1,266 @@
+use crate::Touch;
+#[cfg(feature = "winit")]
+use crate::{PointerKind, TouchPhase};
+use glam::Vec2;
+
+/// 与平台无关的输入事件
+///
+/// 位置与距离的单位均为逻辑像素
+#[derive(Clone, Debug)]
+pub enum InputEvent {
+    /// 手指、触控笔或鼠标主键
+    ///
+    /// 鼠标未按下时的移动以 `Moved` 阶段上报，可用于悬停效果
+    Pointer(Touch),
+    /// 滚轮或触控板滚动，`delta.y` 为正表示向上滚动
+    Wheel { delta: Vec2, position: Vec2 },
+    Key {
+        key: Key,
+        pressed: bool,
+        repeat: bool,
+        modifiers: Modifiers,
+    },
+    /// 输入法或键盘输入的文本
+    Text(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Key {
+    /// 产生字符的按键，内容为考虑了键盘布局与修饰键之后的字符
+    Character(String),
+    Named(NamedKey),
+    Unidentified,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NamedKey {
+    Enter,
+    Escape,
+    Space,
+    Tab,
+    Backspace,
+    Delete,
+    ArrowUp,
+    ArrowDown,
+    ArrowLeft,
+    ArrowRight,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Shift,
+    Control,
+    Alt,
+    Super,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub control: bool,
+    pub alt: bool,
+    pub super_key: bool,
+}
+
+/// 鼠标作为指针时使用的 id，避免与触摸点的 id 冲突
+pub const MOUSE_POINTER_ID: u64 = u64::MAX;
+
+/// 滚轮按行滚动时，每行对应的逻辑像素
+#[cfg(feature = "winit")]
+const WHEEL_LINE_HEIGHT: f32 = 20.0;
+
+/// 把 winit 的窗口事件转换为 `InputEvent`
+///
+/// 每个窗口使用一个实例，以跟踪光标位置、鼠标按键与修饰键的状态
+#[cfg(feature = "winit")]
+#[derive(Clone, Debug)]
+pub struct WinitInputTranslator {
+    scale_factor: f32,
+    cursor: Vec2,
+    mouse_pressed: bool,
+    modifiers: Modifiers,
+    #[cfg(not(target_arch = "wasm32"))]
+    start: std::time::Instant,
+}
+
+#[cfg(feature = "winit")]
+impl WinitInputTranslator {
+    pub fn new(scale_factor: f32) -> Self {
+        Self {
+            scale_factor,
+            cursor: Vec2::ZERO,
+            mouse_pressed: false,
+            modifiers: Modifiers::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            start: std::time::Instant::now(),
+        }
+    }
+
+    pub fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    /// 与输入无关的事件返回空数组；按键同时产生文本时会返回两个事件
+    pub fn translate(&mut self, event: &winit::event::WindowEvent) -> Vec<InputEvent> {
+        use winit::event::{ElementState, MouseButton, MouseScrollDelta, WindowEvent};
+
+        let mut events = vec![];
+        match event {
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                self.scale_factor = *scale_factor as f32;
+            }
+            WindowEvent::ModifiersChanged(modifiers) => {
+                let state = modifiers.state();
+                self.modifiers = Modifiers {
+                    shift: state.shift_key(),
+                    control: state.control_key(),
+                    alt: state.alt_key(),
+                    super_key: state.super_key(),
+                };
+            }
+            WindowEvent::CursorMoved { position, .. } => {
+                self.cursor = self.to_logical(position.x, position.y);
+                events.push(self.mouse_pointer(TouchPhase::Moved));
+            }
+            WindowEvent::CursorLeft { .. } if self.mouse_pressed => {
+                self.mouse_pressed = false;
+                events.push(self.mouse_pointer(TouchPhase::Cancelled));
+            }
+            WindowEvent::MouseInput {
+                state,
+                button: MouseButton::Left,
+                ..
+            } => {
+                let pressed = *state == ElementState::Pressed;
+                // 忽略重复的按下或没有对应按下的抬起
+                if pressed != self.mouse_pressed {
+                    self.mouse_pressed = pressed;
+                    let phase = if pressed {
+                        TouchPhase::Started
+                    } else {
+                        TouchPhase::Ended
+                    };
+                    events.push(self.mouse_pointer(phase));
+                }
+            }
+            WindowEvent::MouseWheel { delta, .. } => {
+                let delta = match delta {
+                    MouseScrollDelta::LineDelta(x, y) => Vec2::new(*x, *y) * WHEEL_LINE_HEIGHT,
+                    MouseScrollDelta::PixelDelta(delta) => self.to_logical(delta.x, delta.y),
+                };
+                events.push(InputEvent::Wheel {
+                    delta,
+                    position: self.cursor,
+                });
+            }
+            WindowEvent::Touch(touch) => {
+                let phase = match touch.phase {
+                    winit::event::TouchPhase::Started => TouchPhase::Started,
+                    winit::event::TouchPhase::Moved => TouchPhase::Moved,
+                    winit::event::TouchPhase::Ended => TouchPhase::Ended,
+                    winit::event::TouchPhase::Cancelled => TouchPhase::Cancelled,
+                };
+                let position = self.to_logical(touch.location.x, touch.location.y);
+                let mut pointer = Touch::new(position, phase)
+                    .with_id(touch.id)
+                    .with_timestamp(self.now_seconds());
+                if let Some(force) = touch.force {
+                    pointer.pressure = force.normalized() as f32;
+                    // 只有触控笔会上报高度角
+                    if let winit::event::Force::Calibrated {
+                        altitude_angle: Some(altitude),
+                        ..
+                    } = force
+                    {
+                        pointer.kind = PointerKind::Stylus;
+                        pointer.stylus_angle.altitude = altitude as f32;
+                    }
+                }
+                events.push(InputEvent::Pointer(pointer));
+            }
+            WindowEvent::KeyboardInput { event, .. } => {
+                let pressed = event.state == ElementState::Pressed;
+                events.push(InputEvent::Key {
+                    key: translate_key(&event.logical_key),
+                    pressed,
+                    repeat: event.repeat,
+                    modifiers: self.modifiers,
+                });
+                // 控制字符（回车、退格等）已经通过按键事件上报
+                if pressed
+                    && let Some(text) = &event.text
+                    && !text.chars().all(char::is_control)
+                {
+                    events.push(InputEvent::Text(text.to_string()));
+                }
+            }
+            WindowEvent::Ime(winit::event::Ime::Commit(text)) => {
+                events.push(InputEvent::Text(text.clone()));
+            }
+            _ => {}
+        }
+        events
+    }
+
+    fn mouse_pointer(&self, phase: TouchPhase) -> InputEvent {
+        let mut pointer = Touch::new(self.cursor, phase)
+            .with_id(MOUSE_POINTER_ID)
+            .with_kind(PointerKind::Mouse)
+            .with_timestamp(self.now_seconds());
+        if self.mouse_pressed {
+            pointer.pressure = 1.0;
+        }
+        InputEvent::Pointer(pointer)
+    }
+
+    fn to_logical(&self, x: f64, y: f64) -> Vec2 {
+        Vec2::new(x as f32, y as f32) / self.scale_factor
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn now_seconds(&self) -> f64 {
+        self.start.elapsed().as_secs_f64()
+    }
+
+    // wasm32-unknown-unknown 上没有 std::time::Instant 的实现
+    #[cfg(target_arch = "wasm32")]
+    fn now_seconds(&self) -> f64 {
+        web_sys::window()
+            .and_then(|window| window.performance())
+            .map(|performance| performance.now() / 1000.0)
+            .unwrap_or(0.0)
+    }
+}
+
+#[cfg(feature = "winit")]
+fn translate_key(key: &winit::keyboard::Key) -> Key {
+    use winit::keyboard::{Key as WinitKey, NamedKey as Named};
+
+    let named = match key {
+        WinitKey::Character(text) => return Key::Character(text.to_string()),
+        WinitKey::Named(named) => named,
+        _ => return Key::Unidentified,
+    };
+    let named = match named {
+        Named::Enter => NamedKey::Enter,
+        Named::Escape => NamedKey::Escape,
+        Named::Space => NamedKey::Space,
+        Named::Tab => NamedKey::Tab,
+        Named::Backspace => NamedKey::Backspace,
+        Named::Delete => NamedKey::Delete,
+        Named::ArrowUp => NamedKey::ArrowUp,
+        Named::ArrowDown => NamedKey::ArrowDown,
+        Named::ArrowLeft => NamedKey::ArrowLeft,
+        Named::ArrowRight => NamedKey::ArrowRight,
+        Named::Home => NamedKey::Home,
+        Named::End => NamedKey::End,
+        Named::PageUp => NamedKey::PageUp,
+        Named::PageDown => NamedKey::PageDown,
+        Named::Shift => NamedKey::Shift,
+        Named::Control => NamedKey::Control,
+        Named::Alt => NamedKey::Alt,
+        Named::Super => NamedKey::Super,
+        _ => return Key::Unidentified,
+    };
+    Key::Named(named)
+}
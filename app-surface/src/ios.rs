@@ -1,3 +1,4 @@
+use crate::{AppSurfaceError, DeviceOptions};
 use core::marker::Sync;
 use libc::c_void;
 use objc2::{msg_send, runtime::AnyObject};
@@ -12,24 +13,44 @@ pub struct IOSViewObj {
     pub metal_layer: *mut c_void,
     // 不同的 iOS 设备支持不同的屏幕刷新率，有时我们的 GPU 程序需要用到这类信息
     pub maximum_frames: i32,
-    // 外部函数接口，用于给 iOS 端传递状态码
-    pub callback_to_swift: extern "C" fn(arg: i32),
+    // App 沙盒内 Library 目录的 UTF-8 路径，可以为空指针；只在创建 AppSurface 期间被读取
+    pub library_directory: *const libc::c_char,
 }
 
+/// `AppSurface::resume` 用来重建 surface 的原生视图
+pub type NativeHandle = IOSViewObj;
+
 pub struct AppSurface {
     pub view: *mut AnyObject,
     pub scale_factor: f32,
+    /// 安全区域的内边距，单位为逻辑像素，由宿主 App 通过 `set_safe_area` 更新
+    pub safe_area: crate::EdgeInsets,
     pub ctx: crate::IASDQContext,
     pub maximum_frames: i32,
-    pub callback_to_app: Option<extern "C" fn(arg: i32)>,
-    pub temporary_directory: &'static str,
-    pub library_directory: &'static str,
+    /// 发给宿主 App 的事件，由宿主 App 通过 `register` 注册接收回调
+    pub host_events: crate::HostEventSink,
+    pub temporary_directory: String,
+    /// 持久化数据（比如管线缓存）的存放目录，为空时不做持久化
+    pub library_directory: String,
 }
 
 unsafe impl Sync for AppSurface {}
 
 impl AppSurface {
+    /// 与 [`AppSurface::try_new`] 相同，但创建失败时直接 panic
     pub fn new(obj: IOSViewObj) -> Self {
+        Self::try_new(obj).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_new(obj: IOSViewObj) -> Result<Self, AppSurfaceError> {
+        Self::try_new_with_options(obj, &DeviceOptions::default())
+    }
+
+    /// 按 `options` 协商 Device 的特性与限制
+    pub fn try_new_with_options(
+        obj: IOSViewObj,
+        options: &DeviceOptions,
+    ) -> Result<Self, AppSurfaceError> {
         // hook up rust logging
         _ = env_logger::try_init();
 
@@ -47,26 +68,94 @@ impl AppSurface {
             ..wgpu::InstanceDescriptor::new_without_display_handle()
         });
         let surface = unsafe {
-            instance
+            instance.create_surface_unsafe(wgpu::SurfaceTargetUnsafe::CoreAnimationLayer(
+                obj.metal_layer,
+            ))?
+        };
+
+        let mut ctx = futures_lite::future::block_on(crate::create_iasdq_context(
+            instance, surface, physical, options,
+        ))?;
+        ctx.frame_clock.set_target_fps(obj.maximum_frames);
+
+        Ok(AppSurface {
+            view: obj.view,
+            scale_factor,
+            safe_area: crate::EdgeInsets::ZERO,
+            ctx,
+            host_events: crate::HostEventSink::default(),
+            maximum_frames: obj.maximum_frames,
+            temporary_directory: String::new(),
+            library_directory: library_directory(&obj),
+        })
+    }
+
+    /// 复用 `ctx` 的 instance、adapter、device 与 queue 为另一个 CAMetalLayer 创建 AppSurface
+    pub fn try_new_shared(
+        obj: IOSViewObj,
+        ctx: &crate::IASDQContext,
+    ) -> Result<Self, AppSurfaceError> {
+        let scale_factor = get_scale_factor(obj.view);
+        let s: CGRect = unsafe { msg_send![obj.view, frame] };
+        let physical = (
+            (s.size.width as f32 * scale_factor) as u32,
+            (s.size.height as f32 * scale_factor) as u32,
+        );
+        let surface = unsafe {
+            ctx.instance
                 .create_surface_unsafe(wgpu::SurfaceTargetUnsafe::CoreAnimationLayer(
                     obj.metal_layer,
-                ))
-                .expect("Surface creation failed")
+                ))?
         };
+        let mut ctx = crate::create_shared_iasdq_context(ctx, surface, physical)?;
+        ctx.frame_clock.set_target_fps(obj.maximum_frames);
 
-        let ctx = futures_lite::future::block_on(crate::create_iasdq_context(
-            instance, surface, physical,
-        ));
-
-        AppSurface {
+        Ok(AppSurface {
             view: obj.view,
             scale_factor,
+            safe_area: crate::EdgeInsets::ZERO,
             ctx,
-            callback_to_app: Some(obj.callback_to_swift),
+            host_events: crate::HostEventSink::default(),
             maximum_frames: obj.maximum_frames,
-            temporary_directory: "",
-            library_directory: "",
+            temporary_directory: String::new(),
+            library_directory: library_directory(&obj),
+        })
+    }
+
+    /// App 进入后台时调用，释放 surface 但保留 device
+    pub fn suspend(&mut self) {
+        self.ctx.suspend_surface();
+    }
+
+    /// 回到前台后用 `obj` 中的 CAMetalLayer 重建 surface，`obj` 可以与创建时的相同
+    pub fn resume(&mut self, obj: NativeHandle) -> Result<(), AppSurfaceError> {
+        if !self.ctx.is_suspended() {
+            return Ok(());
         }
+        let surface = unsafe {
+            self.ctx.instance.create_surface_unsafe(
+                wgpu::SurfaceTargetUnsafe::CoreAnimationLayer(obj.metal_layer),
+            )?
+        };
+        let scale_factor = get_scale_factor(obj.view);
+        let s: CGRect = unsafe { msg_send![obj.view, frame] };
+        let physical = (
+            (s.size.width as f32 * scale_factor) as u32,
+            (s.size.height as f32 * scale_factor) as u32,
+        );
+        self.ctx.resume_surface(surface, physical)?;
+        self.view = obj.view;
+        self.scale_factor = scale_factor;
+        Ok(())
+    }
+
+    pub fn set_scale_factor(&mut self, scale_factor: f32) {
+        self.scale_factor = scale_factor;
+    }
+
+    /// 视图移到其它屏幕（比如外接显示器）后 contentScaleFactor 可能改变
+    pub(crate) fn sync_scale_factor(&mut self) {
+        self.scale_factor = get_scale_factor(self.view);
     }
 
     pub fn get_view_size(&self) -> (u32, u32) {
@@ -78,6 +167,15 @@ impl AppSurface {
     }
 }
 
+fn library_directory(obj: &IOSViewObj) -> String {
+    if obj.library_directory.is_null() {
+        return String::new();
+    }
+    unsafe { core::ffi::CStr::from_ptr(obj.library_directory) }
+        .to_string_lossy()
+        .into_owned()
+}
+
 fn get_scale_factor(obj: *mut AnyObject) -> f32 {
     let mut _scale_factor: CGFloat = 1.0;
 
@@ -1,9 +1,54 @@
 use core::ops::Deref;
+use core::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use wgpu::{Instance, Surface};
 
 mod touch;
 pub use touch::*;
 
+mod gesture;
+pub use gesture::*;
+
+mod input;
+pub use input::*;
+
+mod camera;
+pub use camera::*;
+
+mod view_size;
+pub use view_size::*;
+
+mod asset;
+pub use asset::*;
+
+mod error;
+pub use error::*;
+
+mod device_options;
+pub use device_options::*;
+
+mod frame_clock;
+pub use frame_clock::*;
+
+mod profiler;
+pub use profiler::*;
+
+mod host_event;
+pub use host_event::*;
+
+mod parameter;
+pub use parameter::*;
+
+mod surface_format;
+pub use surface_format::*;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod pipeline_cache;
+
+mod capture;
+pub use capture::{CapturedImage, capture_texture};
+use capture::{FrameCapture, FrameCaptureState};
+
 #[cfg_attr(
     any(target_os = "ios", all(feature = "mac_catalyst", target_os = "macos")),
     path = "ios.rs"
@@ -34,13 +79,6 @@ pub use app_surface::*;
 // #[cfg(all(target_arch = "wasm32", not(feature = "web_rwh")))]
 // compile_error!("web_rwh feature is not enabled -");
 
-#[repr(C)]
-#[derive(Debug)]
-pub struct ViewSize {
-    pub width: u32,
-    pub height: u32,
-}
-
 #[cfg(target_arch = "wasm32")]
 use std::rc::Rc as SharedPtr;
 #[cfg(not(target_arch = "wasm32"))]
@@ -49,14 +87,64 @@ use std::sync::Arc as SharedPtr;
 #[derive(Clone)]
 pub struct IASDQContext {
     pub instance: wgpu::Instance,
-    pub surface: SharedPtr<wgpu::Surface<'static>>,
+    /// 离屏模式下没有 `wgpu::Surface`，此时为 `None`
+    pub surface: Option<SharedPtr<wgpu::Surface<'static>>>,
     pub config: wgpu::SurfaceConfiguration,
     pub adapter: wgpu::Adapter,
     pub device: wgpu::Device,
     pub queue: wgpu::Queue,
+    /// 与适配器协商后 Device 实际获得的特性
+    pub granted_features: wgpu::Features,
+    /// 与适配器协商后 Device 实际获得的限制
+    pub granted_limits: wgpu::Limits,
+    /// 离屏模式下代替 surface 的渲染目标纹理，尺寸与格式始终跟随 `config`
+    pub offscreen_texture: Option<wgpu::Texture>,
+    /// 创建 Device 时使用的选项，设备丢失后按相同的选项恢复
+    pub device_options: DeviceOptions,
+    /// 获取 surface 纹理失败时的重试策略
+    pub acquire_policy: AcquireRetryPolicy,
+    /// 由 `SurfaceFrame::enter_frame` 驱动的帧时钟
+    pub frame_clock: FrameClock,
+    /// 按通道统计 GPU 耗时，默认不启用
+    pub profiler: GpuProfiler,
+    /// 由 `load_pipeline_cache` 创建，创建管线时传给描述符的 `cache` 字段
+    pub pipeline_cache: Option<wgpu::PipelineCache>,
+    /// 管线缓存的文件路径
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    pipeline_cache_file: Option<std::path::PathBuf>,
+    /// 由 device lost 回调置位，回调可能在任意线程上触发
+    device_lost: Arc<AtomicBool>,
+    /// 最近一次获取帧纹理时 surface 已丢失
+    surface_lost: Arc<AtomicBool>,
+    /// 由 `suspend_surface` 置位，此时 `surface` 已释放但不是离屏模式
+    suspended: bool,
+    frame_capture: Arc<Mutex<FrameCaptureState>>,
+    /// 为 `true` 时 `FrameTexture::present` 不立即呈现，由 `take_deferred_frame` 取回后
+    /// 继续绘制（如调试 HUD）再呈现
+    pub defer_present: bool,
+    deferred_frame: SharedPtr<Mutex<Option<FrameTexture>>>,
 }
 
 impl IASDQContext {
+    pub fn is_offscreen(&self) -> bool {
+        self.surface.is_none() && !self.suspended
+    }
+
+    /// 使用当前的 `config` 配置 surface；离屏模式下则按 `config` 重建渲染目标纹理
+    ///
+    /// 挂起期间只更新 `config`，恢复时再配置新的 surface
+    pub fn configure_surface(&mut self) {
+        if self.suspended {
+            return;
+        }
+        match self.surface {
+            Some(ref surface) => surface.configure(&self.device, &self.config),
+            None => {
+                self.offscreen_texture = Some(create_offscreen_texture(&self.device, &self.config))
+            }
+        }
+    }
+
     pub fn update_config_format(&mut self, format: wgpu::TextureFormat) {
         self.config.format = format;
         if cfg!(feature = "webgl") {
@@ -66,7 +154,272 @@ impl IASDQContext {
         } else {
             self.config.view_formats = vec![format];
         }
-        self.surface.configure(&self.device, &self.config);
+        self.configure_surface();
+    }
+
+    /// 取回本帧被推迟呈现的帧，调用方绘制完成后需调用其 `present`
+    pub fn take_deferred_frame(&self) -> Option<FrameTexture> {
+        self.deferred_frame.lock().unwrap().take()
+    }
+
+    /// Device 是否已丢失（驱动重置、GPU 被移除等）
+    ///
+    /// 丢失后所有 GPU 资源都已失效，需调用 `recover_device` 后重建
+    pub fn is_device_lost(&self) -> bool {
+        self.device_lost.load(Ordering::Acquire)
+    }
+
+    /// 最近一次获取帧纹理时 surface 是否已丢失，成功获取到帧纹理后清除
+    ///
+    /// 丢失后重新配置也无法恢复，需要宿主 App 重建视图
+    pub fn is_surface_lost(&self) -> bool {
+        self.surface_lost.load(Ordering::Acquire)
+    }
+
+    /// surface 是否已被 `suspend_surface` 释放
+    pub fn is_suspended(&self) -> bool {
+        self.suspended
+    }
+
+    /// App 进入后台时释放 surface，device 及其创建的 GPU 资源都保持不变
+    ///
+    /// 挂起期间获取帧纹理总是返回 `SkipOccluded`；离屏模式下没有 surface，不做任何事
+    pub fn suspend_surface(&mut self) {
+        if self.surface.is_none() {
+            return;
+        }
+        // 推迟呈现的帧引用着 surface 纹理，需要先于 surface 释放
+        self.deferred_frame.lock().unwrap().take();
+        self.surface = None;
+        self.suspended = true;
+        self.surface_lost.store(false, Ordering::Release);
+    }
+
+    /// 用新的原生视图创建的 `surface` 替换挂起时释放的 surface，`physical_size` 为新视图的尺寸
+    ///
+    /// 新 surface 不支持原有的格式、呈现模式或 alpha 模式时，回退到它支持的值；没有挂起时不做任何事
+    pub fn resume_surface(
+        &mut self,
+        surface: Surface<'static>,
+        physical_size: (u32, u32),
+    ) -> Result<(), AppSurfaceError> {
+        // 没有挂起时原有的 surface 仍在使用，不能再为同一视图配置第二个
+        if !self.suspended {
+            return Ok(());
+        }
+        if !self.adapter.is_surface_supported(&surface) {
+            return Err(AppSurfaceError::UnsupportedSurface {
+                adapter_info: Box::new(self.adapter.get_info()),
+            });
+        }
+        fit_config_to_surface(&mut self.config, &surface, &self.adapter)?;
+        self.config.width = physical_size.0.max(1);
+        self.config.height = physical_size.1.max(1);
+        surface.configure(&self.device, &self.config);
+        self.surface = Some(SharedPtr::new(surface));
+        self.offscreen_texture = None;
+        self.suspended = false;
+        // 不把在后台的时间计入帧时间
+        self.frame_clock.reset_delta();
+        Ok(())
+    }
+
+    /// 重新创建 adapter、device 与 queue，并用新的 device 重新配置 surface
+    ///
+    /// 新适配器不支持原有的格式、呈现模式或 alpha 模式时，回退到 surface 支持的值。
+    /// 共享同一 device 的其它上下文仍持有旧的丢失标记，它们会各自恢复出独立的 device
+    pub async fn recover_device(&mut self) -> Result<(), AppSurfaceError> {
+        self.device_lost = Arc::new(AtomicBool::new(false));
+        let (adapter, device, queue) = request_device(
+            &self.instance,
+            self.surface.as_deref(),
+            &self.device_options,
+            &self.device_lost,
+        )
+        .await?;
+
+        if let Some(ref surface) = self.surface {
+            fit_config_to_surface(&mut self.config, surface, &adapter)?;
+        }
+
+        self.granted_features = device.features();
+        self.granted_limits = device.limits();
+        self.adapter = adapter;
+        self.device = device;
+        self.queue = queue;
+        self.configure_surface();
+        self.profiler.reset_device(&self.device, &self.queue);
+        #[cfg(not(target_arch = "wasm32"))]
+        self.reload_pipeline_cache();
+        Ok(())
+    }
+
+    /// 查询 surface 在当前适配器上支持的格式、呈现模式与 alpha 合成模式
+    ///
+    /// 离屏模式下只支持创建时的配置
+    pub fn surface_capabilities(&self) -> wgpu::SurfaceCapabilities {
+        match self.surface {
+            Some(ref surface) => surface.get_capabilities(&self.adapter),
+            None => wgpu::SurfaceCapabilities {
+                formats: vec![self.config.format],
+                present_modes: vec![self.config.present_mode],
+                alpha_modes: vec![self.config.alpha_mode],
+                usages: self.config.usage,
+            },
+        }
+    }
+
+    /// `AutoVsync` 与 `AutoNoVsync` 总是可用，由 wgpu 自动回退到受支持的模式
+    pub fn set_present_mode(
+        &mut self,
+        present_mode: wgpu::PresentMode,
+    ) -> Result<(), SurfaceConfigError> {
+        let is_auto = matches!(
+            present_mode,
+            wgpu::PresentMode::AutoVsync | wgpu::PresentMode::AutoNoVsync
+        );
+        let supported = self.surface_capabilities().present_modes;
+        if !is_auto && !supported.contains(&present_mode) {
+            return Err(SurfaceConfigError::UnsupportedPresentMode {
+                requested: present_mode,
+                supported,
+            });
+        }
+        if self.config.present_mode != present_mode {
+            self.config.present_mode = present_mode;
+            self.configure_surface();
+        }
+        Ok(())
+    }
+
+    /// 实际生效的帧延迟会被各后端限制在其支持的范围内
+    pub fn set_desired_maximum_frame_latency(
+        &mut self,
+        latency: u32,
+    ) -> Result<(), SurfaceConfigError> {
+        if latency == 0 {
+            return Err(SurfaceConfigError::InvalidFrameLatency(latency));
+        }
+        if self.config.desired_maximum_frame_latency != latency {
+            self.config.desired_maximum_frame_latency = latency;
+            self.configure_surface();
+        }
+        Ok(())
+    }
+
+    /// 画面需要与原生 UI 混合时，使用 `PreMultiplied` 或 `PostMultiplied`
+    pub fn set_alpha_mode(
+        &mut self,
+        alpha_mode: wgpu::CompositeAlphaMode,
+    ) -> Result<(), SurfaceConfigError> {
+        let supported = self.surface_capabilities().alpha_modes;
+        if alpha_mode != wgpu::CompositeAlphaMode::Auto && !supported.contains(&alpha_mode) {
+            return Err(SurfaceConfigError::UnsupportedAlphaMode {
+                requested: alpha_mode,
+                supported,
+            });
+        }
+        if self.config.alpha_mode != alpha_mode {
+            self.config.alpha_mode = alpha_mode;
+            self.configure_surface();
+        }
+        Ok(())
+    }
+}
+
+/// 当前帧的渲染目标
+///
+/// 离屏模式下没有 `wgpu::SurfaceTexture`，帧缓冲就是 `IASDQContext::offscreen_texture`
+pub struct FrameTexture {
+    texture: wgpu::Texture,
+    surface_texture: Option<wgpu::SurfaceTexture>,
+    /// 请求了帧捕获时，在呈现之前把帧缓冲拷贝出来
+    capture: Option<FrameCapture>,
+    /// 开启了 `defer_present` 时，`present` 把帧存入这里而不呈现
+    deferred: Option<SharedPtr<Mutex<Option<FrameTexture>>>>,
+}
+
+impl FrameTexture {
+    pub fn from_surface_texture(frame: wgpu::SurfaceTexture) -> Self {
+        Self {
+            texture: frame.texture.clone(),
+            surface_texture: Some(frame),
+            capture: None,
+            deferred: None,
+        }
+    }
+
+    pub fn from_texture(texture: wgpu::Texture) -> Self {
+        Self {
+            texture,
+            surface_texture: None,
+            capture: None,
+            deferred: None,
+        }
+    }
+
+    pub fn texture(&self) -> &wgpu::Texture {
+        &self.texture
+    }
+
+    /// 离屏模式下没有需要呈现的交换链，直接丢弃即可
+    pub fn present(mut self) {
+        if let Some(deferred) = self.deferred.take() {
+            *deferred.lock().unwrap() = Some(self);
+            return;
+        }
+        let readback = self
+            .capture
+            .as_ref()
+            .map(|capture| capture.submit(&self.texture));
+        if let Some(frame) = self.surface_texture {
+            frame.present();
+        }
+        // 拷贝命令已在呈现前提交，等待读回不会推迟本帧的呈现
+        if let (Some(capture), Some(readback)) = (self.capture, readback) {
+            capture.finish(readback);
+        }
+    }
+}
+
+/// 获取当前帧纹理的结果
+///
+/// 除 `Frame` 外，调用方都应跳过本帧的渲染
+// 每帧只创建一次且立即被解构，无需装箱
+#[allow(clippy::large_enum_variant)]
+pub enum FrameAcquireResult {
+    Frame(FrameTexture, wgpu::TextureView),
+    /// 窗口被遮挡、最小化或 App 进入了后台
+    SkipOccluded,
+    /// 重试次数用尽仍未拿到纹理，surface 已按当前 `config` 重新配置，下一帧可再试
+    Reconfigured,
+    /// surface 已丢失，重新配置也无法恢复，需要重建 surface
+    Lost,
+    /// 获取纹理时出现校验错误，通常是 `config` 与 surface 不匹配
+    Error,
+}
+
+impl FrameAcquireResult {
+    pub fn is_frame(&self) -> bool {
+        matches!(self, FrameAcquireResult::Frame(..))
+    }
+}
+
+/// 获取 surface 纹理遇到 `Timeout`、`Outdated` 或 `Lost` 时的重试策略
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AcquireRetryPolicy {
+    /// 最多重试的次数，为 0 时不重试
+    pub max_retries: u32,
+    /// 重试前是否先按当前 `config` 重新配置 surface
+    pub reconfigure: bool,
+}
+
+impl Default for AcquireRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 1,
+            reconfigure: true,
+        }
     }
 }
 
@@ -77,13 +430,28 @@ impl Deref for AppSurface {
     }
 }
 
+impl AppSurface {
+    /// 宿主 App 的安全区域改变（比如旋转屏幕）后调用，单位为逻辑像素
+    pub fn set_safe_area(&mut self, insets: EdgeInsets) {
+        self.safe_area = insets;
+    }
+}
+
 pub trait SurfaceFrame {
     fn view_size(&self) -> ViewSize;
     // After App view's size or orientation changed, need to resize surface.
+    // 能从原生视图读取缩放因子的平台会同时更新 scale_factor
     fn resize_surface(&mut self);
     fn resize_surface_by_size(&mut self, size: (u32, u32));
     fn pintch(&mut self, _touch: Touch, _scale: f32) {}
     fn touch(&mut self, _touch: Touch) {}
+    /// 同一时刻多个指针的触摸事件，按数组顺序逐个处理
+    fn touches(&mut self, touches: &[Touch]) {
+        for touch in touches {
+            self.touch(*touch);
+        }
+    }
+    /// 把逻辑像素的触摸点转换为 [0, 1] 的归一化坐标，等同于 `ViewSize::logical_to_normalized`
     fn normalize_touch_point(&self, _touch_point_x: f32, _touch_point_y: f32) -> (f32, f32) {
         unimplemented!()
     }
@@ -91,7 +459,7 @@ pub trait SurfaceFrame {
     fn get_current_frame_view(
         &self,
         _view_format: Option<wgpu::TextureFormat>,
-    ) -> Option<(wgpu::SurfaceTexture, wgpu::TextureView)> {
+    ) -> FrameAcquireResult {
         unimplemented!()
     }
     fn create_current_frame_view(
@@ -100,72 +468,163 @@ pub trait SurfaceFrame {
         surface: &wgpu::Surface,
         config: &wgpu::SurfaceConfiguration,
         view_format: Option<wgpu::TextureFormat>,
-    ) -> Option<(wgpu::SurfaceTexture, wgpu::TextureView)> {
-        let frame = match surface.get_current_texture() {
-            wgpu::CurrentSurfaceTexture::Success(frame)
-            | wgpu::CurrentSurfaceTexture::Suboptimal(frame) => frame,
-            wgpu::CurrentSurfaceTexture::Timeout
-            | wgpu::CurrentSurfaceTexture::Outdated
-            | wgpu::CurrentSurfaceTexture::Lost => {
-                surface.configure(device, config);
-                match surface.get_current_texture() {
-                    wgpu::CurrentSurfaceTexture::Success(frame)
-                    | wgpu::CurrentSurfaceTexture::Suboptimal(frame) => frame,
-                    _ => panic!("Failed to acquire next swap chain texture!"),
+        policy: &AcquireRetryPolicy,
+    ) -> FrameAcquireResult {
+        let mut attempt = 0;
+        loop {
+            let lost = match surface.get_current_texture() {
+                wgpu::CurrentSurfaceTexture::Success(frame)
+                | wgpu::CurrentSurfaceTexture::Suboptimal(frame) => {
+                    let view = create_frame_view(&frame.texture, config, view_format);
+                    return FrameAcquireResult::Frame(
+                        FrameTexture::from_surface_texture(frame),
+                        view,
+                    );
                 }
+                wgpu::CurrentSurfaceTexture::Occluded => return FrameAcquireResult::SkipOccluded,
+                wgpu::CurrentSurfaceTexture::Validation => {
+                    log::error!("Validation error acquiring surface texture");
+                    return FrameAcquireResult::Error;
+                }
+                wgpu::CurrentSurfaceTexture::Timeout | wgpu::CurrentSurfaceTexture::Outdated => {
+                    false
+                }
+                wgpu::CurrentSurfaceTexture::Lost => true,
+            };
+            if policy.reconfigure {
+                surface.configure(device, config);
             }
-            wgpu::CurrentSurfaceTexture::Occluded => return None,
-            wgpu::CurrentSurfaceTexture::Validation => panic!("Validation error acquiring texture"),
-        };
-        let view = frame.texture.create_view(&wgpu::TextureViewDescriptor {
-            label: Some("frame texture view"),
-            format: if view_format.is_none() {
-                // frame buffer's view format prefer to use sRGB.
-                Some(config.format.add_srgb_suffix())
-            } else {
-                view_format
-            },
-            ..Default::default()
-        });
-        Some((frame, view))
+            if attempt >= policy.max_retries {
+                return if lost {
+                    log::warn!("Surface lost, it needs to be recreated");
+                    FrameAcquireResult::Lost
+                } else {
+                    FrameAcquireResult::Reconfigured
+                };
+            }
+            attempt += 1;
+        }
     }
 }
 
+fn create_frame_view(
+    texture: &wgpu::Texture,
+    config: &wgpu::SurfaceConfiguration,
+    view_format: Option<wgpu::TextureFormat>,
+) -> wgpu::TextureView {
+    texture.create_view(&wgpu::TextureViewDescriptor {
+        label: Some("frame texture view"),
+        format: if view_format.is_none() {
+            // frame buffer's view format prefer to use sRGB.
+            Some(config.format.add_srgb_suffix())
+        } else {
+            view_format
+        },
+        ..Default::default()
+    })
+}
+
+fn create_offscreen_texture(
+    device: &wgpu::Device,
+    config: &wgpu::SurfaceConfiguration,
+) -> wgpu::Texture {
+    // 与纹理自身格式相同的 view format 无需声明
+    let view_formats: Vec<wgpu::TextureFormat> = config
+        .view_formats
+        .iter()
+        .copied()
+        .filter(|f| *f != config.format)
+        .collect();
+    device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("offscreen frame texture"),
+        size: wgpu::Extent3d {
+            width: config.width,
+            height: config.height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: config.format,
+        usage: config.usage | wgpu::TextureUsages::TEXTURE_BINDING,
+        view_formats: &view_formats,
+    })
+}
+
 impl SurfaceFrame for AppSurface {
     fn view_size(&self) -> ViewSize {
         let size = self.get_view_size();
         ViewSize {
             width: size.0,
             height: size.1,
+            scale_factor: self.scale_factor,
+            safe_area: self.safe_area,
         }
     }
 
     fn resize_surface(&mut self) {
+        self.sync_scale_factor();
         let size = self.get_view_size();
         self.ctx.config.width = size.0;
         self.ctx.config.height = size.1;
-        self.surface.configure(&self.device, &self.config);
+        self.ctx.configure_surface();
     }
 
     fn resize_surface_by_size(&mut self, size: (u32, u32)) {
         self.ctx.config.width = size.0;
         self.ctx.config.height = size.1;
-        self.surface.configure(&self.device, &self.config);
+        self.ctx.configure_surface();
+    }
+
+    fn enter_frame(&mut self) {
+        self.ctx.frame_clock.tick();
+        self.ctx.profiler.begin_frame();
     }
 
     fn normalize_touch_point(&self, touch_point_x: f32, touch_point_y: f32) -> (f32, f32) {
-        let size = self.get_view_size();
-        (
-            touch_point_x * self.scale_factor / size.0 as f32,
-            touch_point_y * self.scale_factor / size.1 as f32,
-        )
+        self.view_size()
+            .logical_to_normalized(glam::Vec2::new(touch_point_x, touch_point_y))
+            .into()
     }
 
     fn get_current_frame_view(
         &self,
         view_format: Option<wgpu::TextureFormat>,
-    ) -> Option<(wgpu::SurfaceTexture, wgpu::TextureView)> {
-        self.create_current_frame_view(&self.device, &self.surface, &self.config, view_format)
+    ) -> FrameAcquireResult {
+        if self.suspended {
+            return FrameAcquireResult::SkipOccluded;
+        }
+        let result = match self.surface {
+            Some(ref surface) => self.create_current_frame_view(
+                &self.device,
+                surface,
+                &self.config,
+                view_format,
+                &self.acquire_policy,
+            ),
+            None => match self.offscreen_texture.clone() {
+                Some(texture) => {
+                    let view = create_frame_view(&texture, &self.config, view_format);
+                    FrameAcquireResult::Frame(FrameTexture::from_texture(texture), view)
+                }
+                None => FrameAcquireResult::Lost,
+            },
+        };
+        match result {
+            FrameAcquireResult::Frame(mut frame, view) => {
+                frame.capture = self.pending_frame_capture();
+                if self.defer_present {
+                    frame.deferred = Some(self.deferred_frame.clone());
+                }
+                self.surface_lost.store(false, Ordering::Release);
+                FrameAcquireResult::Frame(frame, view)
+            }
+            FrameAcquireResult::Lost => {
+                self.surface_lost.store(true, Ordering::Release);
+                FrameAcquireResult::Lost
+            }
+            other => other,
+        }
     }
 }
 
@@ -173,11 +632,108 @@ async fn create_iasdq_context(
     instance: Instance,
     surface: Surface<'static>,
     physical_size: (u32, u32),
-) -> IASDQContext {
-    let (adapter, device, queue) = crate::request_device(&instance, &surface).await;
+    options: &DeviceOptions,
+) -> Result<IASDQContext, AppSurfaceError> {
+    let device_lost = Arc::new(AtomicBool::new(false));
+    let (adapter, device, queue) =
+        crate::request_device(&instance, Some(&surface), options, &device_lost).await?;
+
+    let config = create_surface_config(&surface, &adapter, physical_size)?;
+    surface.configure(&device, &config);
+    let profiler = GpuProfiler::new(&device, &queue);
+
+    Ok(IASDQContext {
+        instance,
+        surface: Some(SharedPtr::new(surface)),
+        config,
+        granted_features: device.features(),
+        granted_limits: device.limits(),
+        adapter,
+        device,
+        queue,
+        offscreen_texture: None,
+        device_options: options.clone(),
+        acquire_policy: AcquireRetryPolicy::default(),
+        frame_clock: FrameClock::default(),
+        profiler,
+        pipeline_cache: None,
+        pipeline_cache_file: None,
+        device_lost,
+        surface_lost: Default::default(),
+        suspended: false,
+        frame_capture: Default::default(),
+        defer_present: false,
+        deferred_frame: Default::default(),
+    })
+}
+
+/// 复用 `shared` 的 instance、adapter、device 与 queue，只为新的 surface 创建配置
+///
+/// `surface` 必须由 `shared.instance` 创建
+fn create_shared_iasdq_context(
+    shared: &IASDQContext,
+    surface: Surface<'static>,
+    physical_size: (u32, u32),
+) -> Result<IASDQContext, AppSurfaceError> {
+    if !shared.adapter.is_surface_supported(&surface) {
+        return Err(AppSurfaceError::UnsupportedSurface {
+            adapter_info: Box::new(shared.adapter.get_info()),
+        });
+    }
+    let config = create_surface_config(&surface, &shared.adapter, physical_size)?;
+    surface.configure(&shared.device, &config);
+
+    Ok(IASDQContext {
+        surface: Some(SharedPtr::new(surface)),
+        config,
+        offscreen_texture: None,
+        frame_clock: FrameClock::default(),
+        profiler: GpuProfiler::new(&shared.device, &shared.queue),
+        surface_lost: Default::default(),
+        suspended: false,
+        frame_capture: Default::default(),
+        defer_present: false,
+        deferred_frame: Default::default(),
+        ..shared.clone()
+    })
+}
+
+/// 把 `config` 中 surface 不支持的格式、呈现模式与 alpha 模式替换为 surface 支持的值
+fn fit_config_to_surface(
+    config: &mut wgpu::SurfaceConfiguration,
+    surface: &Surface<'static>,
+    adapter: &wgpu::Adapter,
+) -> Result<(), AppSurfaceError> {
+    let caps = surface.get_capabilities(adapter);
+    if !caps.formats.contains(&config.format) {
+        let Some(&format) = caps.formats.first() else {
+            return Err(AppSurfaceError::UnsupportedSurface {
+                adapter_info: Box::new(adapter.get_info()),
+            });
+        };
+        config.format = format;
+        config.view_formats = frame_view_formats(format);
+    }
+    if !caps.present_modes.contains(&config.present_mode) {
+        config.present_mode = wgpu::PresentMode::Fifo;
+    }
+    if !caps.alpha_modes.contains(&config.alpha_mode) {
+        config.alpha_mode = caps.alpha_modes[0];
+    }
+    Ok(())
+}
 
-    let caps = surface.get_capabilities(&adapter);
-    let prefered = caps.formats[0];
+fn create_surface_config(
+    surface: &Surface<'static>,
+    adapter: &wgpu::Adapter,
+    physical_size: (u32, u32),
+) -> Result<wgpu::SurfaceConfiguration, AppSurfaceError> {
+    let caps = surface.get_capabilities(adapter);
+    let Some(&prefered) = caps.formats.first() else {
+        return Err(AppSurfaceError::UnsupportedSurface {
+            adapter_info: Box::new(adapter.get_info()),
+        });
+    };
 
     let format = if cfg!(all(target_arch = "wasm32", not(feature = "webgl"))) {
         // Chrome WebGPU doesn't support sRGB:
@@ -186,7 +742,84 @@ async fn create_iasdq_context(
     } else {
         prefered
     };
-    let view_formats = if cfg!(feature = "webgl") {
+    let Some(mut config) = surface.get_default_config(adapter, physical_size.0, physical_size.1)
+    else {
+        return Err(AppSurfaceError::UnsupportedSurface {
+            adapter_info: Box::new(adapter.get_info()),
+        });
+    };
+
+    config.view_formats = frame_view_formats(format);
+    config.format = format;
+    Ok(config)
+}
+
+/// 创建没有 `wgpu::Surface` 的上下文，帧缓冲是一张自有的渲染目标纹理
+///
+/// 没有可用的 GPU 时会退回到软件（fallback）适配器
+// 目前只有 winit 后端提供离屏构造函数
+#[allow(dead_code)]
+async fn create_offscreen_iasdq_context(
+    instance: Instance,
+    physical_size: (u32, u32),
+    options: &DeviceOptions,
+) -> Result<IASDQContext, AppSurfaceError> {
+    let device_lost = Arc::new(AtomicBool::new(false));
+    let (adapter, device, queue) =
+        crate::request_device(&instance, None, options, &device_lost).await?;
+
+    let view_formats_supported = adapter
+        .get_downlevel_capabilities()
+        .flags
+        .contains(wgpu::DownlevelFlags::VIEW_FORMATS);
+    let (format, view_formats) = if view_formats_supported {
+        let format = wgpu::TextureFormat::Rgba8Unorm;
+        (format, frame_view_formats(format))
+    } else {
+        // GL 等后端不支持 view_formats，帧缓冲直接使用 sRGB 格式
+        let format = wgpu::TextureFormat::Rgba8UnormSrgb;
+        (format, vec![format])
+    };
+    let config = wgpu::SurfaceConfiguration {
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        format,
+        width: physical_size.0.max(1),
+        height: physical_size.1.max(1),
+        present_mode: wgpu::PresentMode::Fifo,
+        desired_maximum_frame_latency: 2,
+        alpha_mode: wgpu::CompositeAlphaMode::Opaque,
+        view_formats,
+    };
+    let offscreen_texture = create_offscreen_texture(&device, &config);
+    let profiler = GpuProfiler::new(&device, &queue);
+
+    Ok(IASDQContext {
+        instance,
+        surface: None,
+        config,
+        granted_features: device.features(),
+        granted_limits: device.limits(),
+        adapter,
+        device,
+        queue,
+        offscreen_texture: Some(offscreen_texture),
+        device_options: options.clone(),
+        acquire_policy: AcquireRetryPolicy::default(),
+        frame_clock: FrameClock::default(),
+        profiler,
+        pipeline_cache: None,
+        pipeline_cache_file: None,
+        device_lost,
+        surface_lost: Default::default(),
+        suspended: false,
+        frame_capture: Default::default(),
+        defer_present: false,
+        deferred_frame: Default::default(),
+    })
+}
+
+fn frame_view_formats(format: wgpu::TextureFormat) -> Vec<wgpu::TextureFormat> {
+    if cfg!(feature = "webgl") {
         // panicked at 'Error in Surface::configure: Validation Error
         // Caused by:
         // Downlevel flags DownlevelFlags(SURFACE_VIEW_FORMATS) are required but not supported on the device.
@@ -206,40 +839,36 @@ async fn create_iasdq_context(
         vec![format, format.remove_srgb_suffix()]
     } else {
         vec![format.add_srgb_suffix(), format.remove_srgb_suffix()]
-    };
-
-    let mut config = surface
-        .get_default_config(&adapter, physical_size.0, physical_size.1)
-        .expect("Surface isn't supported by the adapter.");
-
-    config.view_formats = view_formats;
-    config.format = format;
-
-    surface.configure(&device, &config);
-
-    IASDQContext {
-        instance,
-        surface: SharedPtr::new(surface),
-        config,
-        adapter,
-        device,
-        queue,
     }
 }
 
 async fn request_device(
     instance: &Instance,
-    surface: &Surface<'static>,
-) -> (wgpu::Adapter, wgpu::Device, wgpu::Queue) {
-    let adapter = instance
+    surface: Option<&Surface<'static>>,
+    options: &DeviceOptions,
+    device_lost: &Arc<AtomicBool>,
+) -> Result<(wgpu::Adapter, wgpu::Device, wgpu::Queue), AppSurfaceError> {
+    let power_preference = options.power_preference;
+    let adapter = match instance
         .request_adapter(&wgpu::RequestAdapterOptions {
-            power_preference: wgpu::PowerPreference::from_env()
-                .unwrap_or(wgpu::PowerPreference::HighPerformance),
+            power_preference,
             force_fallback_adapter: false,
-            compatible_surface: Some(surface),
+            compatible_surface: surface,
         })
         .await
-        .expect("No suitable GPU adapters found on the system!");
+    {
+        Ok(adapter) => adapter,
+        // 离屏渲染不需要呈现，没有 GPU 时使用软件适配器
+        Err(_) if surface.is_none() => instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference,
+                force_fallback_adapter: true,
+                compatible_surface: None,
+            })
+            .await
+            .map_err(AppSurfaceError::Adapter)?,
+        Err(err) => return Err(AppSurfaceError::Adapter(err)),
+    };
 
     let adapter_info = adapter.get_info();
     println!("Using {} ({:?})", adapter_info.name, adapter_info.backend);
@@ -251,33 +880,41 @@ async fn request_device(
         None
     };
 
-    // remove raytracing features from acquired features under unix like os on nvidia discrete cards
-    // this might be related to wgpu issue, need to keep tracing.
-    let mut adp_features = adapter.features();
-    #[cfg(target_family = "unix")]
-    {
-        if adapter_info.name.contains("NVIDIA") {
-            adp_features.remove(wgpu::Features::EXPERIMENTAL_RAY_QUERY);
-        }
-    }
-    // test features
-    // let adp_features = wgpu::Features::from_bits(0b0011111111011100110111111111111111111111110111000000111111001111).unwrap();
+    let (required_features, required_limits) = options.negotiate(&adapter)?;
+    // 只有明确请求了实验性特性时才开启
+    let experimental_features =
+        if required_features.intersects(wgpu::Features::all_experimental_mask()) {
+            unsafe { wgpu::ExperimentalFeatures::enabled() }
+        } else {
+            wgpu::ExperimentalFeatures::disabled()
+        };
 
     let res = adapter
         .request_device(&wgpu::DeviceDescriptor {
             label: None,
-            required_features: adp_features,
-            required_limits: adapter.limits(),
-            experimental_features: unsafe { wgpu::ExperimentalFeatures::enabled() },
-            memory_hints: wgpu::MemoryHints::Performance,
+            required_features,
+            required_limits,
+            experimental_features,
+            memory_hints: options.memory_hints.clone(),
             trace: wgpu::Trace::Off,
         })
         .await;
 
     match res {
-        Err(err) => {
-            panic!("request_device failed: {err:?}");
+        Err(source) => Err(AppSurfaceError::Device {
+            adapter_info: Box::new(adapter_info),
+            source,
+        }),
+        Ok((device, queue)) => {
+            let device_lost = device_lost.clone();
+            device.set_device_lost_callback(move |reason, message| {
+                // 主动 destroy 或 drop 时也会触发回调，这不属于需要恢复的情况
+                if reason != wgpu::DeviceLostReason::Destroyed {
+                    log::error!("Device lost ({reason:?}): {message}");
+                    device_lost.store(true, Ordering::Release);
+                }
+            });
+            Ok((adapter, device, queue))
         }
-        Ok(tuple) => (adapter, tuple.0, tuple.1),
     }
 }
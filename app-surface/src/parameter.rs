@@ -0,0 +1,328 @@
+use crate::ParameterError;
+use std::fmt::Write;
+
+/// 参数的类型，在 C 端是一个 32 位的枚举
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParameterType {
+    Float = 0,
+    Int,
+    Bool,
+    Color,
+    Enum,
+}
+
+impl TryFrom<i32> for ParameterType {
+    type Error = i32;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        Ok(match value {
+            0 => ParameterType::Float,
+            1 => ParameterType::Int,
+            2 => ParameterType::Bool,
+            3 => ParameterType::Color,
+            4 => ParameterType::Enum,
+            _ => return Err(value),
+        })
+    }
+}
+
+impl ParameterType {
+    /// 在 JSON 描述中的名称
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ParameterType::Float => "float",
+            ParameterType::Int => "int",
+            ParameterType::Bool => "bool",
+            ParameterType::Color => "color",
+            ParameterType::Enum => "enum",
+        }
+    }
+}
+
+/// 示例参数的值
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ParameterValue {
+    Float(f32),
+    Int(i32),
+    Bool(bool),
+    /// RGBA，各分量在 [0, 1] 之间
+    Color([f32; 4]),
+    /// 选项的序号
+    Enum(u32),
+}
+
+impl ParameterValue {
+    pub fn ty(&self) -> ParameterType {
+        match self {
+            ParameterValue::Float(_) => ParameterType::Float,
+            ParameterValue::Int(_) => ParameterType::Int,
+            ParameterValue::Bool(_) => ParameterType::Bool,
+            ParameterValue::Color(_) => ParameterType::Color,
+            ParameterValue::Enum(_) => ParameterType::Enum,
+        }
+    }
+
+    fn write_json(&self, json: &mut String) {
+        match self {
+            ParameterValue::Float(value) => write_json_f32(json, *value),
+            ParameterValue::Int(value) => _ = write!(json, "{value}"),
+            ParameterValue::Bool(value) => _ = write!(json, "{value}"),
+            ParameterValue::Color(rgba) => {
+                json.push('[');
+                for (i, c) in rgba.iter().enumerate() {
+                    if i > 0 {
+                        json.push(',');
+                    }
+                    write_json_f32(json, *c);
+                }
+                json.push(']');
+            }
+            ParameterValue::Enum(index) => _ = write!(json, "{index}"),
+        }
+    }
+}
+
+/// JSON 不能表示 NaN 与无穷大，写为 `null`
+fn write_json_f32(json: &mut String, value: f32) {
+    if value.is_finite() {
+        _ = write!(json, "{value}");
+    } else {
+        json.push_str("null");
+    }
+}
+
+/// 参数的取值范围
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ParameterKind {
+    /// `step` 只用于宿主 App 生成滑块
+    Float {
+        min: f32,
+        max: f32,
+        step: f32,
+    },
+    Int {
+        min: i32,
+        max: i32,
+    },
+    Bool,
+    Color,
+    Enum {
+        options: &'static [&'static str],
+    },
+}
+
+/// 示例声明的一个可调参数
+///
+/// 宿主 App 可以据此自动生成设置界面，`name` 用于读写，`label` 用于显示
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ParameterInfo {
+    pub name: &'static str,
+    pub label: &'static str,
+    pub kind: ParameterKind,
+    pub default: ParameterValue,
+}
+
+impl ParameterInfo {
+    pub const fn float(
+        name: &'static str,
+        label: &'static str,
+        default: f32,
+        min: f32,
+        max: f32,
+        step: f32,
+    ) -> Self {
+        Self {
+            name,
+            label,
+            kind: ParameterKind::Float { min, max, step },
+            default: ParameterValue::Float(default),
+        }
+    }
+
+    pub const fn int(
+        name: &'static str,
+        label: &'static str,
+        default: i32,
+        min: i32,
+        max: i32,
+    ) -> Self {
+        Self {
+            name,
+            label,
+            kind: ParameterKind::Int { min, max },
+            default: ParameterValue::Int(default),
+        }
+    }
+
+    pub const fn bool(name: &'static str, label: &'static str, default: bool) -> Self {
+        Self {
+            name,
+            label,
+            kind: ParameterKind::Bool,
+            default: ParameterValue::Bool(default),
+        }
+    }
+
+    pub const fn color(name: &'static str, label: &'static str, default: [f32; 4]) -> Self {
+        Self {
+            name,
+            label,
+            kind: ParameterKind::Color,
+            default: ParameterValue::Color(default),
+        }
+    }
+
+    pub const fn enumeration(
+        name: &'static str,
+        label: &'static str,
+        default: u32,
+        options: &'static [&'static str],
+    ) -> Self {
+        Self {
+            name,
+            label,
+            kind: ParameterKind::Enum { options },
+            default: ParameterValue::Enum(default),
+        }
+    }
+
+    pub fn ty(&self) -> ParameterType {
+        self.default.ty()
+    }
+
+    /// 检查值的类型，并把数值限制在取值范围内
+    pub fn validate(&self, value: ParameterValue) -> Result<ParameterValue, ParameterError> {
+        let invalid = || ParameterError::InvalidValue {
+            name: self.name.to_string(),
+            value,
+        };
+        match (self.kind, value) {
+            (ParameterKind::Float { min, max, .. }, ParameterValue::Float(v)) => {
+                if !v.is_finite() {
+                    return Err(invalid());
+                }
+                Ok(ParameterValue::Float(v.clamp(min, max)))
+            }
+            (ParameterKind::Int { min, max }, ParameterValue::Int(v)) => {
+                Ok(ParameterValue::Int(v.clamp(min, max)))
+            }
+            (ParameterKind::Bool, ParameterValue::Bool(_)) => Ok(value),
+            (ParameterKind::Color, ParameterValue::Color(rgba)) => {
+                if rgba.iter().any(|c| !c.is_finite()) {
+                    return Err(invalid());
+                }
+                Ok(ParameterValue::Color(rgba.map(|c| c.clamp(0.0, 1.0))))
+            }
+            (ParameterKind::Enum { options }, ParameterValue::Enum(index)) => {
+                if index as usize >= options.len() {
+                    return Err(invalid());
+                }
+                Ok(value)
+            }
+            _ => Err(ParameterError::TypeMismatch {
+                name: self.name.to_string(),
+                expected: self.ty(),
+                found: value.ty(),
+            }),
+        }
+    }
+
+    /// 编码为 JSON 对象，`value` 为参数的当前值
+    pub fn to_json(&self, value: ParameterValue) -> String {
+        let mut json = String::with_capacity(128);
+        json.push_str("{\"name\":");
+        write_json_string(&mut json, self.name);
+        json.push_str(",\"label\":");
+        write_json_string(&mut json, self.label);
+        _ = write!(json, ",\"type\":\"{}\"", self.ty().as_str());
+        match self.kind {
+            ParameterKind::Float { min, max, step } => {
+                for (key, value) in [("min", min), ("max", max), ("step", step)] {
+                    _ = write!(json, ",\"{key}\":");
+                    write_json_f32(&mut json, value);
+                }
+            }
+            ParameterKind::Int { min, max } => _ = write!(json, ",\"min\":{min},\"max\":{max}"),
+            ParameterKind::Enum { options } => {
+                json.push_str(",\"options\":[");
+                for (i, option) in options.iter().enumerate() {
+                    if i > 0 {
+                        json.push(',');
+                    }
+                    write_json_string(&mut json, option);
+                }
+                json.push(']');
+            }
+            ParameterKind::Bool | ParameterKind::Color => {}
+        }
+        json.push_str(",\"default\":");
+        self.default.write_json(&mut json);
+        json.push_str(",\"value\":");
+        value.write_json(&mut json);
+        json.push('}');
+        json
+    }
+}
+
+/// 把 `value` 编码为 JSON 字符串（含引号）追加到 `json` 末尾
+pub fn write_json_string(json: &mut String, value: &str) {
+    json.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            c if (c as u32) < 0x20 => _ = write!(json, "\\u{:04x}", c as u32),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+/// `ParameterValue` 的 C 兼容编码
+///
+/// `Float` 存于 `float_values[0]`，`Color` 占用全部 4 个 `float_values`，
+/// `Int`、`Bool`（0 或 1）与 `Enum` 存于 `int_value`
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RawParameterValue {
+    /// `ParameterType` 的取值；宿主 App 可能传入任意整数，所以不直接使用枚举类型
+    pub ty: i32,
+    pub int_value: i32,
+    pub float_values: [f32; 4],
+}
+
+impl From<ParameterValue> for RawParameterValue {
+    fn from(value: ParameterValue) -> Self {
+        let mut raw = Self {
+            ty: value.ty() as i32,
+            int_value: 0,
+            float_values: [0.0; 4],
+        };
+        match value {
+            ParameterValue::Float(v) => raw.float_values[0] = v,
+            ParameterValue::Int(v) => raw.int_value = v,
+            ParameterValue::Bool(v) => raw.int_value = v as i32,
+            ParameterValue::Color(rgba) => raw.float_values = rgba,
+            ParameterValue::Enum(index) => raw.int_value = index as i32,
+        }
+        raw
+    }
+}
+
+/// `ty` 不是有效的 `ParameterType` 时返回 `Err(ty)`
+impl TryFrom<RawParameterValue> for ParameterValue {
+    type Error = i32;
+
+    fn try_from(raw: RawParameterValue) -> Result<Self, Self::Error> {
+        Ok(match ParameterType::try_from(raw.ty)? {
+            ParameterType::Float => ParameterValue::Float(raw.float_values[0]),
+            ParameterType::Int => ParameterValue::Int(raw.int_value),
+            ParameterType::Bool => ParameterValue::Bool(raw.int_value != 0),
+            ParameterType::Color => ParameterValue::Color(raw.float_values),
+            ParameterType::Enum => {
+                ParameterValue::Enum(u32::try_from(raw.int_value).unwrap_or(u32::MAX))
+            }
+        })
+    }
+}
@@ -0,0 +1,104 @@
+use crate::IASDQContext;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// 缓存文件开头保存的适配器与驱动指纹以换行结尾，之后是 `PipelineCache::get_data` 的数据
+const FINGERPRINT_END: u8 = b'\n';
+
+impl IASDQContext {
+    /// 从 `directory` 加载（或新建）管线缓存，之后可通过 `pipeline_cache` 字段传给管线描述符
+    ///
+    /// 只有 Device 开启了 `Features::PIPELINE_CACHE` 且后端支持时才会创建缓存，否则返回 `Ok(false)`；
+    /// 文件中的适配器或驱动指纹与当前不一致时丢弃旧数据。
+    /// 缓存数据会被直接交给驱动，`directory` 须为只有当前用户（或 App 自己）可写入的目录
+    pub fn load_pipeline_cache(&mut self, directory: impl AsRef<Path>) -> io::Result<bool> {
+        if !self
+            .granted_features
+            .contains(wgpu::Features::PIPELINE_CACHE)
+        {
+            return Ok(false);
+        }
+        let adapter_info = self.adapter.get_info();
+        let Some(key) = wgpu::util::pipeline_cache_key(&adapter_info) else {
+            return Ok(false);
+        };
+        std::fs::create_dir_all(directory.as_ref())?;
+        let file = directory.as_ref().join(key);
+
+        let fingerprint = fingerprint(&adapter_info);
+        let data = match std::fs::read(&file) {
+            Ok(bytes) => match split_fingerprint(&bytes) {
+                Some((saved, data)) if saved == fingerprint.as_bytes() => Some(data.to_vec()),
+                _ => {
+                    log::info!("Pipeline cache fingerprint changed, discarding {file:?}");
+                    None
+                }
+            },
+            Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+            Err(err) => return Err(err),
+        };
+
+        // SAFETY: 调用方保证其他用户无法写入 `directory`，所以数据只来自
+        // `save_pipeline_cache` 之前用同一适配器与驱动保存的文件，
+        // 且 `fallback: true` 让 wgpu 在数据校验失败时退回空缓存
+        let cache = unsafe {
+            self.device
+                .create_pipeline_cache(&wgpu::PipelineCacheDescriptor {
+                    label: Some("app-surface pipeline cache"),
+                    data: data.as_deref(),
+                    fallback: true,
+                })
+        };
+        self.pipeline_cache = Some(cache);
+        self.pipeline_cache_file = Some(file);
+        Ok(true)
+    }
+
+    /// 把管线缓存写回 `load_pipeline_cache` 时的目录，没有加载过缓存时不做任何事
+    ///
+    /// 先写入临时文件再重命名，避免 App 在写入过程中被终止而留下损坏的缓存
+    pub fn save_pipeline_cache(&self) -> io::Result<()> {
+        let (Some(cache), Some(file)) = (&self.pipeline_cache, &self.pipeline_cache_file) else {
+            return Ok(());
+        };
+        let Some(data) = cache.get_data() else {
+            return Ok(());
+        };
+        let mut bytes = fingerprint(&self.adapter.get_info()).into_bytes();
+        bytes.push(FINGERPRINT_END);
+        bytes.extend_from_slice(&data);
+
+        let temp_file = file.with_extension("temp");
+        std::fs::write(&temp_file, &bytes)?;
+        std::fs::rename(&temp_file, file)
+    }
+
+    /// 设备恢复后旧缓存属于已失效的 device，需要重新加载
+    pub(crate) fn reload_pipeline_cache(&mut self) {
+        self.pipeline_cache = None;
+        let Some(directory) = self
+            .pipeline_cache_file
+            .take()
+            .and_then(|file| file.parent().map(PathBuf::from))
+        else {
+            return;
+        };
+        if let Err(err) = self.load_pipeline_cache(directory) {
+            log::warn!("Failed to reload pipeline cache: {err}");
+        }
+    }
+}
+
+/// `pipeline_cache_key` 只区分厂商与设备，这里再加上驱动信息，驱动升级后旧缓存随之失效
+fn fingerprint(info: &wgpu::AdapterInfo) -> String {
+    format!(
+        "{:?}|{}|{}|{}|{}|{}",
+        info.backend, info.vendor, info.device, info.name, info.driver, info.driver_info
+    )
+    .replace(FINGERPRINT_END as char, " ")
+}
+
+fn split_fingerprint(bytes: &[u8]) -> Option<(&[u8], &[u8])> {
+    let end = bytes.iter().position(|&b| b == FINGERPRINT_END)?;
+    Some((&bytes[..end], &bytes[end + 1..]))
+}
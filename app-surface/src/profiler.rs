@@ -0,0 +1,742 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::SharedPtr;
+
+/// 每帧最多写入的时间戳数量，每个计时的通道占用两个
+const MAX_QUERIES_PER_FRAME: u32 = 128;
+
+/// 等待读回结果的帧数上限，超出后新的帧只统计 CPU 时间
+const MAX_FRAMES_IN_FLIGHT: usize = 4;
+
+/// `ProfileEntry::label` 的字节数，含末尾的 `\0`
+pub const PROFILE_LABEL_LEN: usize = 32;
+
+const MAP_IDLE: u8 = 0;
+const MAP_PENDING: u8 = 1;
+const MAP_READY: u8 = 2;
+const MAP_FAILED: u8 = 3;
+
+/// 一个作用域的耗时，时间单位均为毫秒
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ProfileScope {
+    pub label: String,
+    /// 录制命令花费的 CPU 时间
+    pub cpu_ms: f32,
+    /// 设备不支持 `TIMESTAMP_QUERY`，或作用域内没有写入时间戳时为 `None`
+    ///
+    /// 作用域自身没有计时的通道时，取子作用域 GPU 时间的跨度
+    pub gpu_ms: Option<f32>,
+    pub children: Vec<ProfileScope>,
+}
+
+/// 一帧中所有作用域的耗时树
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ProfileFrame {
+    pub frame_index: u64,
+    pub scopes: Vec<ProfileScope>,
+}
+
+impl ProfileFrame {
+    /// 按 `/` 分隔的标签路径查找作用域，如 `"shadow/light 0"`
+    pub fn find(&self, path: &str) -> Option<&ProfileScope> {
+        let mut scopes = &self.scopes;
+        let mut found = None;
+        for label in path.split('/') {
+            let scope = scopes.iter().find(|scope| scope.label == label)?;
+            scopes = &scope.children;
+            found = Some(scope);
+        }
+        found
+    }
+
+    pub fn total_cpu_ms(&self) -> f32 {
+        self.scopes.iter().map(|scope| scope.cpu_ms).sum()
+    }
+
+    /// 顶层作用域的 GPU 时间之和，任一顶层作用域没有 GPU 时间时返回 `None`
+    pub fn total_gpu_ms(&self) -> Option<f32> {
+        if self.scopes.is_empty() {
+            return None;
+        }
+        self.scopes.iter().map(|scope| scope.gpu_ms).sum()
+    }
+
+    /// 按深度优先的顺序展开为扁平的列表，用于通过 FFI 传给宿主 App
+    pub fn entries(&self) -> Vec<ProfileEntry> {
+        let mut entries = vec![];
+        for scope in &self.scopes {
+            push_entries(scope, 0, &mut entries);
+        }
+        entries
+    }
+}
+
+fn push_entries(scope: &ProfileScope, depth: u32, entries: &mut Vec<ProfileEntry>) {
+    entries.push(ProfileEntry::new(scope, depth));
+    for child in &scope.children {
+        push_entries(child, depth + 1, entries);
+    }
+}
+
+impl fmt::Display for ProfileFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "frame {}", self.frame_index)?;
+        for scope in &self.scopes {
+            write_scope(f, scope, 1)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_scope(f: &mut fmt::Formatter<'_>, scope: &ProfileScope, depth: usize) -> fmt::Result {
+    write!(
+        f,
+        "\n{:indent$}{}: cpu {:.3} ms",
+        "",
+        scope.label,
+        scope.cpu_ms,
+        indent = depth * 2
+    )?;
+    match scope.gpu_ms {
+        Some(gpu_ms) => write!(f, ", gpu {gpu_ms:.3} ms")?,
+        None => write!(f, ", gpu -")?,
+    }
+    for child in &scope.children {
+        write_scope(f, child, depth + 1)?;
+    }
+    Ok(())
+}
+
+/// `ProfileFrame::entries` 展开后的一项，内存布局固定，可直接传给宿主 App
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ProfileEntry {
+    /// UTF-8 编码并以 `\0` 结尾，过长时在字符边界处截断
+    pub label: [u8; PROFILE_LABEL_LEN],
+    /// 顶层作用域为 0
+    pub depth: u32,
+    pub cpu_ms: f32,
+    /// 没有 GPU 时间时为 -1
+    pub gpu_ms: f32,
+}
+
+// Swift 与 Kotlin 端按这个大小读取
+const _: () = assert!(core::mem::size_of::<ProfileEntry>() == 44);
+
+impl ProfileEntry {
+    fn new(scope: &ProfileScope, depth: u32) -> Self {
+        let mut label = [0; PROFILE_LABEL_LEN];
+        let mut len = scope.label.len().min(PROFILE_LABEL_LEN - 1);
+        while !scope.label.is_char_boundary(len) {
+            len -= 1;
+        }
+        label[..len].copy_from_slice(&scope.label.as_bytes()[..len]);
+        Self {
+            label,
+            depth,
+            cpu_ms: scope.cpu_ms,
+            gpu_ms: scope.gpu_ms.unwrap_or(-1.0),
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        let len = self
+            .label
+            .iter()
+            .position(|&byte| byte == 0)
+            .unwrap_or(PROFILE_LABEL_LEN);
+        core::str::from_utf8(&self.label[..len]).unwrap_or_default()
+    }
+}
+
+/// 按通道统计耗时的分析器
+///
+/// 设备支持 `TIMESTAMP_QUERY` 时通过时间戳查询统计每个通道的 GPU 耗时，否则只统计录制命令的 CPU 耗时。
+/// 时间戳在之后的帧中异步读回，不会阻塞渲染，因此 `latest` 返回的总是几帧之前的结果
+///
+/// 每帧的使用步骤：
+/// 1. `SurfaceFrame::enter_frame` 中自动调用 `begin_frame`
+/// 2. 用 `scope` 包住要统计的通道，把作用域的 `render_pass_timestamp_writes` 传给通道描述符
+/// 3. 提交最后一个 encoder 之前调用 `resolve_queries`
+#[derive(Clone)]
+pub struct GpuProfiler {
+    state: SharedPtr<Mutex<ProfilerState>>,
+}
+
+struct ProfilerState {
+    device: wgpu::Device,
+    enabled: bool,
+    timestamp_supported: bool,
+    /// 每个时间戳刻度对应的纳秒数
+    timestamp_period: f32,
+    frame_index: u64,
+    current: Option<FrameRecord>,
+    /// 已结束、等待读回时间戳的帧，按帧的先后排列
+    pending: VecDeque<FrameRecord>,
+    /// 可以复用的查询集与缓冲
+    query_pool: Vec<QueryFrame>,
+    query_frame_count: usize,
+    latest: Option<ProfileFrame>,
+}
+
+struct QueryFrame {
+    query_set: wgpu::QuerySet,
+    resolve_buffer: wgpu::Buffer,
+    readback_buffer: wgpu::Buffer,
+    /// 由 map_async 回调更新，回调可能在任意线程上触发
+    map_state: Arc<AtomicU8>,
+}
+
+struct FrameRecord {
+    index: u64,
+    scopes: Vec<ScopeRecord>,
+    /// 尚未结束的作用域，栈顶是新作用域的父作用域
+    stack: Vec<usize>,
+    queries: Option<QueryFrame>,
+    /// 已分配的时间戳数量
+    query_count: u32,
+    /// 调用 `resolve_queries` 时已分配的时间戳数量，之后分配的时间戳不会被读回
+    resolved_count: u32,
+}
+
+struct ScopeRecord {
+    label: String,
+    parent: Option<usize>,
+    cpu_begin: f64,
+    cpu_end: Option<f64>,
+    /// 通道起始时间戳的索引，结束时间戳紧随其后
+    query: Option<u32>,
+}
+
+impl GpuProfiler {
+    /// 创建后默认不启用
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> Self {
+        Self {
+            state: SharedPtr::new(Mutex::new(ProfilerState::new(device, queue, false))),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.state.lock().unwrap().enabled
+    }
+
+    /// 停用后仍会读回已提交帧的结果
+    pub fn set_enabled(&self, enabled: bool) {
+        self.state.lock().unwrap().enabled = enabled;
+    }
+
+    /// 是否能统计 GPU 时间
+    pub fn has_gpu_timestamps(&self) -> bool {
+        self.state.lock().unwrap().timestamp_supported
+    }
+
+    /// 结束上一帧并开始统计新的一帧，同时收集已读回的结果
+    pub fn begin_frame(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.end_frame();
+        state.collect_finished();
+        if state.enabled {
+            state.start_frame();
+        }
+    }
+
+    /// 开始一个作用域，作用域在返回值被 drop 时结束
+    ///
+    /// 作用域之间可以嵌套，未启用时返回的作用域不做任何统计
+    pub fn scope(&self, label: impl Into<String>) -> ProfilerScope {
+        let mut state = self.state.lock().unwrap();
+        let Some(frame) = state.current.as_mut() else {
+            return ProfilerScope::inactive();
+        };
+        let index = frame.scopes.len();
+        frame.scopes.push(ScopeRecord {
+            label: label.into(),
+            parent: frame.stack.last().copied(),
+            cpu_begin: now_ms(),
+            cpu_end: None,
+            query: None,
+        });
+        frame.stack.push(index);
+        ProfilerScope {
+            profiler: Some(self.clone()),
+            frame_index: frame.index,
+            index,
+            query_set: frame
+                .queries
+                .as_ref()
+                .map(|queries| queries.query_set.clone()),
+            query: None,
+        }
+    }
+
+    /// 把本帧已写入的时间戳解析到缓冲，需在提交最后一个 encoder 之前调用
+    pub fn resolve_queries(&self, encoder: &mut wgpu::CommandEncoder) {
+        let mut state = self.state.lock().unwrap();
+        let Some(frame) = state.current.as_mut() else {
+            return;
+        };
+        let Some(queries) = frame.queries.as_ref() else {
+            return;
+        };
+        if frame.query_count == 0 {
+            return;
+        }
+        encoder.resolve_query_set(
+            &queries.query_set,
+            0..frame.query_count,
+            &queries.resolve_buffer,
+            0,
+        );
+        encoder.copy_buffer_to_buffer(
+            &queries.resolve_buffer,
+            0,
+            &queries.readback_buffer,
+            0,
+            frame.query_count as u64 * wgpu::QUERY_SIZE as u64,
+        );
+        frame.resolved_count = frame.query_count;
+    }
+
+    /// 最近一个已读回全部结果的帧
+    pub fn latest(&self) -> Option<ProfileFrame> {
+        self.state.lock().unwrap().latest.clone()
+    }
+
+    /// 设备丢失并恢复后，丢弃旧 device 上的查询集与缓冲
+    pub(crate) fn reset_device(&self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        let mut state = self.state.lock().unwrap();
+        *state = ProfilerState::new(device, queue, state.enabled);
+    }
+
+    fn allocate_query(&self, frame_index: u64, scope: usize) -> Option<u32> {
+        let mut state = self.state.lock().unwrap();
+        let frame = state.current.as_mut()?;
+        if frame.index != frame_index || frame.query_count + 2 > MAX_QUERIES_PER_FRAME {
+            return None;
+        }
+        let query = frame.query_count;
+        frame.query_count += 2;
+        frame.scopes[scope].query = Some(query);
+        Some(query)
+    }
+
+    fn end_scope(&self, frame_index: u64, scope: usize) {
+        let mut state = self.state.lock().unwrap();
+        let Some(frame) = state.current.as_mut() else {
+            return;
+        };
+        if frame.index != frame_index {
+            return;
+        }
+        let Some(position) = frame.stack.iter().rposition(|&index| index == scope) else {
+            return;
+        };
+        // 子作用域先于父作用域结束，否则一并结束栈上更内层的作用域
+        let end = now_ms();
+        for index in frame.stack.drain(position..) {
+            frame.scopes[index].cpu_end = Some(end);
+        }
+    }
+}
+
+impl ProfilerState {
+    fn new(device: &wgpu::Device, queue: &wgpu::Queue, enabled: bool) -> Self {
+        Self {
+            device: device.clone(),
+            enabled,
+            timestamp_supported: device.features().contains(wgpu::Features::TIMESTAMP_QUERY),
+            timestamp_period: queue.get_timestamp_period(),
+            frame_index: 0,
+            current: None,
+            pending: VecDeque::new(),
+            query_pool: vec![],
+            query_frame_count: 0,
+            latest: None,
+        }
+    }
+
+    fn start_frame(&mut self) {
+        self.frame_index += 1;
+        self.current = Some(FrameRecord {
+            index: self.frame_index,
+            scopes: vec![],
+            stack: vec![],
+            queries: self.acquire_query_frame(),
+            query_count: 0,
+            resolved_count: 0,
+        });
+    }
+
+    /// 上一帧的命令此时都已提交，可以请求映射读回缓冲
+    fn end_frame(&mut self) {
+        let Some(mut frame) = self.current.take() else {
+            return;
+        };
+        let end = now_ms();
+        for index in frame.stack.drain(..) {
+            frame.scopes[index].cpu_end = Some(end);
+        }
+        if let Some(ref queries) = frame.queries
+            && frame.resolved_count > 0
+        {
+            let map_state = queries.map_state.clone();
+            map_state.store(MAP_PENDING, Ordering::Release);
+            queries
+                .readback_buffer
+                .slice(..frame.resolved_count as u64 * wgpu::QUERY_SIZE as u64)
+                .map_async(wgpu::MapMode::Read, move |result| {
+                    let value = if result.is_ok() {
+                        MAP_READY
+                    } else {
+                        MAP_FAILED
+                    };
+                    map_state.store(value, Ordering::Release);
+                });
+        }
+        self.pending.push_back(frame);
+    }
+
+    fn collect_finished(&mut self) {
+        if self.pending.is_empty() {
+            return;
+        }
+        // 只推进映射回调，不等待 GPU
+        _ = self.device.poll(wgpu::PollType::Poll);
+
+        while let Some(frame) = self.pending.front() {
+            let map_state = match frame.queries {
+                Some(ref queries) if frame.resolved_count > 0 => {
+                    queries.map_state.load(Ordering::Acquire)
+                }
+                _ => MAP_IDLE,
+            };
+            if map_state == MAP_PENDING {
+                break;
+            }
+            let mut frame = self.pending.pop_front().unwrap();
+            let ticks = match frame.queries {
+                Some(ref queries) if map_state == MAP_READY => {
+                    let ticks = read_ticks(queries, frame.resolved_count);
+                    queries.readback_buffer.unmap();
+                    ticks
+                }
+                _ => vec![],
+            };
+            if let Some(queries) = frame.queries.take() {
+                queries.map_state.store(MAP_IDLE, Ordering::Release);
+                self.query_pool.push(queries);
+            }
+            self.latest = Some(frame.build(&ticks, self.timestamp_period));
+        }
+    }
+
+    fn acquire_query_frame(&mut self) -> Option<QueryFrame> {
+        if !self.timestamp_supported {
+            return None;
+        }
+        if let Some(queries) = self.query_pool.pop() {
+            return Some(queries);
+        }
+        if self.query_frame_count >= MAX_FRAMES_IN_FLIGHT {
+            return None;
+        }
+        self.query_frame_count += 1;
+        let size = MAX_QUERIES_PER_FRAME as u64 * wgpu::QUERY_SIZE as u64;
+        Some(QueryFrame {
+            query_set: self.device.create_query_set(&wgpu::QuerySetDescriptor {
+                label: Some("profiler query set"),
+                ty: wgpu::QueryType::Timestamp,
+                count: MAX_QUERIES_PER_FRAME,
+            }),
+            resolve_buffer: self.device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("profiler resolve buffer"),
+                size,
+                usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+                mapped_at_creation: false,
+            }),
+            readback_buffer: self.device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("profiler readback buffer"),
+                size,
+                usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+                mapped_at_creation: false,
+            }),
+            map_state: Arc::new(AtomicU8::new(MAP_IDLE)),
+        })
+    }
+}
+
+fn read_ticks(queries: &QueryFrame, count: u32) -> Vec<u64> {
+    let mapped = queries
+        .readback_buffer
+        .slice(..count as u64 * wgpu::QUERY_SIZE as u64)
+        .get_mapped_range();
+    mapped
+        .chunks_exact(wgpu::QUERY_SIZE as usize)
+        .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+        .collect()
+}
+
+impl FrameRecord {
+    /// `ticks` 为读回的时间戳，为空表示没有 GPU 时间
+    fn build(&self, ticks: &[u64], timestamp_period: f32) -> ProfileFrame {
+        // 子作用域的索引总是大于父作用域，逆序遍历即可先得到子作用域的 GPU 时间跨度
+        let mut spans: Vec<Option<(u64, u64)>> = vec![None; self.scopes.len()];
+        for (index, scope) in self.scopes.iter().enumerate().rev() {
+            if let Some(query) = scope.query
+                && query + 1 < self.resolved_count
+                && let (Some(&begin), Some(&end)) =
+                    (ticks.get(query as usize), ticks.get(query as usize + 1))
+                && end >= begin
+            {
+                spans[index] = Some((begin, end));
+            }
+            if let (Some(parent), Some((begin, end))) = (scope.parent, spans[index]) {
+                // 父作用域自身写入的时间戳会在之后覆盖这里的跨度
+                spans[parent] = Some(match spans[parent] {
+                    Some((parent_begin, parent_end)) => {
+                        (parent_begin.min(begin), parent_end.max(end))
+                    }
+                    None => (begin, end),
+                });
+            }
+        }
+
+        let to_ms = |(begin, end): (u64, u64)| {
+            ((end - begin) as f64 * timestamp_period as f64 / 1_000_000.0) as f32
+        };
+        let mut nodes: Vec<Option<ProfileScope>> = self
+            .scopes
+            .iter()
+            .zip(&spans)
+            .map(|(scope, span)| {
+                Some(ProfileScope {
+                    label: scope.label.clone(),
+                    cpu_ms: scope
+                        .cpu_end
+                        .map_or(0.0, |end| (end - scope.cpu_begin) as f32),
+                    gpu_ms: span.map(to_ms),
+                    children: vec![],
+                })
+            })
+            .collect();
+        let mut scopes = vec![];
+        for index in (0..self.scopes.len()).rev() {
+            let node = nodes[index].take().unwrap();
+            match self.scopes[index].parent {
+                Some(parent) => nodes[parent].as_mut().unwrap().children.insert(0, node),
+                None => scopes.insert(0, node),
+            }
+        }
+        ProfileFrame {
+            frame_index: self.index,
+            scopes,
+        }
+    }
+}
+
+/// `GpuProfiler::scope` 返回的作用域，drop 时结束
+pub struct ProfilerScope {
+    profiler: Option<GpuProfiler>,
+    frame_index: u64,
+    index: usize,
+    query_set: Option<wgpu::QuerySet>,
+    query: Option<u32>,
+}
+
+impl ProfilerScope {
+    fn inactive() -> Self {
+        Self {
+            profiler: None,
+            frame_index: 0,
+            index: 0,
+            query_set: None,
+            query: None,
+        }
+    }
+
+    /// 记录渲染通道 GPU 耗时的时间戳写入位置
+    ///
+    /// 每个作用域只能为一个通道计时，不能计时时返回 `None`
+    pub fn render_pass_timestamp_writes(&mut self) -> Option<wgpu::RenderPassTimestampWrites<'_>> {
+        let query = self.allocate_query()?;
+        Some(wgpu::RenderPassTimestampWrites {
+            query_set: self.query_set.as_ref()?,
+            beginning_of_pass_write_index: Some(query),
+            end_of_pass_write_index: Some(query + 1),
+        })
+    }
+
+    /// 记录计算通道 GPU 耗时的时间戳写入位置
+    ///
+    /// 每个作用域只能为一个通道计时，不能计时时返回 `None`
+    pub fn compute_pass_timestamp_writes(
+        &mut self,
+    ) -> Option<wgpu::ComputePassTimestampWrites<'_>> {
+        let query = self.allocate_query()?;
+        Some(wgpu::ComputePassTimestampWrites {
+            query_set: self.query_set.as_ref()?,
+            beginning_of_pass_write_index: Some(query),
+            end_of_pass_write_index: Some(query + 1),
+        })
+    }
+
+    fn allocate_query(&mut self) -> Option<u32> {
+        if self.query.is_some() || self.query_set.is_none() {
+            return None;
+        }
+        let query = self
+            .profiler
+            .as_ref()?
+            .allocate_query(self.frame_index, self.index)?;
+        self.query = Some(query);
+        Some(query)
+    }
+}
+
+impl Drop for ProfilerScope {
+    fn drop(&mut self) {
+        if let Some(ref profiler) = self.profiler {
+            profiler.end_scope(self.frame_index, self.index);
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn now_ms() -> f64 {
+    use std::sync::OnceLock;
+    static START: OnceLock<std::time::Instant> = OnceLock::new();
+    START
+        .get_or_init(std::time::Instant::now)
+        .elapsed()
+        .as_secs_f64()
+        * 1000.0
+}
+
+// wasm32-unknown-unknown 上没有 std::time::Instant 的实现
+#[cfg(target_arch = "wasm32")]
+fn now_ms() -> f64 {
+    web_sys::window()
+        .and_then(|window| window.performance())
+        .map(|performance| performance.now())
+        .unwrap_or(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 时间戳刻度为 1 毫秒，便于直接读出 GPU 时间
+    const MS_PERIOD: f32 = 1_000_000.0;
+
+    fn scope(label: &str, parent: Option<usize>, query: Option<u32>) -> ScopeRecord {
+        ScopeRecord {
+            label: label.to_string(),
+            parent,
+            cpu_begin: 1.0,
+            cpu_end: Some(1.5),
+            query,
+        }
+    }
+
+    fn record(scopes: Vec<ScopeRecord>, resolved_count: u32) -> FrameRecord {
+        FrameRecord {
+            index: 7,
+            scopes,
+            stack: vec![],
+            queries: None,
+            query_count: resolved_count,
+            resolved_count,
+        }
+    }
+
+    fn gpu_ms(frame: &ProfileFrame, path: &str) -> Option<f32> {
+        frame.find(path).unwrap().gpu_ms
+    }
+
+    #[test]
+    fn untimed_parents_span_their_children() {
+        let record = record(
+            vec![
+                scope("frame", None, None),
+                scope("shadow", Some(0), None),
+                scope("light 0", Some(1), Some(0)),
+                scope("light 1", Some(1), Some(2)),
+                scope("forward", Some(0), Some(4)),
+            ],
+            6,
+        );
+        let frame = record.build(&[10, 11, 12, 14, 20, 25], MS_PERIOD);
+
+        assert_eq!(frame.frame_index, 7);
+        assert_eq!(frame.scopes.len(), 1);
+        let labels: Vec<&str> = frame.scopes[0]
+            .children
+            .iter()
+            .map(|scope| scope.label.as_str())
+            .collect();
+        assert_eq!(labels, ["shadow", "forward"]);
+        assert_eq!(gpu_ms(&frame, "frame/shadow/light 0"), Some(1.0));
+        assert_eq!(gpu_ms(&frame, "frame/shadow/light 1"), Some(2.0));
+        // 从 light 0 开始到 light 1 结束
+        assert_eq!(gpu_ms(&frame, "frame/shadow"), Some(4.0));
+        assert_eq!(gpu_ms(&frame, "frame"), Some(15.0));
+        assert_eq!(frame.find("frame").unwrap().cpu_ms, 0.5);
+    }
+
+    #[test]
+    fn timed_parents_keep_their_own_span() {
+        let record = record(
+            vec![
+                scope("shadow", None, Some(0)),
+                scope("light 0", Some(0), Some(2)),
+            ],
+            4,
+        );
+        let frame = record.build(&[10, 13, 0, 100], MS_PERIOD);
+        assert_eq!(gpu_ms(&frame, "shadow"), Some(3.0));
+        assert_eq!(gpu_ms(&frame, "shadow/light 0"), Some(100.0));
+    }
+
+    #[test]
+    fn queries_after_resolve_are_ignored() {
+        // light 1 的时间戳在 resolve_queries 之后才分配，读回的数据不可信
+        let record = record(
+            vec![
+                scope("shadow", None, None),
+                scope("light 0", Some(0), Some(0)),
+                scope("light 1", Some(0), Some(2)),
+            ],
+            2,
+        );
+        let frame = record.build(&[10, 11, 0, 100], MS_PERIOD);
+        assert_eq!(gpu_ms(&frame, "shadow/light 1"), None);
+        assert_eq!(gpu_ms(&frame, "shadow"), Some(1.0));
+    }
+
+    #[test]
+    fn reversed_timestamps_are_ignored() {
+        let record = record(
+            vec![
+                scope("shadow", None, None),
+                scope("light 0", Some(0), Some(0)),
+            ],
+            2,
+        );
+        let frame = record.build(&[20, 10], MS_PERIOD);
+        assert_eq!(gpu_ms(&frame, "shadow/light 0"), None);
+        assert_eq!(gpu_ms(&frame, "shadow"), None);
+    }
+
+    #[test]
+    fn missing_ticks_leave_cpu_time_only() {
+        let mut scopes = vec![scope("forward", None, Some(0))];
+        scopes[0].cpu_end = None;
+        let frame = record(scopes, 2).build(&[], MS_PERIOD);
+        let forward = frame.find("forward").unwrap();
+        assert_eq!(forward.gpu_ms, None);
+        assert_eq!(forward.cpu_ms, 0.0);
+    }
+}
@@ -0,0 +1,307 @@
+use crate::IASDQContext;
+use wgpu::TextureFormat;
+
+/// 调用方对 view_formats 的需求
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ViewFormatNeed {
+    /// 只以 surface 格式本身创建视图
+    #[default]
+    None,
+    /// 希望能以 sRGB 与线性两种方式创建视图，平台不支持时退回只用 surface 格式
+    PreferSrgbPair,
+    /// 必须能以 sRGB 与线性两种方式创建视图，平台不支持时拒绝无法满足的候选格式
+    RequireSrgbPair,
+}
+
+/// 候选格式被拒绝的原因
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FormatRejection {
+    /// surface 在当前适配器上不支持此格式
+    UnsupportedBySurface,
+    /// 格式依赖的特性没有被 Device 开启
+    MissingFeatures(wgpu::Features),
+    /// 适配器不支持将此格式用作渲染目标
+    NotRenderable,
+    /// 需要 sRGB/线性视图，但平台的 downlevel 能力不支持 surface view_formats
+    ViewFormatsUnsupported,
+}
+
+/// 格式协商的结果
+#[derive(Clone, Debug)]
+pub struct FormatNegotiation {
+    /// 排在最前面的可用候选格式，所有候选都不可用时为 `None`
+    pub format: Option<TextureFormat>,
+    /// 配合 `format` 使用的 view_formats，已按平台的 downlevel 能力裁剪
+    pub view_formats: Vec<TextureFormat>,
+    /// 排在 `format` 之前被拒绝的候选格式及原因
+    pub rejected: Vec<(TextureFormat, FormatRejection)>,
+}
+
+impl IASDQContext {
+    /// 按 `preferred` 的先后顺序选出 adapter 与 surface 都支持的最佳格式
+    ///
+    /// 只做协商，不修改 `config`；需要立即生效时使用 `apply_preferred_format`
+    pub fn negotiate_format(
+        &self,
+        preferred: &[TextureFormat],
+        view_need: ViewFormatNeed,
+    ) -> FormatNegotiation {
+        let surface_formats = self
+            .surface
+            .as_ref()
+            .map(|surface| surface.get_capabilities(&self.adapter).formats);
+        // 离屏模式下帧缓冲是普通纹理，受 VIEW_FORMATS 而不是 SURFACE_VIEW_FORMATS 限制
+        let view_formats_flag = if self.is_offscreen() {
+            wgpu::DownlevelFlags::VIEW_FORMATS
+        } else {
+            wgpu::DownlevelFlags::SURFACE_VIEW_FORMATS
+        };
+        let view_formats_supported = self
+            .adapter
+            .get_downlevel_capabilities()
+            .flags
+            .contains(view_formats_flag);
+
+        negotiate(
+            preferred,
+            view_need,
+            surface_formats.as_deref(),
+            self.granted_features,
+            view_formats_supported,
+            |format| {
+                self.adapter
+                    .get_texture_format_features(format)
+                    .allowed_usages
+                    .contains(wgpu::TextureUsages::RENDER_ATTACHMENT)
+            },
+        )
+    }
+
+    /// 协商并把选中的格式应用到 `config`，所有候选都不可用时保持原配置不变
+    pub fn apply_preferred_format(
+        &mut self,
+        preferred: &[TextureFormat],
+        view_need: ViewFormatNeed,
+    ) -> FormatNegotiation {
+        let negotiation = self.negotiate_format(preferred, view_need);
+        if let Some(format) = negotiation.format {
+            self.config.format = format;
+            self.config.view_formats = negotiation.view_formats.clone();
+            self.configure_surface();
+        }
+        negotiation
+    }
+}
+
+/// `negotiate_format` 的协商规则，拒绝原因按 surface、特性、可渲染、view_formats 的顺序检查
+///
+/// `surface_formats` 为 `None` 表示离屏模式，不受 surface 限制
+fn negotiate(
+    preferred: &[TextureFormat],
+    view_need: ViewFormatNeed,
+    surface_formats: Option<&[TextureFormat]>,
+    granted_features: wgpu::Features,
+    view_formats_supported: bool,
+    is_renderable: impl Fn(TextureFormat) -> bool,
+) -> FormatNegotiation {
+    let mut rejected = vec![];
+    for &format in preferred {
+        let srgb_pair = srgb_pair(format);
+        let rejection = match surface_formats {
+            Some(formats) if !formats.contains(&format) => {
+                Some(FormatRejection::UnsupportedBySurface)
+            }
+            _ => None,
+        }
+        .or_else(|| {
+            let missing = format.required_features() - granted_features;
+            (!missing.is_empty()).then_some(FormatRejection::MissingFeatures(missing))
+        })
+        .or_else(|| (!is_renderable(format)).then_some(FormatRejection::NotRenderable))
+        .or_else(|| {
+            let needs_view_formats = view_need == ViewFormatNeed::RequireSrgbPair
+                && srgb_pair.len() > 1
+                && !view_formats_supported;
+            needs_view_formats.then_some(FormatRejection::ViewFormatsUnsupported)
+        });
+
+        match rejection {
+            Some(reason) => rejected.push((format, reason)),
+            None => {
+                let view_formats = if view_need != ViewFormatNeed::None && view_formats_supported {
+                    srgb_pair
+                } else {
+                    vec![format]
+                };
+                return FormatNegotiation {
+                    format: Some(format),
+                    view_formats,
+                    rejected,
+                };
+            }
+        }
+    }
+
+    FormatNegotiation {
+        format: None,
+        view_formats: vec![],
+        rejected,
+    }
+}
+
+/// 格式本身及其 sRGB/线性对应格式；没有对应格式时只有其本身
+fn srgb_pair(format: TextureFormat) -> Vec<TextureFormat> {
+    let srgb = format.add_srgb_suffix();
+    let linear = format.remove_srgb_suffix();
+    if srgb == linear {
+        vec![format]
+    } else {
+        vec![format, if format == srgb { linear } else { srgb }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wgpu::{AstcBlock, AstcChannel};
+
+    const ASTC_HDR: TextureFormat = TextureFormat::Astc {
+        block: AstcBlock::B8x8,
+        channel: AstcChannel::Hdr,
+    };
+
+    #[test]
+    fn srgb_pair_lists_the_format_first() {
+        assert_eq!(
+            srgb_pair(TextureFormat::Bgra8UnormSrgb),
+            [TextureFormat::Bgra8UnormSrgb, TextureFormat::Bgra8Unorm]
+        );
+        assert_eq!(
+            srgb_pair(TextureFormat::Rgba8Unorm),
+            [TextureFormat::Rgba8Unorm, TextureFormat::Rgba8UnormSrgb]
+        );
+        assert_eq!(
+            srgb_pair(TextureFormat::Rgba16Float),
+            [TextureFormat::Rgba16Float]
+        );
+        assert_eq!(
+            srgb_pair(TextureFormat::Rgb10a2Unorm),
+            [TextureFormat::Rgb10a2Unorm]
+        );
+    }
+
+    fn negotiate_bgra(
+        view_need: ViewFormatNeed,
+        view_formats_supported: bool,
+    ) -> FormatNegotiation {
+        negotiate(
+            &[TextureFormat::Bgra8Unorm],
+            view_need,
+            Some(&[TextureFormat::Bgra8Unorm]),
+            wgpu::Features::empty(),
+            view_formats_supported,
+            |_| true,
+        )
+    }
+
+    #[test]
+    fn view_formats_follow_the_need_and_platform() {
+        let pair = [TextureFormat::Bgra8Unorm, TextureFormat::Bgra8UnormSrgb];
+        let single = [TextureFormat::Bgra8Unorm];
+        assert_eq!(
+            negotiate_bgra(ViewFormatNeed::None, true).view_formats,
+            single
+        );
+        assert_eq!(
+            negotiate_bgra(ViewFormatNeed::PreferSrgbPair, true).view_formats,
+            pair
+        );
+        assert_eq!(
+            negotiate_bgra(ViewFormatNeed::PreferSrgbPair, false).view_formats,
+            single
+        );
+        assert_eq!(
+            negotiate_bgra(ViewFormatNeed::RequireSrgbPair, true).view_formats,
+            pair
+        );
+    }
+
+    #[test]
+    fn required_pair_skips_formats_that_have_one() {
+        // Rgba16Float 没有 sRGB 对应格式，不需要 view_formats
+        let negotiation = negotiate(
+            &[TextureFormat::Bgra8Unorm, TextureFormat::Rgba16Float],
+            ViewFormatNeed::RequireSrgbPair,
+            Some(&[TextureFormat::Bgra8Unorm, TextureFormat::Rgba16Float]),
+            wgpu::Features::empty(),
+            false,
+            |_| true,
+        );
+        assert_eq!(negotiation.format, Some(TextureFormat::Rgba16Float));
+        assert_eq!(negotiation.view_formats, [TextureFormat::Rgba16Float]);
+        assert_eq!(
+            negotiation.rejected,
+            [(
+                TextureFormat::Bgra8Unorm,
+                FormatRejection::ViewFormatsUnsupported
+            )]
+        );
+    }
+
+    #[test]
+    fn rejections_report_the_first_failed_check() {
+        // 每个候选都同时不满足多项检查，只记录最先检查的一项
+        let astc_ldr = TextureFormat::Astc {
+            block: AstcBlock::B8x8,
+            channel: AstcChannel::Unorm,
+        };
+        let negotiation = negotiate(
+            &[
+                astc_ldr,
+                ASTC_HDR,
+                TextureFormat::Rgba8Unorm,
+                TextureFormat::Bgra8Unorm,
+            ],
+            ViewFormatNeed::RequireSrgbPair,
+            Some(&[
+                ASTC_HDR,
+                TextureFormat::Rgba8Unorm,
+                TextureFormat::Bgra8Unorm,
+            ]),
+            wgpu::Features::empty(),
+            false,
+            |format| format == TextureFormat::Bgra8Unorm,
+        );
+        assert_eq!(negotiation.format, None);
+        assert!(negotiation.view_formats.is_empty());
+        assert_eq!(
+            negotiation.rejected,
+            [
+                (astc_ldr, FormatRejection::UnsupportedBySurface),
+                (
+                    ASTC_HDR,
+                    FormatRejection::MissingFeatures(wgpu::Features::TEXTURE_COMPRESSION_ASTC_HDR)
+                ),
+                (TextureFormat::Rgba8Unorm, FormatRejection::NotRenderable),
+                (
+                    TextureFormat::Bgra8Unorm,
+                    FormatRejection::ViewFormatsUnsupported
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn offscreen_is_not_limited_by_surface_formats() {
+        let negotiation = negotiate(
+            &[TextureFormat::Rgba16Float],
+            ViewFormatNeed::None,
+            None,
+            wgpu::Features::empty(),
+            false,
+            |_| true,
+        );
+        assert_eq!(negotiation.format, Some(TextureFormat::Rgba16Float));
+        assert!(negotiation.rejected.is_empty());
+    }
+}
@@ -1,12 +1,12 @@
 #[repr(C)]
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Default)]
 pub struct StylusAngle<T> {
     pub azimuth: T,
     pub altitude: T,
 }
 
 #[repr(C)]
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum TouchPhase {
     Started,
     Moved, // Or pintch changed
@@ -14,13 +14,32 @@ pub enum TouchPhase {
     Cancelled,
 }
 
+/// 产生触摸事件的指针类型
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum PointerKind {
+    #[default]
+    Finger,
+    Stylus,
+    Mouse,
+}
+
+/// 单个指针的触摸事件
+///
+/// 内存布局与 C 兼容，宿主 App 可以直接传入 `Touch` 数组
 #[repr(C)]
 #[derive(Copy, Clone, Debug)]
 pub struct Touch {
+    // 指针的 id，在按下到抬起期间保持不变，抬起后可能被复用
+    pub id: u64,
+    // 事件发生的时间戳，单位为秒，只要求单调递增
+    pub timestamp: f64,
     pub phase: TouchPhase,
+    pub kind: PointerKind,
     pub position: glam::Vec2,
     // The angle of the stylus: Apple Pencil
-    pub stylus_angle: Option<StylusAngle<f32>>,
+    // 只在 kind 为 Stylus 时有效
+    pub stylus_angle: StylusAngle<f32>,
     pub pressure: f32,
     // The radius of the contact ellipse along the major axis, in logical pixels.
     pub major_radius: f32,
@@ -28,6 +47,9 @@ pub struct Touch {
     pub interval: f32,
 }
 
+// Swift/Kotlin 端按此大小打包 `Touch` 数组
+const _: () = assert!(core::mem::size_of::<Touch>() == 56);
+
 impl Touch {
     pub fn touch_start(position: glam::Vec2) -> Self {
         Self::new(position, TouchPhase::Started)
@@ -41,14 +63,101 @@ impl Touch {
         Self::new(position, TouchPhase::Ended)
     }
 
-    fn new(position: glam::Vec2, phase: TouchPhase) -> Self {
+    pub fn touch_cancel(position: glam::Vec2) -> Self {
+        Self::new(position, TouchPhase::Cancelled)
+    }
+
+    pub fn with_id(mut self, id: u64) -> Self {
+        self.id = id;
+        self
+    }
+
+    pub fn with_kind(mut self, kind: PointerKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn with_timestamp(mut self, timestamp: f64) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    /// 触控笔的角度，非触控笔事件返回 `None`
+    pub fn stylus_angle(&self) -> Option<StylusAngle<f32>> {
+        (self.kind == PointerKind::Stylus).then_some(self.stylus_angle)
+    }
+
+    pub fn new(position: glam::Vec2, phase: TouchPhase) -> Self {
         Touch {
-            position,
+            id: 0,
+            timestamp: 0.0,
             phase,
-            stylus_angle: None,
+            kind: PointerKind::Finger,
+            position,
+            stylus_angle: StylusAngle::default(),
             pressure: 0.0,
             major_radius: 0.0,
             interval: 0.0,
         }
     }
 }
+
+/// 跟踪当前按下的所有指针
+///
+/// 按指针按下的先后顺序保存每个指针最新的触摸事件，抬起或取消后移除
+#[derive(Clone, Debug, Default)]
+pub struct TouchTracker {
+    active: Vec<Touch>,
+}
+
+impl TouchTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn update(&mut self, touch: &Touch) {
+        let index = self.active.iter().position(|t| t.id == touch.id);
+        match (touch.phase, index) {
+            (TouchPhase::Started, Some(index)) => {
+                // 丢失了抬起事件的指针，视为重新按下
+                self.active.remove(index);
+                self.active.push(*touch);
+            }
+            (TouchPhase::Started, None) => self.active.push(*touch),
+            (TouchPhase::Moved, Some(index)) => self.active[index] = *touch,
+            (TouchPhase::Ended | TouchPhase::Cancelled, Some(index)) => {
+                self.active.remove(index);
+            }
+            // 没有按下事件的指针（比如悬停的鼠标）不做跟踪
+            (_, None) => {}
+        }
+    }
+
+    pub fn update_batch(&mut self, touches: &[Touch]) {
+        for touch in touches {
+            self.update(touch);
+        }
+    }
+
+    /// 当前按下的指针，按按下的先后排列
+    pub fn active(&self) -> &[Touch] {
+        &self.active
+    }
+
+    pub fn get(&self, id: u64) -> Option<&Touch> {
+        self.active.iter().find(|t| t.id == id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.active.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.active.is_empty()
+    }
+
+    /// 宿主 App 取消了所有触摸时（比如进入后台）调用
+    pub fn clear(&mut self) {
+        self.active.clear();
+    }
+}
@@ -0,0 +1,133 @@
+use glam::Vec2;
+
+/// 视图的尺寸及坐标空间之间的换算
+///
+/// 用到的四种坐标空间：
+/// - 逻辑像素（点）：触摸事件与 UI 布局使用的坐标，原点在左上角，y 轴向下
+/// - 物理像素：帧缓冲的像素坐标，等于逻辑像素乘以 `scale_factor`
+/// - 归一化坐标：[0, 1] 范围，原点在左上角，y 轴向下，可直接用作纹理坐标
+/// - NDC：裁剪空间的 [-1, 1] 范围，原点在中心，y 轴向上
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ViewSize {
+    /// 物理像素宽度
+    pub width: u32,
+    /// 物理像素高度
+    pub height: u32,
+    /// 物理像素与逻辑像素之比
+    pub scale_factor: f32,
+    /// 安全区域的内边距（刘海、圆角、Home 指示条等），单位为逻辑像素
+    pub safe_area: EdgeInsets,
+}
+
+/// 四边的内边距
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct EdgeInsets {
+    pub top: f32,
+    pub left: f32,
+    pub bottom: f32,
+    pub right: f32,
+}
+
+impl EdgeInsets {
+    pub const ZERO: Self = Self::new(0.0, 0.0, 0.0, 0.0);
+
+    pub const fn new(top: f32, left: f32, bottom: f32, right: f32) -> Self {
+        Self {
+            top,
+            left,
+            bottom,
+            right,
+        }
+    }
+
+    pub fn scaled(&self, factor: f32) -> Self {
+        Self::new(
+            self.top * factor,
+            self.left * factor,
+            self.bottom * factor,
+            self.right * factor,
+        )
+    }
+}
+
+impl ViewSize {
+    /// 以物理像素为单位的安全区域内边距
+    pub fn safe_area_physical(&self) -> EdgeInsets {
+        self.safe_area.scaled(self.scale_factor)
+    }
+
+    /// 安全区域的原点与尺寸，单位为逻辑像素
+    pub fn safe_rect_logical(&self) -> (Vec2, Vec2) {
+        let insets = self.safe_area;
+        let origin = Vec2::new(insets.left, insets.top);
+        let size = self.logical_size() - origin - Vec2::new(insets.right, insets.bottom);
+        (origin, size.max(Vec2::ZERO))
+    }
+
+    /// 安全区域的原点与尺寸，单位为物理像素，可直接用作视口
+    pub fn safe_rect_physical(&self) -> (Vec2, Vec2) {
+        let (origin, size) = self.safe_rect_logical();
+        (
+            self.logical_to_physical(origin),
+            self.logical_to_physical(size),
+        )
+    }
+
+    pub fn physical_size(&self) -> Vec2 {
+        Vec2::new(self.width as f32, self.height as f32)
+    }
+
+    pub fn logical_size(&self) -> Vec2 {
+        self.physical_size() / self.scale_factor
+    }
+
+    pub fn logical_to_physical(&self, point: Vec2) -> Vec2 {
+        point * self.scale_factor
+    }
+
+    pub fn physical_to_logical(&self, point: Vec2) -> Vec2 {
+        point / self.scale_factor
+    }
+
+    pub fn physical_to_normalized(&self, point: Vec2) -> Vec2 {
+        point / self.physical_size().max(Vec2::ONE)
+    }
+
+    pub fn normalized_to_physical(&self, point: Vec2) -> Vec2 {
+        point * self.physical_size()
+    }
+
+    pub fn logical_to_normalized(&self, point: Vec2) -> Vec2 {
+        self.physical_to_normalized(self.logical_to_physical(point))
+    }
+
+    pub fn normalized_to_logical(&self, point: Vec2) -> Vec2 {
+        self.physical_to_logical(self.normalized_to_physical(point))
+    }
+
+    pub fn normalized_to_ndc(point: Vec2) -> Vec2 {
+        Vec2::new(point.x * 2.0 - 1.0, 1.0 - point.y * 2.0)
+    }
+
+    pub fn ndc_to_normalized(point: Vec2) -> Vec2 {
+        Vec2::new((point.x + 1.0) * 0.5, (1.0 - point.y) * 0.5)
+    }
+
+    pub fn logical_to_ndc(&self, point: Vec2) -> Vec2 {
+        Self::normalized_to_ndc(self.logical_to_normalized(point))
+    }
+
+    pub fn ndc_to_logical(&self, point: Vec2) -> Vec2 {
+        self.normalized_to_logical(Self::ndc_to_normalized(point))
+    }
+
+    pub fn physical_to_ndc(&self, point: Vec2) -> Vec2 {
+        Self::normalized_to_ndc(self.physical_to_normalized(point))
+    }
+
+    pub fn ndc_to_physical(&self, point: Vec2) -> Vec2 {
+        self.normalized_to_physical(Self::ndc_to_normalized(point))
+    }
+}
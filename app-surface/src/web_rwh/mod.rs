@@ -1,4 +1,4 @@
-use crate::IASDQContext;
+use crate::{AppSurfaceError, DeviceOptions, IASDQContext};
 use raw_window_handle::{HasDisplayHandle, HasWindowHandle};
 
 mod canvas;
@@ -10,12 +10,29 @@ pub use offscreen_canvas::*;
 pub struct AppSurface {
     pub view: ViewObj,
     pub scale_factor: f32,
+    /// 安全区域的内边距，单位为逻辑像素，由宿主 App 通过 `set_safe_area` 更新
+    pub safe_area: crate::EdgeInsets,
     pub ctx: IASDQContext,
 }
 
 #[allow(dead_code)]
 impl AppSurface {
+    /// 与 [`AppSurface::try_new`] 相同，但创建失败时直接 panic
     pub async fn new(view: ViewObj) -> Self {
+        Self::try_new(view)
+            .await
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub async fn try_new(view: ViewObj) -> Result<Self, AppSurfaceError> {
+        Self::try_new_with_options(view, &DeviceOptions::default()).await
+    }
+
+    /// 按 `options` 协商 Device 的特性与限制
+    pub async fn try_new_with_options(
+        view: ViewObj,
+        options: &DeviceOptions,
+    ) -> Result<Self, AppSurfaceError> {
         let (scale_factor, physical_size) = match view {
             ViewObj::Canvas(ref canvas) => (canvas.scale_factor, canvas.physical_resolution()),
             ViewObj::Offscreen(ref offscreen) => {
@@ -28,27 +45,26 @@ impl AppSurface {
             ..Default::default()
         });
         let surface = unsafe {
-            instance
-                .create_surface_unsafe(match view {
-                    ViewObj::Canvas(ref canvas) => wgpu::SurfaceTargetUnsafe::RawHandle {
-                        raw_display_handle: canvas.display_handle().unwrap().into(),
-                        raw_window_handle: canvas.window_handle().unwrap().into(),
-                    },
-                    ViewObj::Offscreen(ref offscreen) => wgpu::SurfaceTargetUnsafe::RawHandle {
-                        raw_display_handle: offscreen.display_handle().unwrap().into(),
-                        raw_window_handle: offscreen.window_handle().unwrap().into(),
-                    },
-                })
-                .expect("Surface creation failed")
+            instance.create_surface_unsafe(match view {
+                ViewObj::Canvas(ref canvas) => wgpu::SurfaceTargetUnsafe::RawHandle {
+                    raw_display_handle: canvas.display_handle().unwrap().into(),
+                    raw_window_handle: canvas.window_handle().unwrap().into(),
+                },
+                ViewObj::Offscreen(ref offscreen) => wgpu::SurfaceTargetUnsafe::RawHandle {
+                    raw_display_handle: offscreen.display_handle().unwrap().into(),
+                    raw_window_handle: offscreen.window_handle().unwrap().into(),
+                },
+            })?
         };
 
-        let ctx = crate::create_iasdq_context(instance, surface, physical_size).await;
+        let ctx = crate::create_iasdq_context(instance, surface, physical_size, options).await?;
 
-        Self {
+        Ok(Self {
             view,
             scale_factor,
+            safe_area: crate::EdgeInsets::ZERO,
             ctx,
-        }
+        })
     }
 
     /// 用 Canvas id 创建 AppSurface
@@ -56,8 +72,15 @@ impl AppSurface {
     /// element_id: 存在于当前页面中的 canvas 元素的 id
     /// handle: 用于 WebGPU 的 raw handle number, 0 是保留的值, 不能使用
     pub async fn from_canvas(element_id: &str, handle: u32) -> Self {
+        Self::try_from_canvas(element_id, handle)
+            .await
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// 与 [`AppSurface::from_canvas`] 相同，但创建失败时返回错误
+    pub async fn try_from_canvas(element_id: &str, handle: u32) -> Result<Self, AppSurfaceError> {
         let wrapper = CanvasWrapper::new(Canvas::new(element_id, handle));
-        Self::new(ViewObj::Canvas(wrapper)).await
+        Self::try_new(ViewObj::Canvas(wrapper)).await
     }
 
     /// 用 OffscreenCanvas 创建 AppSurface
@@ -68,12 +91,23 @@ impl AppSurface {
         scale_factor: f32,
         handle: u32,
     ) -> Self {
+        Self::try_from_offscreen_canvas(offscreen_canvas, scale_factor, handle)
+            .await
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// 与 [`AppSurface::from_offscreen_canvas`] 相同，但创建失败时返回错误
+    pub async fn try_from_offscreen_canvas(
+        offscreen_canvas: web_sys::OffscreenCanvas,
+        scale_factor: f32,
+        handle: u32,
+    ) -> Result<Self, AppSurfaceError> {
         let wrapper = OffscreenCanvasWrapper::new(OffscreenCanvas::new(
             offscreen_canvas,
             scale_factor,
             handle,
         ));
-        Self::new(ViewObj::Offscreen(wrapper)).await
+        Self::try_new(ViewObj::Offscreen(wrapper)).await
     }
 
     pub fn get_view_size(&self) -> (u32, u32) {
@@ -99,7 +133,15 @@ impl AppSurface {
         )
     }
 
+    pub fn set_scale_factor(&mut self, scale_factor: f32) {
+        self.update_device_pixel_ratio(scale_factor);
+    }
+
+    /// devicePixelRatio 的变化需要由页面通过 `update_device_pixel_ratio` 传入
+    pub(crate) fn sync_scale_factor(&mut self) {}
+
     pub fn update_device_pixel_ratio(&mut self, ratio: f32) {
+        self.scale_factor = ratio;
         match self.view {
             ViewObj::Canvas(ref mut canvas) => canvas.scale_factor = ratio,
             ViewObj::Offscreen(ref mut offscreen) => offscreen.scale_factor = ratio,
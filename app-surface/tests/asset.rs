@@ -0,0 +1,46 @@
+use app_surface::{
+    AssetChain, AssetError, AssetSource, EmbeddedAssets, FileSystemAssets, MemoryAssets,
+};
+
+static EMBEDDED: &[(&str, &[u8])] = &[("shader.wgsl", b"embedded"), ("data.bin", b"\xff\xfe")];
+
+#[test]
+fn chain_prefers_earlier_sources() {
+    let mut overrides = MemoryAssets::new();
+    overrides.insert("shader.wgsl", "override");
+    let assets = AssetChain::new()
+        .with(overrides)
+        .with(EmbeddedAssets::new(EMBEDDED));
+
+    assert_eq!(assets.load_string("shader.wgsl").unwrap(), "override");
+    assert_eq!(&*assets.load("data.bin").unwrap(), b"\xff\xfe");
+    assert!(matches!(
+        assets.load("missing.wgsl"),
+        Err(AssetError::NotFound(path)) if path == "missing.wgsl"
+    ));
+}
+
+#[test]
+fn reports_invalid_utf8() {
+    let assets = EmbeddedAssets::new(EMBEDDED);
+    assert!(matches!(
+        assets.load_string("data.bin"),
+        Err(AssetError::InvalidUtf8 { .. })
+    ));
+}
+
+#[test]
+fn file_system_rejects_escaping_paths() {
+    let assets = FileSystemAssets::new(env!("CARGO_MANIFEST_DIR"));
+    assert!(assets.load("Cargo.toml").is_ok());
+    for path in ["../Cargo.toml", "/etc/hosts", "", "src/../Cargo.toml"] {
+        assert!(
+            matches!(assets.load(path), Err(AssetError::InvalidPath(_))),
+            "{path}"
+        );
+    }
+    assert!(matches!(
+        assets.load("missing.txt"),
+        Err(AssetError::NotFound(_))
+    ));
+}
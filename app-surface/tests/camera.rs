@@ -0,0 +1,170 @@
+use app_surface::{
+    Camera, CameraMode, InputEvent, Key, Modifiers, ParameterValue, Projection, Touch,
+};
+use glam::{Vec2, Vec3};
+
+fn camera() -> Camera {
+    let mut camera = Camera::look_at(Vec3::new(0.0, -10.0, 0.0), Vec3::ZERO, Vec3::Z);
+    camera.smoothing = 0.0;
+    camera.set_viewport(Vec2::new(800.0, 600.0));
+    camera.update(0.0);
+    camera
+}
+
+fn drag(camera: &mut Camera, id: u64, from: Vec2, to: Vec2) {
+    camera.handle_touch(&Touch::touch_start(from).with_id(id));
+    camera.handle_touch(&Touch::touch_move(to).with_id(id));
+    camera.handle_touch(&Touch::touch_end(to).with_id(id));
+}
+
+fn key(camera: &mut Camera, c: &str, pressed: bool) -> bool {
+    camera.handle_input(&InputEvent::Key {
+        key: Key::Character(c.to_string()),
+        pressed,
+        repeat: false,
+        modifiers: Modifiers::default(),
+    })
+}
+
+fn assert_near(a: Vec3, b: Vec3) {
+    assert!(a.distance(b) < 1e-3, "{a} != {b}");
+}
+
+#[test]
+fn look_at_round_trips() {
+    let eye = Vec3::new(1.5, -5.0, 3.0);
+    let camera = Camera::look_at(eye, Vec3::ZERO, Vec3::Z);
+    assert_near(camera.eye(), eye);
+
+    let eye = Vec3::new(-200.0, 70.0, 200.0);
+    let camera = Camera::look_at(eye, Vec3::ZERO, Vec3::Y);
+    assert_near(camera.eye(), eye);
+}
+
+#[test]
+fn orbit_keeps_target_and_distance() {
+    let mut camera = camera();
+    drag(&mut camera, 0, Vec2::ZERO, Vec2::new(100.0, 50.0));
+    assert!(camera.update(0.016));
+    assert_near(camera.target(), Vec3::ZERO);
+    assert!((camera.eye().length() - 10.0).abs() < 1e-3);
+    // 向下拖动时相机升高
+    assert!(camera.eye().z > 0.0);
+    assert!(!camera.update(0.016));
+}
+
+#[test]
+fn pitch_is_clamped() {
+    let mut camera = camera();
+    drag(&mut camera, 0, Vec2::ZERO, Vec2::new(0.0, 10000.0));
+    camera.update(0.016);
+    let eye = camera.eye();
+    assert!(eye.z < 10.0 && eye.z > 9.9);
+    assert!(camera.view_matrix().is_finite());
+}
+
+#[test]
+fn wheel_and_pinch_zoom() {
+    let mut camera = camera().with_distance_range(2.0, 20.0);
+    camera.handle_input(&InputEvent::Wheel {
+        delta: Vec2::new(0.0, 10000.0),
+        position: Vec2::ZERO,
+    });
+    camera.update(0.016);
+    assert!((camera.distance() - 2.0).abs() < 1e-4);
+
+    // 双指间距从 100 缩小到 50，距离加倍
+    camera.handle_touch(&Touch::touch_start(Vec2::new(0.0, 0.0)).with_id(1));
+    camera.handle_touch(&Touch::touch_start(Vec2::new(100.0, 0.0)).with_id(2));
+    camera.handle_touch(&Touch::touch_move(Vec2::new(50.0, 0.0)).with_id(2));
+    camera.update(0.016);
+    assert!((camera.distance() - 4.0).abs() < 1e-3);
+}
+
+#[test]
+fn shift_drag_pans() {
+    let mut camera = camera();
+    camera.handle_input(&InputEvent::Key {
+        key: Key::Named(app_surface::NamedKey::Shift),
+        pressed: true,
+        repeat: false,
+        modifiers: Modifiers::default(),
+    });
+    drag(&mut camera, 0, Vec2::ZERO, Vec2::new(100.0, 0.0));
+    camera.update(0.016);
+    // 向右拖动时画面右移，目标点向左移动，视线方向不变
+    assert!(camera.target().x < 0.0);
+    assert_near(camera.eye() - camera.target(), Vec3::new(0.0, -10.0, 0.0));
+}
+
+#[test]
+fn fly_mode_rotates_around_the_eye() {
+    let mut camera = camera();
+    assert!(key(&mut camera, "f", true));
+    assert_eq!(camera.mode, CameraMode::Fly);
+    drag(&mut camera, 0, Vec2::ZERO, Vec2::new(100.0, 0.0));
+    camera.update(0.016);
+    assert_near(camera.eye(), Vec3::new(0.0, -10.0, 0.0));
+    assert!(camera.target().distance(Vec3::ZERO) > 1.0);
+
+    // 按住 W 一秒，沿视线前进 move_speed
+    let forward = (camera.target() - camera.eye()).normalize();
+    key(&mut camera, "w", true);
+    camera.update(1.0);
+    key(&mut camera, "w", false);
+    assert_near(
+        camera.eye(),
+        Vec3::new(0.0, -10.0, 0.0) + forward * camera.move_speed,
+    );
+}
+
+#[test]
+fn move_keys_are_ignored_in_orbit_mode() {
+    let mut camera = camera();
+    assert!(!key(&mut camera, "w", true));
+    assert!(!camera.update(1.0));
+}
+
+#[test]
+fn smoothing_converges_and_reset() {
+    let mut camera = camera();
+    camera.smoothing = 0.1;
+    drag(&mut camera, 0, Vec2::ZERO, Vec2::new(100.0, 0.0));
+    camera.update(0.016);
+    let halfway = camera.eye();
+    assert!(halfway.distance(Vec3::new(0.0, -10.0, 0.0)) > 1e-3);
+    for _ in 0..200 {
+        camera.update(0.016);
+    }
+    assert!(!camera.update(0.016));
+
+    key(&mut camera, "r", true);
+    camera.snap();
+    assert!(camera.update(0.016));
+    assert_near(camera.eye(), Vec3::new(0.0, -10.0, 0.0));
+}
+
+#[test]
+fn projection_parameter() {
+    let mut camera = camera();
+    assert_eq!(
+        camera.parameter("camera_projection"),
+        Some(ParameterValue::Enum(0))
+    );
+    camera
+        .set_parameter("camera_projection", ParameterValue::Enum(1))
+        .unwrap();
+    assert_eq!(camera.projection, Projection::Orthographic);
+    assert!(camera.update(0.016));
+    // 正交投影下目标点处的可见高度与透视投影相同
+    let top = camera.view_projection()
+        * Vec3::new(0.0, 0.0, 10.0 * (camera.fov_y * 0.5).tan()).extend(1.0);
+    assert!((top.y / top.w - 1.0).abs() < 1e-4);
+
+    assert!(
+        camera
+            .set_parameter("speed", ParameterValue::Enum(0))
+            .is_err()
+    );
+    assert_eq!(camera.parameter("speed"), None);
+}
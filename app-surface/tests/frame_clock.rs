@@ -0,0 +1,91 @@
+use app_surface::FrameClock;
+
+/// 目标帧间隔为 10 毫秒，依次在 `times` 中的时间戳调用 `tick_at`
+fn clock(times: &[f64]) -> FrameClock {
+    let mut clock = FrameClock::new(100);
+    for &time in times {
+        clock.tick_at(time);
+    }
+    clock
+}
+
+#[test]
+fn first_tick_has_no_delta() {
+    let clock = clock(&[500.0]);
+    assert_eq!(clock.frame_count(), 1);
+    assert_eq!(clock.delta_seconds(), 0.0);
+    assert_eq!(clock.samples().count(), 0);
+    assert_eq!(clock.average_fps(), 0.0);
+}
+
+#[test]
+fn dropped_frames_start_above_one_and_a_half_intervals() {
+    // 15 毫秒正好是阈值，不算掉帧
+    assert_eq!(clock(&[0.0, 15.0]).dropped_frames(), 0);
+    // 16 毫秒约等于 2 个间隔，掉了 1 帧
+    assert_eq!(clock(&[0.0, 16.0]).dropped_frames(), 1);
+}
+
+#[test]
+fn dropped_frames_round_to_whole_intervals() {
+    // 34 毫秒约 3 个间隔，36 毫秒约 4 个间隔
+    assert_eq!(clock(&[0.0, 34.0]).dropped_frames(), 2);
+    assert_eq!(clock(&[0.0, 36.0]).dropped_frames(), 3);
+    assert_eq!(clock(&[0.0, 34.0, 70.0]).dropped_frames(), 5);
+}
+
+#[test]
+fn statistics_use_the_latest_120_frames() {
+    // 前 9 帧间隔 100 毫秒，之后 120 帧间隔 10 毫秒，慢帧都被挤出统计窗口
+    let mut times = vec![0.0];
+    times.extend((1..=9).map(|i| i as f64 * 100.0));
+    times.extend((1..=120).map(|i| 900.0 + i as f64 * 10.0));
+    let clock = clock(&times);
+
+    assert_eq!(clock.frame_count(), 130);
+    assert_eq!(clock.samples().count(), 120);
+    assert!(clock.samples().all(|sample| sample == 10.0));
+    let stats = clock.stats();
+    assert_eq!(stats.average_frame_time_ms, 10.0);
+    assert_eq!(stats.average_fps, 100.0);
+    assert_eq!(stats.p99_frame_time_ms, 10.0);
+    // 掉帧数是累计的，不随窗口滑动而减少
+    assert_eq!(stats.dropped_frames, 9 * 9);
+    assert!((clock.elapsed_seconds() - 2.1).abs() < 1e-6);
+}
+
+#[test]
+fn percentiles_pick_the_nearest_rank() {
+    // 帧时间依次为 1..=10 毫秒
+    let mut times = vec![0.0];
+    for i in 1..=10 {
+        times.push(times.last().unwrap() + i as f64);
+    }
+    let clock = clock(&times);
+    let stats = clock.stats();
+    assert_eq!(stats.average_frame_time_ms, 5.5);
+    assert_eq!(clock.frame_time_percentile(0.0), 1.0);
+    assert_eq!(stats.p50_frame_time_ms, 6.0);
+    assert_eq!(stats.p95_frame_time_ms, 10.0);
+    assert_eq!(clock.frame_time_percentile(200.0), 10.0);
+}
+
+#[test]
+fn reset_delta_skips_the_paused_interval() {
+    let mut clock = clock(&[0.0, 10.0]);
+    clock.reset_delta();
+    assert_eq!(clock.delta_seconds(), 0.0);
+
+    // 暂停期间的 1 秒不计入帧时间，也不算掉帧
+    clock.tick_at(1010.0);
+    assert_eq!(clock.frame_count(), 3);
+    assert_eq!(clock.delta_seconds(), 0.0);
+    assert_eq!(clock.samples().count(), 1);
+    assert_eq!(clock.dropped_frames(), 0);
+
+    clock.tick_at(1020.0);
+    assert_eq!(clock.delta_seconds(), 0.01);
+    assert_eq!(clock.samples().collect::<Vec<_>>(), [10.0, 10.0]);
+    assert!((clock.elapsed_seconds() - 0.02).abs() < 1e-6);
+    assert_eq!(clock.dropped_frames(), 0);
+}
@@ -0,0 +1,234 @@
+use app_surface::{Gesture, GestureConfig, GesturePhase, GestureRecognizer, Touch};
+use glam::Vec2;
+
+fn start(r: &mut GestureRecognizer, id: u64, pos: Vec2, time: f64) -> Vec<Gesture> {
+    r.handle_touch(&Touch::touch_start(pos).with_id(id).with_timestamp(time))
+}
+
+fn moved(r: &mut GestureRecognizer, id: u64, pos: Vec2, time: f64) -> Vec<Gesture> {
+    r.handle_touch(&Touch::touch_move(pos).with_id(id).with_timestamp(time))
+}
+
+fn end(r: &mut GestureRecognizer, id: u64, pos: Vec2, time: f64) -> Vec<Gesture> {
+    r.handle_touch(&Touch::touch_end(pos).with_id(id).with_timestamp(time))
+}
+
+#[test]
+fn tap() {
+    let mut r = GestureRecognizer::default();
+    assert!(start(&mut r, 0, Vec2::new(10.0, 10.0), 0.0).is_empty());
+    assert!(moved(&mut r, 0, Vec2::new(12.0, 11.0), 0.05).is_empty());
+    let gestures = end(&mut r, 0, Vec2::new(12.0, 11.0), 0.1);
+    assert_eq!(
+        gestures,
+        vec![Gesture::Tap {
+            position: Vec2::new(12.0, 11.0)
+        }]
+    );
+}
+
+#[test]
+fn slow_release_is_not_a_tap() {
+    let mut r = GestureRecognizer::default();
+    start(&mut r, 0, Vec2::ZERO, 0.0);
+    assert!(end(&mut r, 0, Vec2::ZERO, 0.4).is_empty());
+}
+
+#[test]
+fn double_tap() {
+    let mut r = GestureRecognizer::default();
+    start(&mut r, 0, Vec2::ZERO, 0.0);
+    end(&mut r, 0, Vec2::ZERO, 0.1);
+    start(&mut r, 0, Vec2::new(5.0, 0.0), 0.2);
+    let gestures = end(&mut r, 0, Vec2::new(5.0, 0.0), 0.25);
+    assert_eq!(
+        gestures,
+        vec![Gesture::DoubleTap {
+            position: Vec2::new(5.0, 0.0)
+        }]
+    );
+
+    // 第三次点击重新开始计数
+    start(&mut r, 0, Vec2::ZERO, 0.4);
+    let gestures = end(&mut r, 0, Vec2::ZERO, 0.45);
+    assert!(matches!(gestures[..], [Gesture::Tap { .. }]));
+}
+
+#[test]
+fn taps_too_far_apart_are_not_a_double_tap() {
+    let mut r = GestureRecognizer::default();
+    start(&mut r, 0, Vec2::ZERO, 0.0);
+    end(&mut r, 0, Vec2::ZERO, 0.1);
+    start(&mut r, 0, Vec2::ZERO, 0.6);
+    let gestures = end(&mut r, 0, Vec2::ZERO, 0.65);
+    assert!(matches!(gestures[..], [Gesture::Tap { .. }]));
+}
+
+#[test]
+fn long_press() {
+    let mut r = GestureRecognizer::default();
+    start(&mut r, 0, Vec2::new(3.0, 4.0), 0.0);
+    assert_eq!(r.update(0.3), None);
+    moved(&mut r, 0, Vec2::new(5.0, 4.0), 0.4);
+    assert_eq!(
+        r.update(0.5),
+        Some(Gesture::LongPress {
+            position: Vec2::new(5.0, 4.0)
+        })
+    );
+    // 只触发一次，抬起后也不算点击
+    assert_eq!(r.update(0.6), None);
+    assert!(end(&mut r, 0, Vec2::new(5.0, 4.0), 0.7).is_empty());
+}
+
+#[test]
+fn pan_with_velocity() {
+    let mut r = GestureRecognizer::default();
+    start(&mut r, 0, Vec2::ZERO, 0.0);
+    assert!(moved(&mut r, 0, Vec2::new(5.0, 0.0), 0.01).is_empty());
+
+    let gestures = moved(&mut r, 0, Vec2::new(20.0, 0.0), 0.02);
+    let [Gesture::Pan { phase, delta, .. }] = gestures[..] else {
+        panic!("expected pan began, got {gestures:?}");
+    };
+    assert_eq!(phase, GesturePhase::Began);
+    assert_eq!(delta, Vec2::new(20.0, 0.0));
+
+    let gestures = moved(&mut r, 0, Vec2::new(30.0, 0.0), 0.03);
+    let [
+        Gesture::Pan {
+            phase,
+            delta,
+            velocity,
+            ..
+        },
+    ] = gestures[..]
+    else {
+        panic!("expected pan changed, got {gestures:?}");
+    };
+    assert_eq!(phase, GesturePhase::Changed);
+    assert_eq!(delta, Vec2::new(10.0, 0.0));
+    assert!(velocity.x > 500.0 && velocity.y == 0.0);
+
+    let gestures = end(&mut r, 0, Vec2::new(30.0, 0.0), 0.04);
+    assert!(matches!(
+        gestures[..],
+        [Gesture::Pan {
+            phase: GesturePhase::Ended,
+            ..
+        }]
+    ));
+    // 拖动后不会再触发长按
+    assert_eq!(r.update(1.0), None);
+}
+
+#[test]
+fn pinch() {
+    let mut r = GestureRecognizer::default();
+    start(&mut r, 0, Vec2::new(-50.0, 0.0), 0.0);
+    start(&mut r, 1, Vec2::new(50.0, 0.0), 0.0);
+
+    let gestures = moved(&mut r, 1, Vec2::new(150.0, 0.0), 0.1);
+    let [
+        Gesture::Pinch {
+            phase: GesturePhase::Began,
+            ..
+        },
+        Gesture::Pinch {
+            phase: GesturePhase::Changed,
+            focal_point,
+            scale,
+            ..
+        },
+    ] = gestures[..]
+    else {
+        panic!("expected pinch, got {gestures:?}");
+    };
+    assert_eq!(focal_point, Vec2::new(50.0, 0.0));
+    assert!((scale - 2.0).abs() < 1e-5);
+
+    let gestures = moved(&mut r, 0, Vec2::new(50.0, 0.0), 0.2);
+    let [
+        Gesture::Pinch {
+            scale, delta_scale, ..
+        },
+    ] = gestures[..]
+    else {
+        panic!("expected pinch changed, got {gestures:?}");
+    };
+    assert!((scale - 1.0).abs() < 1e-5);
+    assert!((delta_scale - 0.5).abs() < 1e-5);
+
+    let gestures = end(&mut r, 1, Vec2::new(150.0, 0.0), 0.3);
+    assert!(matches!(
+        gestures[..],
+        [Gesture::Pinch {
+            phase: GesturePhase::Ended,
+            ..
+        }]
+    ));
+    // 双指操作后抬起最后一根手指不算点击
+    assert!(end(&mut r, 0, Vec2::new(50.0, 0.0), 0.35).is_empty());
+}
+
+#[test]
+fn rotate() {
+    let mut r = GestureRecognizer::default();
+    start(&mut r, 0, Vec2::new(-50.0, 0.0), 0.0);
+    start(&mut r, 1, Vec2::new(50.0, 0.0), 0.0);
+
+    // 绕中点在屏幕上顺时针转 90 度（y 轴向下），两指间距不变
+    moved(&mut r, 0, Vec2::new(0.0, -50.0), 0.1);
+    let gestures = moved(&mut r, 1, Vec2::new(0.0, 50.0), 0.1);
+    let Some(Gesture::Rotate {
+        rotation,
+        focal_point,
+        ..
+    }) = gestures.last().copied()
+    else {
+        panic!("expected rotation, got {gestures:?}");
+    };
+    assert!((rotation - core::f32::consts::FRAC_PI_2).abs() < 1e-5);
+    assert_eq!(focal_point, Vec2::ZERO);
+    // 中间步骤改变过两指间距，但最终间距不变
+    for gesture in gestures {
+        if let Gesture::Pinch { scale, .. } = gesture {
+            assert!((scale - 1.0).abs() < 1e-5);
+        }
+    }
+}
+
+#[test]
+fn second_finger_ends_pan() {
+    let mut r = GestureRecognizer::default();
+    start(&mut r, 0, Vec2::ZERO, 0.0);
+    moved(&mut r, 0, Vec2::new(30.0, 0.0), 0.05);
+    let gestures = start(&mut r, 1, Vec2::new(100.0, 0.0), 0.1);
+    assert!(matches!(
+        gestures[..],
+        [Gesture::Pan {
+            phase: GesturePhase::Ended,
+            ..
+        }]
+    ));
+}
+
+#[test]
+fn cancel_reports_active_gestures() {
+    let mut r = GestureRecognizer::new(GestureConfig {
+        pan_threshold: 1.0,
+        ..Default::default()
+    });
+    start(&mut r, 0, Vec2::ZERO, 0.0);
+    moved(&mut r, 0, Vec2::new(2.0, 0.0), 0.05);
+    let mut gestures = vec![];
+    r.cancel(&mut gestures);
+    assert!(matches!(
+        gestures[..],
+        [Gesture::Pan {
+            phase: GesturePhase::Cancelled,
+            ..
+        }]
+    ));
+    assert!(end(&mut r, 0, Vec2::ZERO, 0.1).is_empty());
+}
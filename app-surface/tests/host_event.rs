@@ -0,0 +1,85 @@
+use app_surface::{
+    FrameStats, HOST_EVENT_MASK_ALL, HostEvent, HostEventKind, HostEventSink, RawHostEvent,
+};
+use core::ffi::c_void;
+
+#[derive(Default)]
+struct Received {
+    events: Vec<(HostEventKind, i64, Option<String>)>,
+    released: bool,
+}
+
+extern "C" fn record(event: *const RawHostEvent, user_data: *mut c_void) {
+    let received = unsafe { &mut *(user_data as *mut Received) };
+    let event = unsafe { &*event };
+    let payload = unsafe { event.payload_str() }.map(String::from);
+    received.events.push((event.kind, event.code, payload));
+}
+
+extern "C" fn release(user_data: *mut c_void) {
+    let received = unsafe { &mut *(user_data as *mut Received) };
+    received.released = true;
+}
+
+#[test]
+fn encodes_kind_code_and_payload() {
+    let changed = HostEvent::ExampleChanged {
+        index: 3,
+        name: "Water".to_string(),
+    };
+    assert_eq!(changed.kind(), HostEventKind::ExampleChanged);
+    assert_eq!(changed.code(), 3);
+    assert_eq!(changed.payload().as_deref(), Some("Water"));
+
+    let stats = FrameStats {
+        frame_count: 120,
+        dropped_frames: 2,
+        delta_ms: 16.5,
+        average_fps: 60.0,
+        average_frame_time_ms: 16.5,
+        p50_frame_time_ms: 16.0,
+        p95_frame_time_ms: 18.0,
+        p99_frame_time_ms: 20.0,
+    };
+    let rendered = HostEvent::FrameRendered(stats);
+    assert_eq!(rendered.code(), 120);
+    let json = rendered.payload().unwrap();
+    assert!(json.starts_with("{\"frame_count\":120,\"dropped_frames\":2,"));
+    assert!(json.ends_with("\"p99_frame_time_ms\":20}"));
+
+    assert_eq!(HostEvent::DeviceLost.payload(), None);
+    assert_eq!(HostEventKind::SurfaceLost as i32, 7);
+    assert_eq!(HostEventKind::Warning.mask(), 1 << 3);
+}
+
+#[test]
+fn replays_pending_events_on_register() {
+    let mut received = Received::default();
+    let mut sink = HostEventSink::default();
+    sink.emit(&HostEvent::Ready);
+    // 没有回调时不缓存逐帧的事件
+    sink.emit(&HostEvent::FrameRendered(FrameStats::default()));
+    sink.emit(&HostEvent::Error("bad\0shader".to_string()));
+
+    let mask = HOST_EVENT_MASK_ALL & !HostEventKind::Warning.mask();
+    sink.register(
+        Some(record),
+        &mut received as *mut Received as *mut c_void,
+        mask,
+        Some(release),
+    );
+    sink.emit(&HostEvent::Warning("ignored".to_string()));
+    sink.emit(&HostEvent::SurfaceLost);
+    assert!(!sink.wants(HostEventKind::Warning));
+    drop(sink);
+
+    assert_eq!(
+        received.events,
+        vec![
+            (HostEventKind::Ready, 0, None),
+            (HostEventKind::Error, 0, Some("bad shader".to_string())),
+            (HostEventKind::SurfaceLost, 0, None),
+        ]
+    );
+    assert!(received.released);
+}
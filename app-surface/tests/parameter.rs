@@ -0,0 +1,88 @@
+use app_surface::{
+    ParameterError, ParameterInfo, ParameterType, ParameterValue, RawParameterValue,
+};
+
+const SPEED: ParameterInfo = ParameterInfo::float("speed", "Speed", 1.0, 0.0, 4.0, 0.1);
+const MSAA: ParameterInfo = ParameterInfo::enumeration("msaa", "MSAA", 1, &["1x", "4x"]);
+
+#[test]
+fn validate_clamps_and_checks_types() {
+    assert_eq!(
+        SPEED.validate(ParameterValue::Float(9.0)),
+        Ok(ParameterValue::Float(4.0))
+    );
+    assert!(matches!(
+        SPEED.validate(ParameterValue::Float(f32::NAN)),
+        Err(ParameterError::InvalidValue { .. })
+    ));
+    assert!(matches!(
+        SPEED.validate(ParameterValue::Float(f32::INFINITY)),
+        Err(ParameterError::InvalidValue { .. })
+    ));
+    assert_eq!(
+        SPEED.validate(ParameterValue::Int(1)),
+        Err(ParameterError::TypeMismatch {
+            name: "speed".to_string(),
+            expected: ParameterType::Float,
+            found: ParameterType::Int,
+        })
+    );
+
+    let color = ParameterInfo::color("tint", "Tint", [1.0; 4]);
+    assert_eq!(
+        color.validate(ParameterValue::Color([-1.0, 0.5, 2.0, 1.0])),
+        Ok(ParameterValue::Color([0.0, 0.5, 1.0, 1.0]))
+    );
+    assert!(
+        color
+            .validate(ParameterValue::Color([0.0, f32::NEG_INFINITY, 0.0, 1.0]))
+            .is_err()
+    );
+    assert_eq!(
+        MSAA.validate(ParameterValue::Enum(1)),
+        Ok(ParameterValue::Enum(1))
+    );
+    assert!(MSAA.validate(ParameterValue::Enum(2)).is_err());
+}
+
+#[test]
+fn describes_parameters_as_json() {
+    assert_eq!(
+        SPEED.to_json(ParameterValue::Float(2.5)),
+        r#"{"name":"speed","label":"Speed","type":"float","min":0,"max":4,"step":0.1,"default":1,"value":2.5}"#
+    );
+    assert_eq!(
+        MSAA.to_json(ParameterValue::Enum(0)),
+        r#"{"name":"msaa","label":"MSAA","type":"enum","options":["1x","4x"],"default":1,"value":0}"#
+    );
+    // 绕过校验的非有限值不能破坏 JSON
+    let color = ParameterInfo::color("tint", "Tint", [1.0; 4]);
+    assert_eq!(
+        color.to_json(ParameterValue::Color([f32::NAN, 0.5, f32::INFINITY, 1.0])),
+        r#"{"name":"tint","label":"Tint","type":"color","default":[1,1,1,1],"value":[null,0.5,null,1]}"#
+    );
+}
+
+#[test]
+fn raw_values_round_trip() {
+    assert_eq!(core::mem::size_of::<RawParameterValue>(), 24);
+    let values = [
+        ParameterValue::Float(0.5),
+        ParameterValue::Int(-3),
+        ParameterValue::Bool(true),
+        ParameterValue::Color([0.1, 0.2, 0.3, 0.4]),
+        ParameterValue::Enum(2),
+    ];
+    for value in values {
+        let raw = RawParameterValue::from(value);
+        assert_eq!(ParameterType::try_from(raw.ty), Ok(value.ty()));
+        assert_eq!(ParameterValue::try_from(raw), Ok(value));
+    }
+    assert_eq!(ParameterType::try_from(5), Err(5));
+    let raw = RawParameterValue {
+        ty: 5,
+        int_value: 0,
+        float_values: [0.0; 4],
+    };
+    assert_eq!(ParameterValue::try_from(raw), Err(5));
+}
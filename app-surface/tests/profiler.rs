@@ -0,0 +1,72 @@
+use app_surface::{PROFILE_LABEL_LEN, ProfileFrame, ProfileScope};
+
+fn scope(label: &str, gpu_ms: Option<f32>, children: Vec<ProfileScope>) -> ProfileScope {
+    ProfileScope {
+        label: label.to_string(),
+        cpu_ms: 0.5,
+        gpu_ms,
+        children,
+    }
+}
+
+fn shadow_frame() -> ProfileFrame {
+    ProfileFrame {
+        frame_index: 3,
+        scopes: vec![
+            scope(
+                "shadow",
+                Some(1.5),
+                vec![
+                    scope("light 0", Some(1.0), vec![]),
+                    scope("light 1", Some(0.5), vec![]),
+                ],
+            ),
+            scope("forward", Some(2.0), vec![]),
+        ],
+    }
+}
+
+#[test]
+fn finds_scopes_by_path() {
+    let frame = shadow_frame();
+    assert_eq!(frame.find("shadow/light 1").unwrap().gpu_ms, Some(0.5));
+    assert_eq!(frame.find("forward").unwrap().label, "forward");
+    assert!(frame.find("forward/light 0").is_none());
+    assert_eq!(frame.total_cpu_ms(), 1.0);
+    assert_eq!(frame.total_gpu_ms(), Some(3.5));
+
+    let mut cpu_only = frame.clone();
+    cpu_only.scopes[1].gpu_ms = None;
+    assert_eq!(cpu_only.total_gpu_ms(), None);
+}
+
+#[test]
+fn flattens_depth_first() {
+    let entries = shadow_frame().entries();
+    let flattened: Vec<(&str, u32)> = entries
+        .iter()
+        .map(|entry| (entry.label(), entry.depth))
+        .collect();
+    assert_eq!(
+        flattened,
+        [
+            ("shadow", 0),
+            ("light 0", 1),
+            ("light 1", 1),
+            ("forward", 0)
+        ]
+    );
+    assert_eq!(entries[3].gpu_ms, 2.0);
+}
+
+#[test]
+fn truncates_long_labels_on_char_boundary() {
+    let frame = ProfileFrame {
+        frame_index: 0,
+        scopes: vec![scope(&"阴影".repeat(10), None, vec![])],
+    };
+    let entry = frame.entries()[0];
+    assert_eq!(entry.label(), "阴影".repeat(5));
+    assert_eq!(entry.label[PROFILE_LABEL_LEN - 1], 0);
+    assert_eq!(entry.gpu_ms, -1.0);
+}
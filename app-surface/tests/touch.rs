@@ -0,0 +1,56 @@
+use app_surface::{PointerKind, Touch, TouchTracker};
+use glam::Vec2;
+
+#[test]
+fn tracks_active_pointers_in_press_order() {
+    let mut tracker = TouchTracker::new();
+    tracker.update_batch(&[
+        Touch::touch_start(Vec2::ZERO).with_id(7),
+        Touch::touch_start(Vec2::ONE).with_id(3),
+    ]);
+    tracker.update(&Touch::touch_move(Vec2::new(5.0, 5.0)).with_id(7));
+
+    let ids: Vec<u64> = tracker.active().iter().map(|t| t.id).collect();
+    assert_eq!(ids, [7, 3]);
+    assert_eq!(tracker.get(7).unwrap().position, Vec2::new(5.0, 5.0));
+
+    tracker.update(&Touch::touch_end(Vec2::ONE).with_id(7));
+    tracker.update(&Touch::touch_cancel(Vec2::ONE).with_id(3));
+    assert!(tracker.is_empty());
+}
+
+#[test]
+fn ignores_pointers_that_were_never_pressed() {
+    let mut tracker = TouchTracker::new();
+    let hover = Touch::touch_move(Vec2::ONE)
+        .with_id(1)
+        .with_kind(PointerKind::Mouse);
+    tracker.update(&hover);
+    tracker.update(&Touch::touch_end(Vec2::ONE).with_id(1));
+    assert!(tracker.is_empty());
+}
+
+#[test]
+fn restarted_pointer_replaces_stale_contact() {
+    let mut tracker = TouchTracker::new();
+    tracker.update(&Touch::touch_start(Vec2::ZERO).with_id(1));
+    tracker.update(&Touch::touch_start(Vec2::ZERO).with_id(2));
+    // 丢失了 id 1 的抬起事件
+    tracker.update(&Touch::touch_start(Vec2::ONE).with_id(1));
+
+    let ids: Vec<u64> = tracker.active().iter().map(|t| t.id).collect();
+    assert_eq!(ids, [2, 1]);
+    assert_eq!(tracker.get(1).unwrap().position, Vec2::ONE);
+}
+
+#[test]
+fn stylus_angle_only_for_stylus() {
+    let touch = Touch::touch_start(Vec2::ZERO);
+    assert!(touch.stylus_angle().is_none());
+    assert!(
+        touch
+            .with_kind(PointerKind::Stylus)
+            .stylus_angle()
+            .is_some()
+    );
+}
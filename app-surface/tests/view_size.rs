@@ -0,0 +1,130 @@
+use app_surface::{EdgeInsets, ViewSize};
+use glam::Vec2;
+
+const SIZE: ViewSize = ViewSize {
+    width: 800,
+    height: 600,
+    scale_factor: 2.0,
+    safe_area: EdgeInsets::ZERO,
+};
+
+fn assert_near(a: Vec2, b: Vec2) {
+    assert!(a.abs_diff_eq(b, 1e-5), "{a} != {b}");
+}
+
+#[test]
+fn logical_size() {
+    assert_eq!(SIZE.logical_size(), Vec2::new(400.0, 300.0));
+    assert_eq!(SIZE.physical_size(), Vec2::new(800.0, 600.0));
+}
+
+#[test]
+fn logical_and_physical() {
+    let logical = Vec2::new(100.0, 50.0);
+    let physical = SIZE.logical_to_physical(logical);
+    assert_eq!(physical, Vec2::new(200.0, 100.0));
+    assert_eq!(SIZE.physical_to_logical(physical), logical);
+}
+
+#[test]
+fn physical_and_normalized() {
+    let normalized = SIZE.physical_to_normalized(Vec2::new(200.0, 150.0));
+    assert_near(normalized, Vec2::new(0.25, 0.25));
+    assert_near(
+        SIZE.normalized_to_physical(normalized),
+        Vec2::new(200.0, 150.0),
+    );
+}
+
+#[test]
+fn logical_and_normalized() {
+    // 逻辑尺寸为 400x300，其中心应映射到 (0.5, 0.5)
+    let normalized = SIZE.logical_to_normalized(Vec2::new(200.0, 150.0));
+    assert_near(normalized, Vec2::splat(0.5));
+    assert_near(
+        SIZE.normalized_to_logical(normalized),
+        Vec2::new(200.0, 150.0),
+    );
+}
+
+#[test]
+fn normalized_and_ndc() {
+    assert_near(
+        ViewSize::normalized_to_ndc(Vec2::ZERO),
+        Vec2::new(-1.0, 1.0),
+    );
+    assert_near(ViewSize::normalized_to_ndc(Vec2::ONE), Vec2::new(1.0, -1.0));
+    assert_near(ViewSize::normalized_to_ndc(Vec2::splat(0.5)), Vec2::ZERO);
+    let point = Vec2::new(0.2, 0.7);
+    assert_near(
+        ViewSize::ndc_to_normalized(ViewSize::normalized_to_ndc(point)),
+        point,
+    );
+}
+
+#[test]
+fn logical_and_ndc() {
+    assert_near(SIZE.logical_to_ndc(Vec2::ZERO), Vec2::new(-1.0, 1.0));
+    assert_near(
+        SIZE.logical_to_ndc(Vec2::new(400.0, 300.0)),
+        Vec2::new(1.0, -1.0),
+    );
+    assert_near(SIZE.ndc_to_logical(Vec2::ZERO), Vec2::new(200.0, 150.0));
+}
+
+#[test]
+fn physical_and_ndc() {
+    assert_near(
+        SIZE.physical_to_ndc(Vec2::new(800.0, 0.0)),
+        Vec2::new(1.0, 1.0),
+    );
+    assert_near(
+        SIZE.ndc_to_physical(Vec2::new(-1.0, -1.0)),
+        Vec2::new(0.0, 600.0),
+    );
+}
+
+#[test]
+fn mixed_dpi_maps_same_logical_point_to_same_normalized_point() {
+    // 同一窗口从 1x 显示器移到 2x 显示器后，物理尺寸翻倍而逻辑尺寸不变
+    let low_dpi = ViewSize {
+        width: 400,
+        height: 300,
+        scale_factor: 1.0,
+        safe_area: EdgeInsets::ZERO,
+    };
+    let point = Vec2::new(100.0, 75.0);
+    assert_near(
+        low_dpi.logical_to_normalized(point),
+        SIZE.logical_to_normalized(point),
+    );
+}
+
+#[test]
+fn safe_area_in_logical_and_physical_pixels() {
+    let size = ViewSize {
+        safe_area: EdgeInsets::new(47.0, 10.0, 34.0, 20.0),
+        ..SIZE
+    };
+    assert_eq!(
+        size.safe_area_physical(),
+        EdgeInsets::new(94.0, 20.0, 68.0, 40.0)
+    );
+
+    let (origin, extent) = size.safe_rect_logical();
+    assert_eq!(origin, Vec2::new(10.0, 47.0));
+    assert_eq!(extent, Vec2::new(370.0, 219.0));
+
+    let (origin, extent) = size.safe_rect_physical();
+    assert_eq!(origin, Vec2::new(20.0, 94.0));
+    assert_eq!(extent, Vec2::new(740.0, 438.0));
+}
+
+#[test]
+fn safe_rect_never_has_negative_size() {
+    let size = ViewSize {
+        safe_area: EdgeInsets::new(200.0, 0.0, 200.0, 0.0),
+        ..SIZE
+    };
+    assert_eq!(size.safe_rect_logical().1, Vec2::new(400.0, 0.0));
+}
@@ -15,7 +15,8 @@ if [ "${TARGET}" = "--release" ]; then
     : ${RELEASE_MODE:=--release}
 fi
 
-cargo build --target ${TARGET} ${RELEASE_MODE}
+# App 没有把 HDRImageView 的纹理打包进 bundle，需要嵌入静态库
+cargo build --target ${TARGET} --features embed-hdr-image ${RELEASE_MODE}
 
 # Copy .a file to iOS/ipadOS/visionOS project
 # 
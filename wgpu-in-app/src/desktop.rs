@@ -1,7 +1,12 @@
 use std::thread;
 
 use crate::WgpuCanvas;
-use app_surface::AppSurface;
+use crate::examples::supported_examples;
+use app_surface::{AppSurface, DeviceOptions, EdgeInsets, WinitInputTranslator};
+use clap::Parser;
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::Path;
 use std::sync::Arc;
 use std::time;
 
@@ -13,123 +18,420 @@ use winit::{
     window::{Window, WindowId},
 };
 
-pub fn run() -> Result<(), impl std::error::Error> {
+mod cli;
+pub use cli::{Args, Mode, PresentMode};
+
+/// 解析命令行参数后运行；指定了 `--frames` 时不创建窗口，离屏渲染后退出
+pub fn run() -> Result<(), Box<dyn Error>> {
     crate::init_logger();
+    let args = Args::parse();
 
-    let events_loop = EventLoop::new().unwrap();
-    let mut app = WgpuApp::default();
-    events_loop.run_app(&mut app)
+    if let (Some(frames), Some(output)) = (args.frames, args.output.as_deref()) {
+        return run_headless(&args, frames, output);
+    }
+    let events_loop = EventLoop::new()?;
+    let mut app = WgpuApp::new(args);
+    events_loop.run_app(&mut app)?;
+    Ok(())
 }
 
-const WAIT_TIME: time::Duration = time::Duration::from_millis(16);
-const POLL_SLEEP_TIME: time::Duration = time::Duration::from_millis(16);
-
-#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
-enum Mode {
-    #[allow(dead_code)]
-    Wait,
-    WaitUntil,
-    #[default]
-    Poll,
+/// 不创建窗口，离屏渲染 `frames` 帧并把每一帧保存为 `output` 目录下的 PNG
+fn run_headless(args: &Args, frames: u64, output: &Path) -> Result<(), Box<dyn Error>> {
+    std::fs::create_dir_all(output)?;
+    let mut app_view = futures_lite::future::block_on(AppSurface::try_new_offscreen_with_options(
+        args.size.unwrap_or(HEADLESS_SIZE),
+        &device_options(args),
+    ))?;
+    app_view.library_directory = library_directory();
+    let index = example_index(args, &app_view);
+    let mut canvas = WgpuCanvas::new(app_view, index);
+    if let Some(id) = args.example
+        && canvas.current_example().map(|desc| desc.id) != Some(id)
+    {
+        return Err(format!("Failed to create example {id}").into());
+    }
+
+    let frame_interval = args.frame_interval();
+    let mut next_frame = time::Instant::now();
+    for frame in 0..frames {
+        if let Some(interval) = frame_interval {
+            // 与窗口模式相同的帧间隔，示例的动画才会以相同的速度推进
+            thread::sleep(next_frame.saturating_duration_since(time::Instant::now()));
+            next_frame = (next_frame + interval).max(time::Instant::now());
+        }
+        canvas.app_surface.request_frame_capture();
+        canvas.enter_frame();
+        let path = output.join(format!("frame-{frame:04}.png"));
+        match canvas.app_surface.take_captured_frame() {
+            Some(result) => result?.save_png(&path)?,
+            None => log::warn!("Frame {frame} wasn't rendered"),
+        }
+    }
+    log::info!("{frames} frames saved to {}", output.display());
+    Ok(())
+}
+
+fn device_options(args: &Args) -> DeviceOptions {
+    let options = WgpuCanvas::device_options();
+    match args.backend {
+        Some(backends) => options.with_backends(backends),
+        None => options,
+    }
 }
 
-#[derive(Default)]
+/// 示例在当前适配器能运行的示例中的序号，未指定或不支持时从第一个示例开始
+fn example_index(args: &Args, app_view: &AppSurface) -> i32 {
+    let Some(id) = args.example else {
+        return 0;
+    };
+    match supported_examples(app_view)
+        .iter()
+        .position(|desc| desc.id == id)
+    {
+        Some(index) => index as i32,
+        None => {
+            log::warn!("Example {id} is not supported by this adapter");
+            0
+        }
+    }
+}
+
+/// 管线缓存保存在当前用户的缓存目录下，重启程序后仍可复用
+///
+/// 不能用系统临时目录：Linux 上的 /tmp 所有用户都可写入，而缓存数据会被直接交给驱动。
+/// 找不到缓存目录时返回空字符串，不使用管线缓存
+fn library_directory() -> String {
+    dirs::cache_dir()
+        .map(|dir| dir.join("wgpu-in-app").to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// 按 I 键模拟的安全区域，单位为逻辑像素
+const SIMULATED_SAFE_AREA: EdgeInsets = EdgeInsets::new(47.0, 0.0, 34.0, 0.0);
+
+/// 启用分析器后，每隔多少帧打印一次各通道的耗时
+const PROFILE_LOG_INTERVAL: u64 = 60;
+
+/// `wait-until` 模式下不限制帧率时的重绘间隔
+const WAIT_TIME: time::Duration = time::Duration::from_millis(16);
+
+/// 离屏渲染时未指定 `--size` 的渲染目标尺寸
+const HEADLESS_SIZE: (u32, u32) = (1280, 720);
+
 struct WgpuApp {
-    mode: Mode,
-    wait_cancelled: bool,
+    args: Args,
+    /// 下一帧最早的开始时间，用于限制帧率
+    next_frame: time::Instant,
     close_requested: bool,
-    canvas: Option<WgpuCanvas>,
+    /// 关闭主窗口时退出程序
+    main_window: Option<WindowId>,
+    canvases: HashMap<WindowId, WgpuCanvas>,
+    inputs: HashMap<WindowId, WinitInputTranslator>,
 }
 
 impl WgpuApp {
-    fn get_canvas(&mut self) -> &mut WgpuCanvas {
-        self.canvas.as_mut().unwrap()
+    fn new(args: Args) -> Self {
+        Self {
+            args,
+            next_frame: time::Instant::now(),
+            close_requested: false,
+            main_window: None,
+            canvases: HashMap::new(),
+            inputs: HashMap::new(),
+        }
+    }
+
+    /// 打开一个新窗口，与主窗口共享同一个 device
+    fn open_shared_window(&mut self, event_loop: &ActiveEventLoop) {
+        let Some(main_canvas) = self.main_window.and_then(|id| self.canvases.get(&id)) else {
+            return;
+        };
+        let window_attributes = Window::default_attributes()
+            .with_title("Wgpu on Desktop (shared device)")
+            .with_inner_size(winit::dpi::LogicalSize::new(480, 360));
+        let window = Arc::new(event_loop.create_window(window_attributes).unwrap());
+
+        match AppSurface::try_new_shared(window, &main_canvas.app_surface.ctx) {
+            Ok(app_view) => self.add_canvas(app_view),
+            Err(err) => log::error!("{err}"),
+        }
+    }
+
+    fn add_canvas(&mut self, mut app_view: AppSurface) {
+        // 默认优先使用低延迟的 Mailbox，不支持时保持默认的 Fifo
+        if let Err(err) = app_view.ctx.set_present_mode(self.args.present_mode.into()) {
+            log::info!("{err}");
+        }
+        // 允许按 P 键截图
+        if let Err(err) = app_view.ctx.set_capture_enabled(true) {
+            log::info!("{err}");
+        }
+
+        app_view.library_directory = library_directory();
+
+        let window_id = app_view.get_view().id();
+        self.inputs
+            .insert(window_id, WinitInputTranslator::new(app_view.scale_factor));
+        let index = example_index(&self.args, &app_view);
+        let canvas = WgpuCanvas::new(app_view, index);
+        for (index, desc) in canvas.examples().iter().enumerate() {
+            log::info!("[{index}] {}: {}", desc.name, desc.description);
+        }
+        canvas.app_surface.request_redraw();
+        self.canvases.insert(window_id, canvas);
     }
 }
 
 impl ApplicationHandler for WgpuApp {
     fn new_events(&mut self, _event_loop: &ActiveEventLoop, cause: StartCause) {
-        self.wait_cancelled = match cause {
-            StartCause::WaitCancelled { .. } => self.mode == Mode::WaitUntil,
-            _ => false,
+        // wait-until 模式下由定时器驱动重绘
+        if let StartCause::ResumeTimeReached { .. } = cause {
+            for canvas in self.canvases.values() {
+                canvas.app_surface.request_redraw();
+            }
         }
     }
 
     fn resumed(&mut self, event_loop: &ActiveEventLoop) {
-        if self.canvas.is_some() {
+        if self.main_window.is_some() {
+            // 从挂起中恢复，窗口仍然有效，用它们重建 surface
+            for canvas in self.canvases.values_mut() {
+                let Some(view) = canvas.app_surface.view.clone() else {
+                    continue;
+                };
+                if canvas.resume(view).is_ok() {
+                    canvas.app_surface.request_redraw();
+                }
+            }
             return;
         }
 
-        let window_attributes = Window::default_attributes().with_title("Wgpu on Desktop");
+        let mut window_attributes = Window::default_attributes().with_title("Wgpu on Desktop");
+        if let Some((width, height)) = self.args.size {
+            window_attributes =
+                window_attributes.with_inner_size(winit::dpi::LogicalSize::new(width, height));
+        }
         let window = Arc::new(event_loop.create_window(window_attributes).unwrap());
 
-        let app_view = futures_lite::future::block_on(AppSurface::new(window));
+        let app_view = match futures_lite::future::block_on(AppSurface::try_new_with_options(
+            window,
+            &device_options(&self.args),
+        )) {
+            Ok(app_view) => app_view,
+            Err(err) => {
+                log::error!("{err}");
+                event_loop.exit();
+                return;
+            }
+        };
+
+        self.main_window = Some(app_view.get_view().id());
+        self.add_canvas(app_view);
+    }
 
-        self.canvas = Some(WgpuCanvas::new(app_view, 0));
-        self.get_canvas().app_surface.request_redraw();
+    fn suspended(&mut self, _event_loop: &ActiveEventLoop) {
+        for canvas in self.canvases.values_mut() {
+            canvas.suspend();
+        }
     }
 
     fn window_event(
         &mut self,
-        _event_loop: &ActiveEventLoop,
-        _window_id: WindowId,
+        event_loop: &ActiveEventLoop,
+        window_id: WindowId,
         event: WindowEvent,
     ) {
-        match event {
-            WindowEvent::CloseRequested => {
+        if matches!(event, WindowEvent::CloseRequested) {
+            if self.main_window == Some(window_id) {
                 self.close_requested = true;
+            } else {
+                self.canvases.remove(&window_id);
+                self.inputs.remove(&window_id);
             }
+            return;
+        }
+        // N 键需要借用 self 打开新窗口，先于 canvas 的借用处理
+        if let WindowEvent::KeyboardInput {
+            event:
+                KeyEvent {
+                    physical_key: PhysicalKey::Code(KeyCode::KeyN),
+                    state: ElementState::Pressed,
+                    ..
+                },
+            ..
+        } = event
+        {
+            self.open_shared_window(event_loop);
+            return;
+        }
+        let Some(canvas) = self.canvases.get_mut(&window_id) else {
+            return;
+        };
+        // wait 模式下只在收到窗口与输入事件后重绘
+        if self.args.mode == Mode::Wait && !matches!(event, WindowEvent::RedrawRequested) {
+            canvas.app_surface.request_redraw();
+        }
+
+        match event {
             WindowEvent::Resized(size) => {
                 if size.width == 0 || size.height == 0 {
                     println!("Window minimized!");
                 } else {
-                    self.get_canvas().resize();
+                    canvas.resize();
                 }
             }
+            // 快捷键的按下与抬起都不转发给示例
             WindowEvent::KeyboardInput {
                 event:
                     KeyEvent {
                         physical_key: PhysicalKey::Code(key),
-                        state: ElementState::Pressed,
+                        state,
                         ..
                     },
                 ..
-            } => match key {
-                KeyCode::Digit1 => self.get_canvas().change_example(1),
-                KeyCode::Digit2 => self.get_canvas().change_example(2),
-                KeyCode::Digit3 => self.get_canvas().change_example(3),
-                KeyCode::Digit4 => self.get_canvas().change_example(4),
-                KeyCode::Digit5 => self.get_canvas().change_example(5),
-                _ => self.get_canvas().change_example(0),
-            },
+            } if is_app_shortcut(key) => {
+                if state != ElementState::Pressed {
+                    return;
+                }
+                if let Some(index) = digit_index(key) {
+                    // 数字键依次对应当前适配器能运行的示例
+                    if let Some(desc) = canvas.examples().get(index)
+                        && let Err(err) = canvas.change_example_by_id(desc.id)
+                    {
+                        log::error!("{err}");
+                    }
+                    return;
+                }
+                match key {
+                    KeyCode::KeyP => canvas.app_surface.request_frame_capture(),
+                    KeyCode::KeyH => canvas.set_hud_visible(!canvas.is_hud_visible()),
+                    KeyCode::KeyT => {
+                        let enabled = !canvas.app_surface.profiler.is_enabled();
+                        canvas.set_profiler_enabled(enabled);
+                        if enabled && !canvas.app_surface.profiler.has_gpu_timestamps() {
+                            log::info!(
+                                "TIMESTAMP_QUERY is not supported, only CPU time is profiled"
+                            );
+                        }
+                    }
+                    KeyCode::KeyI => {
+                        // 桌面窗口没有安全区域，模拟带刘海与 Home 指示条的手机屏幕以检查示例的布局
+                        let insets = if canvas.app_surface.safe_area == EdgeInsets::ZERO {
+                            SIMULATED_SAFE_AREA
+                        } else {
+                            EdgeInsets::ZERO
+                        };
+                        canvas.set_safe_area(insets);
+                    }
+                    _ => {}
+                }
+            }
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                canvas.set_scale_factor(scale_factor as f32);
+                if let Some(input) = self.inputs.get_mut(&window_id) {
+                    input.translate(&event);
+                }
+            }
             WindowEvent::RedrawRequested => {
-                self.get_canvas().app_surface.pre_present_notify();
+                canvas.app_surface.pre_present_notify();
 
-                self.get_canvas().enter_frame();
+                canvas.enter_frame();
+                save_captured_frame(canvas);
+                log_profile(canvas);
 
-                self.get_canvas().app_surface.request_redraw();
+                if self.args.mode == Mode::Poll {
+                    canvas.app_surface.request_redraw();
+                }
+            }
+            _ => {
+                // 其余的输入事件交给当前示例处理
+                if let Some(input) = self.inputs.get_mut(&window_id) {
+                    for event in input.translate(&event) {
+                        canvas.input(&event);
+                    }
+                }
             }
-            _ => (),
         }
     }
 
     fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
-        match self.mode {
+        if self.close_requested {
+            event_loop.exit();
+            return;
+        }
+        let frame_interval = self.args.frame_interval();
+        match self.args.mode {
             Mode::Wait => event_loop.set_control_flow(ControlFlow::Wait),
             Mode::WaitUntil => {
-                if !self.wait_cancelled {
-                    event_loop
-                        .set_control_flow(ControlFlow::WaitUntil(time::Instant::now() + WAIT_TIME));
+                // 被输入事件提前唤醒时，保持原定的重绘时间
+                let now = time::Instant::now();
+                if self.next_frame <= now {
+                    self.next_frame = now + frame_interval.unwrap_or(WAIT_TIME);
                 }
+                event_loop.set_control_flow(ControlFlow::WaitUntil(self.next_frame));
             }
             Mode::Poll => {
-                thread::sleep(POLL_SLEEP_TIME);
+                if let Some(interval) = frame_interval {
+                    thread::sleep(
+                        self.next_frame
+                            .saturating_duration_since(time::Instant::now()),
+                    );
+                    // 落后超过一帧时不追赶，从现在开始重新计时
+                    self.next_frame = (self.next_frame + interval).max(time::Instant::now());
+                }
                 event_loop.set_control_flow(ControlFlow::Poll);
             }
         };
+    }
+}
 
-        if self.close_requested {
-            event_loop.exit();
-        }
+/// 由 App 处理而不转发给示例的按键：0~5 切换示例，N 打开新窗口，P 截图，I 模拟安全区域，T 开关分析器，H 开关 HUD
+fn is_app_shortcut(key: KeyCode) -> bool {
+    digit_index(key).is_some()
+        || matches!(
+            key,
+            KeyCode::KeyH | KeyCode::KeyI | KeyCode::KeyN | KeyCode::KeyP | KeyCode::KeyT
+        )
+}
+
+fn digit_index(key: KeyCode) -> Option<usize> {
+    Some(match key {
+        KeyCode::Digit0 => 0,
+        KeyCode::Digit1 => 1,
+        KeyCode::Digit2 => 2,
+        KeyCode::Digit3 => 3,
+        KeyCode::Digit4 => 4,
+        KeyCode::Digit5 => 5,
+        _ => return None,
+    })
+}
+
+fn log_profile(canvas: &WgpuCanvas) {
+    if !canvas.app_surface.profiler.is_enabled()
+        || !canvas
+            .app_surface
+            .frame_clock
+            .frame_count()
+            .is_multiple_of(PROFILE_LOG_INTERVAL)
+    {
+        return;
+    }
+    if let Some(profile) = canvas.latest_profile() {
+        log::info!("{profile}");
+    }
+}
+
+/// 截图保存在当前工作目录下
+fn save_captured_frame(canvas: &WgpuCanvas) {
+    let Some(result) = canvas.app_surface.take_captured_frame() else {
+        return;
+    };
+    let path = format!(
+        "screenshot-{}.png",
+        canvas.app_surface.frame_clock.frame_count()
+    );
+    match result.and_then(|image| image.save_png(&path)) {
+        Ok(_) => log::info!("Screenshot saved to {path}"),
+        Err(err) => log::error!("{err}"),
     }
 }
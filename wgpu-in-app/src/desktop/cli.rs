@@ -0,0 +1,119 @@
+use crate::examples::EXAMPLES;
+use clap::builder::{PossibleValue, PossibleValuesParser, TypedValueParser};
+use clap::{Parser, ValueEnum};
+use std::path::PathBuf;
+
+/// 桌面端的命令行参数，帮助信息面向终端用户，使用英文
+#[derive(Parser, Debug)]
+#[command(version, about = "Run the wgpu-in-app examples on desktop")]
+pub struct Args {
+    /// Example to show on startup, by id or display name [default: boids]
+    #[arg(short, long, ignore_case = true, value_parser = example_parser())]
+    pub example: Option<&'static str>,
+
+    /// Comma separated wgpu backends, e.g. `vulkan,gl` [default: $WGPU_BACKEND or the primary backends]
+    #[arg(short, long, value_parser = parse_backends)]
+    pub backend: Option<wgpu::Backends>,
+
+    /// Present mode, falls back to `fifo` when the surface doesn't support it
+    #[arg(long, value_enum, default_value_t = PresentMode::Mailbox)]
+    pub present_mode: PresentMode,
+
+    /// Window size in logical pixels, or the render target size in physical pixels when headless
+    #[arg(long, value_name = "WIDTHxHEIGHT", value_parser = parse_size)]
+    pub size: Option<(u32, u32)>,
+
+    /// How the event loop schedules frames
+    #[arg(long, value_enum, default_value_t = Mode::Poll)]
+    pub mode: Mode,
+
+    /// Frame rate cap, 0 for uncapped; in `wait-until` mode it's also the redraw rate
+    #[arg(long, default_value_t = 60)]
+    pub fps: u32,
+
+    /// Render this many frames offscreen without a window, then exit
+    #[arg(long, requires = "output")]
+    pub frames: Option<u64>,
+
+    /// Directory that receives one PNG per frame in headless mode
+    #[arg(long, requires = "frames")]
+    pub output: Option<PathBuf>,
+}
+
+/// 事件循环的调度方式
+#[derive(ValueEnum, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Redraw only on window and input events
+    Wait,
+    /// Redraw on a timer at `--fps`
+    WaitUntil,
+    /// Redraw continuously, sleeping to honor `--fps`
+    #[default]
+    Poll,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PresentMode {
+    AutoVsync,
+    AutoNoVsync,
+    Fifo,
+    FifoRelaxed,
+    Immediate,
+    Mailbox,
+}
+
+impl From<PresentMode> for wgpu::PresentMode {
+    fn from(mode: PresentMode) -> Self {
+        match mode {
+            PresentMode::AutoVsync => wgpu::PresentMode::AutoVsync,
+            PresentMode::AutoNoVsync => wgpu::PresentMode::AutoNoVsync,
+            PresentMode::Fifo => wgpu::PresentMode::Fifo,
+            PresentMode::FifoRelaxed => wgpu::PresentMode::FifoRelaxed,
+            PresentMode::Immediate => wgpu::PresentMode::Immediate,
+            PresentMode::Mailbox => wgpu::PresentMode::Mailbox,
+        }
+    }
+}
+
+impl Args {
+    /// 两帧之间的最短间隔，不限制帧率时为 `None`
+    pub fn frame_interval(&self) -> Option<std::time::Duration> {
+        (self.fps > 0).then(|| std::time::Duration::from_secs_f64(1.0 / self.fps as f64))
+    }
+}
+
+/// 可选值为注册表中的示例，显示名称作为别名，解析结果为示例的 id
+fn example_parser() -> impl TypedValueParser<Value = &'static str> {
+    let values = EXAMPLES.iter().map(|desc| {
+        PossibleValue::new(desc.id)
+            .alias(desc.name)
+            .help(desc.description)
+    });
+    PossibleValuesParser::new(values).map(|value| {
+        EXAMPLES
+            .iter()
+            .find(|desc| {
+                desc.id.eq_ignore_ascii_case(&value) || desc.name.eq_ignore_ascii_case(&value)
+            })
+            .map(|desc| desc.id)
+            .expect("value was validated by PossibleValuesParser")
+    })
+}
+
+fn parse_backends(value: &str) -> Result<wgpu::Backends, String> {
+    let backends = wgpu::Backends::from_comma_list(value);
+    if backends.is_empty() {
+        return Err(format!(
+            "no valid backend in `{value}`, expected a comma separated list of vulkan, metal, dx12 or gl"
+        ));
+    }
+    Ok(backends)
+}
+
+fn parse_size(value: &str) -> Result<(u32, u32), String> {
+    let parse = |value: &str| value.trim().parse::<u32>().ok().filter(|v| *v > 0);
+    value
+        .split_once(['x', 'X'])
+        .and_then(|(width, height)| Some((parse(width)?, parse(height)?)))
+        .ok_or_else(|| format!("invalid size `{value}`, expected WIDTHxHEIGHT such as 1280x720"))
+}
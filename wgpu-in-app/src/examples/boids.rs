@@ -1,13 +1,15 @@
 //! copy from wgpu's example
 
 use super::Example;
-use app_surface::{AppSurface, SurfaceFrame};
+use app_surface::{
+    AppSurface, AssetError, AssetSource, FrameAcquireResult, ParameterError, ParameterInfo,
+    ParameterValue, SurfaceFrame,
+};
 use core::mem;
 use rand::{
     SeedableRng,
     distributions::{Distribution, Uniform},
 };
-use std::borrow::Cow;
 use wgpu::util::DeviceExt;
 
 // number of boid particles to simulate
@@ -16,6 +18,72 @@ const NUM_PARTICLES: u32 = 1500;
 // number of single-particle calculations (invocations) in each gpu work group
 const PARTICLES_PER_GROUP: u32 = 16;
 
+// simulation parameters uniform
+const SIM_PARAM_COUNT: usize = 7;
+const DEFAULT_SIM_PARAMS: [f32; SIM_PARAM_COUNT] = [
+    0.04,  // deltaT
+    0.1,   // rule1Distance
+    0.025, // rule2Distance
+    0.025, // rule3Distance
+    0.02,  // rule1Scale
+    0.05,  // rule2Scale
+    0.005, // rule3Scale
+];
+
+// 前 SIM_PARAM_COUNT 个参数与 DEFAULT_SIM_PARAMS 一一对应
+static PARAMETERS: &[ParameterInfo] = &[
+    ParameterInfo::float("delta_t", "Delta T", DEFAULT_SIM_PARAMS[0], 0.0, 0.1, 0.005),
+    ParameterInfo::float(
+        "rule1_distance",
+        "Cohesion Distance",
+        DEFAULT_SIM_PARAMS[1],
+        0.0,
+        0.2,
+        0.005,
+    ),
+    ParameterInfo::float(
+        "rule2_distance",
+        "Separation Distance",
+        DEFAULT_SIM_PARAMS[2],
+        0.0,
+        0.2,
+        0.005,
+    ),
+    ParameterInfo::float(
+        "rule3_distance",
+        "Alignment Distance",
+        DEFAULT_SIM_PARAMS[3],
+        0.0,
+        0.2,
+        0.005,
+    ),
+    ParameterInfo::float(
+        "rule1_scale",
+        "Cohesion Scale",
+        DEFAULT_SIM_PARAMS[4],
+        0.0,
+        0.1,
+        0.001,
+    ),
+    ParameterInfo::float(
+        "rule2_scale",
+        "Separation Scale",
+        DEFAULT_SIM_PARAMS[5],
+        0.0,
+        0.1,
+        0.001,
+    ),
+    ParameterInfo::float(
+        "rule3_scale",
+        "Alignment Scale",
+        DEFAULT_SIM_PARAMS[6],
+        0.0,
+        0.1,
+        0.001,
+    ),
+    ParameterInfo::bool("paused", "Paused", false),
+];
+
 #[allow(dead_code)]
 pub struct Boids {
     particle_bind_groups: Vec<wgpu::BindGroup>,
@@ -25,37 +93,28 @@ pub struct Boids {
     render_pipeline: wgpu::RenderPipeline,
     work_group_count: u32,
     frame_num: usize,
+    sim_param_data: [f32; SIM_PARAM_COUNT],
+    sim_param_buffer: wgpu::Buffer,
+    /// 暂停时只绘制，不再执行模拟
+    paused: bool,
 }
 
 impl Boids {
-    pub fn new(app_surface: &AppSurface) -> Self {
+    pub fn new(app_surface: &AppSurface, assets: &dyn AssetSource) -> Result<Self, AssetError> {
         let config = &app_surface.config;
         let device = &app_surface.device;
 
         let compute_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: None,
-            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(include_str!(
-                "../../wgsl_shader/compute.wgsl"
-            ))),
+            source: wgpu::ShaderSource::Wgsl(assets.load_string("wgsl_shader/compute.wgsl")?),
         });
         let draw_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: None,
-            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(include_str!(
-                "../../wgsl_shader/draw.wgsl"
-            ))),
+            source: wgpu::ShaderSource::Wgsl(assets.load_string("wgsl_shader/draw.wgsl")?),
         });
 
         // buffer for simulation parameters uniform
-        let sim_param_data = [
-            0.04f32, // deltaT
-            0.1,     // rule1Distance
-            0.025,   // rule2Distance
-            0.025,   // rule3Distance
-            0.02,    // rule1Scale
-            0.05,    // rule2Scale
-            0.005,   // rule3Scale
-        ]
-        .to_vec();
+        let sim_param_data = DEFAULT_SIM_PARAMS;
         let sim_param_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Simulation Parameter Buffer"),
             contents: bytemuck::cast_slice(&sim_param_data),
@@ -145,7 +204,7 @@ impl Boids {
             depth_stencil: None,
             multisample: wgpu::MultisampleState::default(),
             multiview_mask: None,
-            cache: None,
+            cache: app_surface.pipeline_cache.as_ref(),
         });
 
         // create compute pipeline
@@ -155,7 +214,7 @@ impl Boids {
             module: &compute_shader,
             entry_point: Some("main"),
             compilation_options: Default::default(),
-            cache: None,
+            cache: app_surface.pipeline_cache.as_ref(),
         });
 
         // buffer for the three 2d triangle vertices of each instance
@@ -220,7 +279,7 @@ impl Boids {
         let work_group_count =
             ((NUM_PARTICLES as f32) / (PARTICLES_PER_GROUP as f32)).ceil() as u32;
 
-        Self {
+        Ok(Self {
             particle_bind_groups,
             particle_buffers,
             vertices_buffer,
@@ -228,7 +287,10 @@ impl Boids {
             render_pipeline,
             work_group_count,
             frame_num: 0,
-        }
+            sim_param_data,
+            sim_param_buffer,
+            paused: false,
+        })
     }
 }
 
@@ -236,9 +298,10 @@ impl Example for Boids {
     fn enter_frame(&mut self, app_surface: &AppSurface) {
         let device = &app_surface.device;
         let queue = &app_surface.queue;
-        let frame_view = app_surface.get_current_frame_view(None);
-        if frame_view.is_none() { return; }
-        let (frame, view) = frame_view.unwrap();
+        let FrameAcquireResult::Frame(frame, view) = app_surface.get_current_frame_view(None)
+        else {
+            return;
+        };
         {
             // create render pass descriptor and its color attachments
             let color_attachments = [Some(wgpu::RenderPassColorAttachment {
@@ -266,16 +329,18 @@ impl Example for Boids {
             let mut command_encoder =
                 device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
 
-            command_encoder.push_debug_group("compute boid movement");
-            {
-                // compute pass
-                let mut cpass =
-                    command_encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
-                cpass.set_pipeline(&self.compute_pipeline);
-                cpass.set_bind_group(0, &self.particle_bind_groups[self.frame_num % 2], &[]);
-                cpass.dispatch_workgroups(self.work_group_count, 1, 1);
+            if !self.paused {
+                command_encoder.push_debug_group("compute boid movement");
+                {
+                    // compute pass
+                    let mut cpass =
+                        command_encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+                    cpass.set_pipeline(&self.compute_pipeline);
+                    cpass.set_bind_group(0, &self.particle_bind_groups[self.frame_num % 2], &[]);
+                    cpass.dispatch_workgroups(self.work_group_count, 1, 1);
+                }
+                command_encoder.pop_debug_group();
             }
-            command_encoder.pop_debug_group();
 
             command_encoder.push_debug_group("render boids");
             {
@@ -297,7 +362,63 @@ impl Example for Boids {
             queue.submit(Some(command_encoder.finish()));
         }
         frame.present();
-        // update frame count
-        self.frame_num += 1;
+        // 暂停时两个缓冲区不再交替
+        if !self.paused {
+            self.frame_num += 1;
+        }
+    }
+
+    fn rebuild(
+        &mut self,
+        app_surface: &mut AppSurface,
+        assets: &dyn AssetSource,
+    ) -> Result<(), AssetError> {
+        let (sim_param_data, paused) = (self.sim_param_data, self.paused);
+        *self = Self::new(app_surface, assets)?;
+        self.sim_param_data = sim_param_data;
+        self.paused = paused;
+        app_surface.queue.write_buffer(
+            &self.sim_param_buffer,
+            0,
+            bytemuck::cast_slice(&self.sim_param_data),
+        );
+        Ok(())
+    }
+
+    fn parameters(&self) -> &'static [ParameterInfo] {
+        PARAMETERS
+    }
+
+    fn parameter(&self, name: &str) -> Option<ParameterValue> {
+        let index = PARAMETERS.iter().position(|p| p.name == name)?;
+        Some(match self.sim_param_data.get(index) {
+            Some(value) => ParameterValue::Float(*value),
+            None => ParameterValue::Bool(self.paused),
+        })
+    }
+
+    fn set_parameter(
+        &mut self,
+        app_surface: &AppSurface,
+        name: &str,
+        value: ParameterValue,
+    ) -> Result<(), ParameterError> {
+        let index = PARAMETERS
+            .iter()
+            .position(|p| p.name == name)
+            .ok_or_else(|| ParameterError::Unknown(name.to_string()))?;
+        match value {
+            ParameterValue::Float(value) if index < SIM_PARAM_COUNT => {
+                self.sim_param_data[index] = value;
+                app_surface.queue.write_buffer(
+                    &self.sim_param_buffer,
+                    (index * mem::size_of::<f32>()) as wgpu::BufferAddress,
+                    bytemuck::bytes_of(&value),
+                );
+            }
+            ParameterValue::Bool(paused) if index == SIM_PARAM_COUNT => self.paused = paused,
+            _ => return Err(ParameterError::Unknown(name.to_string())),
+        }
+        Ok(())
     }
 }
@@ -1,10 +1,22 @@
 //! copy from wgpu's example
 
 use super::Example;
-use app_surface::{AppSurface, SurfaceFrame};
+use app_surface::{
+    AppSurface, AssetError, AssetSource, Camera, FrameAcquireResult, InputEvent, ParameterError,
+    ParameterInfo, ParameterValue, SurfaceFrame,
+};
 use bytemuck::{Pod, Zeroable};
 use core::mem::size_of;
 use wgpu::util::DeviceExt;
+
+static PARAMETERS: &[ParameterInfo] = &[
+    // 适配器不支持 POLYGON_MODE_LINE 时没有效果
+    ParameterInfo::bool("wireframe", "Wireframe", true),
+    ParameterInfo::color("clear_color", "Background", [0.1, 0.2, 0.3, 1.0]),
+    Camera::MODE_PARAMETER,
+    Camera::PROJECTION_PARAMETER,
+];
+
 #[repr(C)]
 #[derive(Clone, Copy, Pod, Zeroable)]
 struct Vertex {
@@ -91,10 +103,13 @@ pub struct Cube {
     uniform_buf: wgpu::Buffer,
     pipeline: wgpu::RenderPipeline,
     pipeline_wire: Option<wgpu::RenderPipeline>,
+    wireframe: bool,
+    clear_color: wgpu::Color,
+    camera: Camera,
 }
 
 impl Cube {
-    pub fn new(app_surface: &AppSurface) -> Self {
+    pub fn new(app_surface: &AppSurface, assets: &dyn AssetSource) -> Result<Self, AssetError> {
         let config = &app_surface.config;
         let queue = &app_surface.queue;
         let device = &app_surface.device;
@@ -177,7 +192,8 @@ impl Cube {
         );
 
         // Create other resources
-        let mx_total = Self::generate_matrix(config.width as f32 / config.height as f32);
+        let camera = Self::create_camera(app_surface);
+        let mx_total = camera.view_projection();
         let mx_ref: &[f32; 16] = mx_total.as_ref();
         let uniform_buf = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Uniform Buffer"),
@@ -201,8 +217,10 @@ impl Cube {
             label: None,
         });
 
-        let shader =
-            device.create_shader_module(wgpu::include_wgsl!("../../wgsl_shader/cube.wgsl"));
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("cube.wgsl"),
+            source: wgpu::ShaderSource::Wgsl(assets.load_string("wgsl_shader/cube.wgsl")?),
+        });
 
         let vertex_buffers = [wgpu::VertexBufferLayout {
             array_stride: vertex_size as wgpu::BufferAddress,
@@ -243,11 +261,11 @@ impl Cube {
             depth_stencil: None,
             multisample: wgpu::MultisampleState::default(),
             multiview_mask: None,
-            cache: None,
+            cache: app_surface.pipeline_cache.as_ref(),
         });
 
-        let pipeline_wire = if device
-            .features()
+        let pipeline_wire = if app_surface
+            .granted_features
             .contains(wgpu::Features::POLYGON_MODE_LINE)
         {
             let pipeline_wire = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
@@ -285,14 +303,14 @@ impl Cube {
                 depth_stencil: None,
                 multisample: wgpu::MultisampleState::default(),
                 multiview_mask: None,
-                cache: None,
+                cache: app_surface.pipeline_cache.as_ref(),
             });
             Some(pipeline_wire)
         } else {
             None
         };
         // Done
-        Self {
+        Ok(Self {
             vertex_buf,
             index_buf,
             index_count: index_data.len(),
@@ -300,36 +318,53 @@ impl Cube {
             uniform_buf,
             pipeline,
             pipeline_wire,
-        }
+            wireframe: true,
+            clear_color: wgpu::Color {
+                r: 0.1,
+                g: 0.2,
+                b: 0.3,
+                a: 1.0,
+            },
+            camera,
+        })
     }
 
-    fn generate_matrix(aspect_ratio: f32) -> glam::Mat4 {
-        let projection =
-            glam::Mat4::perspective_rh(core::f32::consts::FRAC_PI_4, aspect_ratio, 1.0, 10.0);
-        let view = glam::Mat4::look_at_rh(
+    fn create_camera(app_surface: &AppSurface) -> Camera {
+        let mut camera = Camera::look_at(
             glam::Vec3::new(1.5f32, -5.0, 3.0),
             glam::Vec3::ZERO,
             glam::Vec3::Z,
-        );
-        projection * view
+        )
+        .with_depth_range(1.0, 20.0)
+        .with_distance_range(3.0, 12.0);
+        camera.set_viewport(app_surface.view_size().logical_size());
+        camera
+    }
+
+    fn write_matrix(&self, queue: &wgpu::Queue) {
+        let mx_total = self.camera.view_projection();
+        let mx_ref: &[f32; 16] = mx_total.as_ref();
+        queue.write_buffer(&self.uniform_buf, 0, bytemuck::cast_slice(mx_ref));
     }
 }
 
 impl Example for Cube {
     fn resize(&mut self, app_surface: &AppSurface) {
-        let config = &app_surface.config;
-        let queue = &app_surface.queue;
-        let mx_total = Self::generate_matrix(config.width as f32 / config.height as f32);
-        let mx_ref: &[f32; 16] = mx_total.as_ref();
-        queue.write_buffer(&self.uniform_buf, 0, bytemuck::cast_slice(mx_ref));
+        self.camera
+            .set_viewport(app_surface.view_size().logical_size());
+        self.write_matrix(&app_surface.queue);
     }
 
     fn enter_frame(&mut self, app_surface: &AppSurface) {
         let device = &app_surface.device;
         let queue = &app_surface.queue;
-        let frame_view = app_surface.get_current_frame_view(None);
-        if frame_view.is_none() { return; }
-        let (frame, view) = frame_view.unwrap();
+        if self.camera.update(app_surface.frame_clock.delta_seconds()) {
+            self.write_matrix(queue);
+        }
+        let FrameAcquireResult::Frame(frame, view) = app_surface.get_current_frame_view(None)
+        else {
+            return;
+        };
         let mut encoder =
             device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
         {
@@ -339,12 +374,7 @@ impl Example for Cube {
                     view: &view,
                     resolve_target: None,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color {
-                            r: 0.1,
-                            g: 0.2,
-                            b: 0.3,
-                            a: 1.0,
-                        }),
+                        load: wgpu::LoadOp::Clear(self.clear_color),
                         // load: wgpu::LoadOp::Load,
                         store: wgpu::StoreOp::Store,
                     },
@@ -360,7 +390,9 @@ impl Example for Cube {
             rpass.pop_debug_group();
             rpass.insert_debug_marker("Draw!");
             rpass.draw_indexed(0..self.index_count as u32, 0, 0..1);
-            if let Some(ref pipe) = self.pipeline_wire {
+            if let Some(ref pipe) = self.pipeline_wire
+                && self.wireframe
+            {
                 rpass.set_pipeline(pipe);
                 rpass.draw_indexed(0..self.index_count as u32, 0, 0..1);
             }
@@ -368,4 +400,59 @@ impl Example for Cube {
         queue.submit(Some(encoder.finish()));
         frame.present();
     }
+
+    fn rebuild(
+        &mut self,
+        app_surface: &mut AppSurface,
+        assets: &dyn AssetSource,
+    ) -> Result<(), AssetError> {
+        let (wireframe, clear_color) = (self.wireframe, self.clear_color);
+        let camera = self.camera.clone();
+        *self = Self::new(app_surface, assets)?;
+        self.wireframe = wireframe;
+        self.clear_color = clear_color;
+        self.camera = camera;
+        self.write_matrix(&app_surface.queue);
+        Ok(())
+    }
+
+    fn input(&mut self, _app_surface: &AppSurface, event: &InputEvent) {
+        self.camera.handle_input(event);
+    }
+
+    fn parameters(&self) -> &'static [ParameterInfo] {
+        PARAMETERS
+    }
+
+    fn parameter(&self, name: &str) -> Option<ParameterValue> {
+        let c = self.clear_color;
+        match name {
+            "wireframe" => Some(ParameterValue::Bool(self.wireframe)),
+            "clear_color" => Some(ParameterValue::Color([
+                c.r as f32, c.g as f32, c.b as f32, c.a as f32,
+            ])),
+            _ => self.camera.parameter(name),
+        }
+    }
+
+    fn set_parameter(
+        &mut self,
+        _app_surface: &AppSurface,
+        name: &str,
+        value: ParameterValue,
+    ) -> Result<(), ParameterError> {
+        match (name, value) {
+            ("wireframe", ParameterValue::Bool(wireframe)) => self.wireframe = wireframe,
+            ("clear_color", ParameterValue::Color([r, g, b, a])) => {
+                self.clear_color = wgpu::Color {
+                    r: r as f64,
+                    g: g as f64,
+                    b: b as f64,
+                    a: a as f64,
+                };
+            }
+            _ => return self.camera.set_parameter(name, value),
+        }
+        Ok(())
+    }
 }
@@ -1,30 +1,65 @@
 use super::Example;
-use app_surface::{AppSurface, SurfaceFrame};
-use std::borrow::Cow;
+use app_surface::{
+    AppSurface, AssetError, AssetSource, FrameAcquireResult, SurfaceFrame, ViewFormatNeed,
+};
 use wgpu::PrimitiveTopology;
 use wgpu::util::DeviceExt;
 use wgpu::{AstcBlock, AstcChannel, TextureFormat};
 
+/// 体积较大，默认不嵌入程序，各平台获取此文件的方式：
+/// - 桌面端：开发时直接读取仓库中的文件
+/// - iOS 与 Android：`apple_lib_build.sh` 与 `android_lib_build.sh` 开启了 `embed-hdr-image` 特性，文件嵌入在库中
+/// - 其它情况：开启 `embed-hdr-image` 特性，或在 iOS 与 Android 上放在 `library_directory/assets` 下
+pub const HDR_IMAGE: &str = "assets/8x8.astc";
+const HDR_IMAGE_SIZE: (u32, u32) = (4096, 2048);
+/// .astc 文件头的长度
+const ASTC_HEADER_SIZE: usize = 16;
+/// 8x8 的 ASTC 块，每块 16 字节
+const HDR_IMAGE_DATA_SIZE: usize = (HDR_IMAGE_SIZE.0 / 8 * HDR_IMAGE_SIZE.1 / 8 * 16) as usize;
+
 #[allow(dead_code)]
 pub struct HDRImageView {
+    format: TextureFormat,
     bind_group: wgpu::BindGroup,
     pipeline: wgpu::RenderPipeline,
 }
 
 impl HDRImageView {
-    pub fn new(app_surface: &mut AppSurface) -> Self {
-        let hdr_pixel_format = wgpu::TextureFormat::Rgba16Float;
-        // let hdr_pixel_format = wgpu::TextureFormat::Rgb10a2Unorm;
+    pub fn new(app_surface: &mut AppSurface, assets: &dyn AssetSource) -> Result<Self, AssetError> {
+        // 先加载纹理，加载失败时不改动 surface 的格式
+        let astc_data = assets.load(HDR_IMAGE)?;
+        if astc_data.len() != ASTC_HEADER_SIZE + HDR_IMAGE_DATA_SIZE {
+            return Err(AssetError::InvalidData {
+                path: HDR_IMAGE.to_string(),
+                reason: format!(
+                    "expected {} bytes, got {}",
+                    ASTC_HEADER_SIZE + HDR_IMAGE_DATA_SIZE,
+                    astc_data.len()
+                ),
+            });
+        }
 
-        app_surface.ctx.update_config_format(hdr_pixel_format);
-        log::info!("update_config_format: Rgba16Float");
+        // 优先使用可以显示 HDR 的格式，都不支持时退回 SDR
+        let negotiation = app_surface.ctx.apply_preferred_format(
+            &[
+                TextureFormat::Rgba16Float,
+                TextureFormat::Rgb10a2Unorm,
+                TextureFormat::Bgra8UnormSrgb,
+                TextureFormat::Rgba8UnormSrgb,
+            ],
+            ViewFormatNeed::None,
+        );
+        for (format, reason) in &negotiation.rejected {
+            log::info!("{format:?} rejected: {reason:?}");
+        }
+        let hdr_pixel_format = negotiation.format.unwrap_or(app_surface.config.format);
+        log::info!("HDRImageView uses {hdr_pixel_format:?}");
         let device = &app_surface.device;
         let queue = &app_surface.queue;
 
-        let astc_data = &include_bytes!("../../assets/8x8.astc")[..];
         let size = wgpu::Extent3d {
-            width: 4096,
-            height: 2048,
+            width: HDR_IMAGE_SIZE.0,
+            height: HDR_IMAGE_SIZE.1,
             depth_or_array_layers: 1,
         };
 
@@ -44,7 +79,7 @@ impl HDRImageView {
                 view_formats: &[],
             },
             wgpu::util::TextureDataOrder::MipMajor,
-            &astc_data[16..],
+            &astc_data[ASTC_HEADER_SIZE..],
         );
         let texture_view = texture.create_view(&wgpu::TextureViewDescriptor {
             label: None,
@@ -100,9 +135,7 @@ impl HDRImageView {
         });
         let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: None,
-            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(include_str!(
-                "../../wgsl_shader/bufferless.wgsl"
-            ))),
+            source: wgpu::ShaderSource::Wgsl(assets.load_string("wgsl_shader/bufferless.wgsl")?),
         });
 
         let pipeline_vertex_buffers = [];
@@ -141,13 +174,14 @@ impl HDRImageView {
             depth_stencil: None,
             multisample: wgpu::MultisampleState::default(),
             multiview_mask: None,
-            cache: None,
+            cache: app_surface.pipeline_cache.as_ref(),
         });
 
-        Self {
+        Ok(Self {
+            format: hdr_pixel_format,
             bind_group,
             pipeline,
-        }
+        })
     }
 }
 
@@ -155,9 +189,11 @@ impl Example for HDRImageView {
     fn enter_frame(&mut self, app_surface: &AppSurface) {
         let device = &app_surface.device;
         let queue = &app_surface.queue;
-        let frame_view = app_surface.get_current_frame_view(None);
-        if frame_view.is_none() { return; }
-        let (frame, view) = frame_view.unwrap();
+        let FrameAcquireResult::Frame(frame, view) =
+            app_surface.get_current_frame_view(Some(self.format))
+        else {
+            return;
+        };
         let mut encoder =
             device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
         {
@@ -181,4 +217,13 @@ impl Example for HDRImageView {
         queue.submit(Some(encoder.finish()));
         frame.present();
     }
+
+    fn rebuild(
+        &mut self,
+        app_surface: &mut AppSurface,
+        assets: &dyn AssetSource,
+    ) -> Result<(), AssetError> {
+        *self = Self::new(app_surface, assets)?;
+        Ok(())
+    }
 }
@@ -1,9 +1,48 @@
-use app_surface::AppSurface;
+use app_surface::{
+    AppSurface, AssetChain, AssetError, AssetSource, EmbeddedAssets, InputEvent, ParameterError,
+    ParameterInfo, ParameterValue, Touch,
+};
 
 #[allow(dead_code)]
 pub trait Example {
     fn resize(&mut self, _app_surface: &AppSurface) {}
     fn enter_frame(&mut self, app_surface: &AppSurface);
+    /// 设备丢失并恢复后，旧 device 创建的 GPU 资源都已失效，需要全部重建
+    fn rebuild(
+        &mut self,
+        app_surface: &mut AppSurface,
+        assets: &dyn AssetSource,
+    ) -> Result<(), AssetError>;
+    /// App 进入后台、surface 释放之前调用，可以释放能重新创建的大块临时资源（比如全屏的渲染目标）
+    ///
+    /// 挂起期间不会调用 `enter_frame`
+    fn suspend(&mut self, _app_surface: &AppSurface) {}
+    /// 回到前台、surface 重建之后调用，紧接着会调用 `resize`
+    fn resume(&mut self, _app_surface: &AppSurface) {}
+    fn input(&mut self, _app_surface: &AppSurface, _event: &InputEvent) {}
+    /// 同一时刻多个指针的触摸事件，默认逐个转发给 `input`
+    fn touches(&mut self, app_surface: &AppSurface, touches: &[Touch]) {
+        for touch in touches {
+            self.input(app_surface, &InputEvent::Pointer(*touch));
+        }
+    }
+    /// 可由宿主 App 调节的参数，切换示例后恢复为默认值，设备恢复后保持不变
+    fn parameters(&self) -> &'static [ParameterInfo] {
+        &[]
+    }
+    /// 参数的当前值，没有此参数时为 `None`
+    fn parameter(&self, _name: &str) -> Option<ParameterValue> {
+        None
+    }
+    /// `value` 已按 `parameters` 中的声明校验过类型，数值也已限制在取值范围内
+    fn set_parameter(
+        &mut self,
+        _app_surface: &AppSurface,
+        name: &str,
+        _value: ParameterValue,
+    ) -> Result<(), ParameterError> {
+        Err(ParameterError::Unknown(name.to_string()))
+    }
 }
 
 pub struct Empty;
@@ -14,6 +53,13 @@ impl Empty {
 }
 impl Example for Empty {
     fn enter_frame(&mut self, _app_surface: &AppSurface) {}
+    fn rebuild(
+        &mut self,
+        _app_surface: &mut AppSurface,
+        _assets: &dyn AssetSource,
+    ) -> Result<(), AssetError> {
+        Ok(())
+    }
 }
 
 mod boids;
@@ -34,3 +80,71 @@ pub use shadow::Shadow;
 
 mod hdr_image_view;
 pub use hdr_image_view::HDRImageView;
+
+mod registry;
+pub use registry::*;
+
+/// 着色器体积很小，始终嵌入程序，作为文件系统中找不到时的后备
+static EMBEDDED_SHADERS: &[(&str, &[u8])] = &[
+    (
+        "wgsl_shader/bufferless.wgsl",
+        include_bytes!("../../wgsl_shader/bufferless.wgsl"),
+    ),
+    (
+        "wgsl_shader/compute.wgsl",
+        include_bytes!("../../wgsl_shader/compute.wgsl"),
+    ),
+    (
+        "wgsl_shader/cube.wgsl",
+        include_bytes!("../../wgsl_shader/cube.wgsl"),
+    ),
+    (
+        "wgsl_shader/draw.wgsl",
+        include_bytes!("../../wgsl_shader/draw.wgsl"),
+    ),
+    (
+        "wgsl_shader/hud.wgsl",
+        include_bytes!("../../wgsl_shader/hud.wgsl"),
+    ),
+    (
+        "wgsl_shader/msaa_line.wgsl",
+        include_bytes!("../../wgsl_shader/msaa_line.wgsl"),
+    ),
+    (
+        "wgsl_shader/shadow.wgsl",
+        include_bytes!("../../wgsl_shader/shadow.wgsl"),
+    ),
+    (
+        "wgsl_shader/terrain.wgsl",
+        include_bytes!("../../wgsl_shader/terrain.wgsl"),
+    ),
+    (
+        "wgsl_shader/water.wgsl",
+        include_bytes!("../../wgsl_shader/water.wgsl"),
+    ),
+];
+
+#[cfg(feature = "embed-hdr-image")]
+static EMBEDDED_HDR_IMAGE: &[(&str, &[u8])] = &[(
+    hdr_image_view::HDR_IMAGE,
+    include_bytes!("../../assets/8x8.astc"),
+)];
+
+/// 示例的资源源：依次查找 `library_directory`（仅 iOS 与 Android）、开发时的仓库目录（仅桌面端）与嵌入资源
+///
+/// 只有移动端的 `library_directory` 是 App 私有的目录；桌面端的是用户缓存目录，
+/// 不能让放在那里的文件替换示例的着色器
+pub fn example_assets(library_directory: &str) -> AssetChain {
+    let mut assets = AssetChain::new();
+    if cfg!(any(target_os = "ios", target_os = "android")) && !library_directory.is_empty() {
+        assets.push(app_surface::FileSystemAssets::new(library_directory));
+    }
+    #[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
+    assets.push(app_surface::FileSystemAssets::new(env!(
+        "CARGO_MANIFEST_DIR"
+    )));
+    assets.push(EmbeddedAssets::new(EMBEDDED_SHADERS));
+    #[cfg(feature = "embed-hdr-image")]
+    assets.push(EmbeddedAssets::new(EMBEDDED_HDR_IMAGE));
+    assets
+}
@@ -10,14 +10,24 @@
 //! *   Vertices and Indices describe the two points that make up a line.
 
 use super::Example;
-use app_surface::{AppSurface, SurfaceFrame};
+use app_surface::{
+    AppSurface, AssetError, AssetSource, FrameAcquireResult, ParameterError, ParameterInfo,
+    ParameterValue, SurfaceFrame,
+};
 
 use core::iter;
-use std::borrow::Cow;
 
 use bytemuck::{Pod, Zeroable};
 use wgpu::util::DeviceExt;
 
+// 选项的序号 i 对应 2^i 倍采样，适配器不支持时回退到支持的最大采样数
+static PARAMETERS: &[ParameterInfo] = &[ParameterInfo::enumeration(
+    "sample_count",
+    "MSAA",
+    3,
+    &["1x", "2x", "4x", "8x"],
+)];
+
 #[repr(C)]
 #[derive(Clone, Copy, Pod, Zeroable)]
 struct Vertex {
@@ -29,16 +39,19 @@ pub struct MSAALine {
     bundle: wgpu::RenderBundle,
     shader: wgpu::ShaderModule,
     pipeline_layout: wgpu::PipelineLayout,
-    multisampled_framebuffer: wgpu::TextureView,
+    /// 挂起期间释放，为 `None`
+    multisampled_framebuffer: Option<wgpu::TextureView>,
     vertex_buffer_list: Vec<wgpu::Buffer>,
     vertex_count: u32,
     sample_count: u32,
     rebuild_bundle: bool,
     config: wgpu::SurfaceConfiguration,
+    /// 安全区域对应的视口：x, y, width, height
+    viewport: [f32; 4],
 }
 
 impl MSAALine {
-    pub fn new(app_surface: &mut AppSurface) -> Self {
+    pub fn new(app_surface: &mut AppSurface, assets: &dyn AssetSource) -> Result<Self, AssetError> {
         // Only srgb format can show real MSAA effect on metal backend.
         if app_surface.adapter.get_info().backend == wgpu::Backend::Metal {
             let msaa_format = app_surface.config.format.add_srgb_suffix();
@@ -48,27 +61,11 @@ impl MSAALine {
         let config = &app_surface.config;
         let device = &app_surface.device;
 
-        let sample_flags = app_surface
-            .adapter
-            .get_texture_format_features(config.format)
-            .flags;
-        let sample_count = {
-            if sample_flags.contains(wgpu::TextureFormatFeatureFlags::MULTISAMPLE_X8) {
-                8
-            } else if sample_flags.contains(wgpu::TextureFormatFeatureFlags::MULTISAMPLE_X4) {
-                4
-            } else if sample_flags.contains(wgpu::TextureFormatFeatureFlags::MULTISAMPLE_X2) {
-                2
-            } else {
-                1
-            }
-        };
+        let sample_count = Self::supported_sample_count(app_surface, config.format, 8);
 
         let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: None,
-            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(include_str!(
-                "../../wgsl_shader/msaa_line.wgsl"
-            ))),
+            source: wgpu::ShaderSource::Wgsl(assets.load_string("wgsl_shader/msaa_line.wgsl")?),
         });
 
         let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
@@ -117,7 +114,7 @@ impl MSAALine {
         let vertex_count = max as u32 * 2;
 
         let bundle = Self::create_bundle(
-            device,
+            app_surface,
             config,
             &shader,
             &pipeline_layout,
@@ -126,21 +123,38 @@ impl MSAALine {
             vertex_count,
         );
 
-        Self {
+        Ok(Self {
             bundle,
             shader,
             pipeline_layout,
-            multisampled_framebuffer,
+            multisampled_framebuffer: Some(multisampled_framebuffer),
             vertex_buffer_list,
             vertex_count,
             sample_count,
             rebuild_bundle: false,
             config: config.clone(),
-        }
+            viewport: Self::safe_viewport(app_surface),
+        })
+    }
+
+    /// 不超过 `max` 的最大可用采样数
+    fn supported_sample_count(
+        app_surface: &AppSurface,
+        format: wgpu::TextureFormat,
+        max: u32,
+    ) -> u32 {
+        let flags = app_surface
+            .adapter
+            .get_texture_format_features(format)
+            .flags;
+        [8, 4, 2]
+            .into_iter()
+            .find(|&count| count <= max && flags.sample_count_supported(count))
+            .unwrap_or(1)
     }
 
     fn create_bundle(
-        device: &wgpu::Device,
+        app_surface: &AppSurface,
         config: &wgpu::SurfaceConfiguration,
         shader: &wgpu::ShaderModule,
         pipeline_layout: &wgpu::PipelineLayout,
@@ -148,6 +162,7 @@ impl MSAALine {
         vertex_buffer_list: &Vec<wgpu::Buffer>,
         vertex_count: u32,
     ) -> wgpu::RenderBundle {
+        let device = &app_surface.device;
         let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
             label: None,
             layout: Some(pipeline_layout),
@@ -178,7 +193,7 @@ impl MSAALine {
                 ..Default::default()
             },
             multiview_mask: None,
-            cache: None,
+            cache: app_surface.pipeline_cache.as_ref(),
         });
         let mut encoder =
             device.create_render_bundle_encoder(&wgpu::RenderBundleEncoderDescriptor {
@@ -200,6 +215,16 @@ impl MSAALine {
         })
     }
 
+    fn safe_viewport(app_surface: &AppSurface) -> [f32; 4] {
+        let (origin, extent) = app_surface.view_size().safe_rect_physical();
+        // 视图的实时尺寸可能与帧缓冲尺寸暂时不一致，视口不能超出帧缓冲
+        let config = &app_surface.config;
+        let target = glam::Vec2::new(config.width as f32, config.height as f32);
+        let origin = origin.clamp(glam::Vec2::ZERO, target - 1.0);
+        let extent = extent.clamp(glam::Vec2::ONE, target - origin);
+        [origin.x, origin.y, extent.x, extent.y]
+    }
+
     fn create_multisampled_framebuffer(
         device: &wgpu::Device,
         config: &wgpu::SurfaceConfiguration,
@@ -229,12 +254,18 @@ impl MSAALine {
 
 impl Example for MSAALine {
     fn resize(&mut self, app_surface: &AppSurface) {
+        self.viewport = Self::safe_viewport(app_surface);
         self.config = app_surface.config.clone();
-        self.multisampled_framebuffer = Self::create_multisampled_framebuffer(
+        self.multisampled_framebuffer = Some(Self::create_multisampled_framebuffer(
             &app_surface.device,
             &app_surface.config,
             self.sample_count,
-        );
+        ));
+    }
+
+    /// 多重采样的帧缓冲是全屏的，8x 时占用的显存是 surface 的 8 倍，在后台时释放掉
+    fn suspend(&mut self, _app_surface: &AppSurface) {
+        self.multisampled_framebuffer = None;
     }
 
     fn enter_frame(&mut self, app_surface: &AppSurface) {
@@ -242,7 +273,7 @@ impl Example for MSAALine {
         let queue = &app_surface.queue;
         if self.rebuild_bundle {
             self.bundle = Self::create_bundle(
-                device,
+                app_surface,
                 &self.config,
                 &self.shader,
                 &self.pipeline_layout,
@@ -250,16 +281,22 @@ impl Example for MSAALine {
                 &self.vertex_buffer_list,
                 self.vertex_count,
             );
-            self.multisampled_framebuffer =
-                MSAALine::create_multisampled_framebuffer(device, &self.config, self.sample_count);
+            self.multisampled_framebuffer = Some(MSAALine::create_multisampled_framebuffer(
+                device,
+                &self.config,
+                self.sample_count,
+            ));
             self.rebuild_bundle = false;
         }
-        let frame_view =
-            app_surface.get_current_frame_view(Some(self.config.format.add_srgb_suffix()));
-        if frame_view.is_none() {
+        // 恢复后由 `resize` 重新创建
+        let Some(ref multisampled_framebuffer) = self.multisampled_framebuffer else {
             return;
-        }
-        let (frame, view) = frame_view.unwrap();
+        };
+        let FrameAcquireResult::Frame(frame, view) =
+            app_surface.get_current_frame_view(Some(self.config.format.add_srgb_suffix()))
+        else {
+            return;
+        };
         let mut encoder =
             device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
         {
@@ -275,7 +312,7 @@ impl Example for MSAALine {
                 }
             } else {
                 wgpu::RenderPassColorAttachment {
-                    view: &self.multisampled_framebuffer,
+                    view: multisampled_framebuffer,
                     resolve_target: Some(&view),
                     ops: wgpu::Operations {
                         load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
@@ -287,16 +324,63 @@ impl Example for MSAALine {
                 }
             };
 
-            encoder
-                .begin_render_pass(&wgpu::RenderPassDescriptor {
-                    label: None,
-                    color_attachments: &[Some(rpass_color_attachment)],
-                    ..Default::default()
-                })
-                .execute_bundles(iter::once(&self.bundle));
+            let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: None,
+                color_attachments: &[Some(rpass_color_attachment)],
+                ..Default::default()
+            });
+            // 线条只绘制在安全区域内，避免被刘海与 Home 指示条遮挡
+            let [x, y, width, height] = self.viewport;
+            rpass.set_viewport(x, y, width, height, 0.0, 1.0);
+            rpass.execute_bundles(iter::once(&self.bundle));
         }
 
         queue.submit(iter::once(encoder.finish()));
         frame.present();
     }
+
+    fn rebuild(
+        &mut self,
+        app_surface: &mut AppSurface,
+        assets: &dyn AssetSource,
+    ) -> Result<(), AssetError> {
+        let sample_count = self.sample_count;
+        *self = Self::new(app_surface, assets)?;
+        let sample_count =
+            Self::supported_sample_count(app_surface, self.config.format, sample_count);
+        if sample_count != self.sample_count {
+            self.sample_count = sample_count;
+            self.rebuild_bundle = true;
+        }
+        Ok(())
+    }
+
+    fn parameters(&self) -> &'static [ParameterInfo] {
+        PARAMETERS
+    }
+
+    fn parameter(&self, name: &str) -> Option<ParameterValue> {
+        match name {
+            "sample_count" => Some(ParameterValue::Enum(self.sample_count.trailing_zeros())),
+            _ => None,
+        }
+    }
+
+    fn set_parameter(
+        &mut self,
+        app_surface: &AppSurface,
+        name: &str,
+        value: ParameterValue,
+    ) -> Result<(), ParameterError> {
+        let ("sample_count", ParameterValue::Enum(index)) = (name, value) else {
+            return Err(ParameterError::Unknown(name.to_string()));
+        };
+        let sample_count =
+            Self::supported_sample_count(app_surface, self.config.format, 1 << index);
+        if sample_count != self.sample_count {
+            self.sample_count = sample_count;
+            self.rebuild_bundle = true;
+        }
+        Ok(())
+    }
 }
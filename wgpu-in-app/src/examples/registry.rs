@@ -0,0 +1,159 @@
+use super::*;
+use app_surface::{AppSurface, AssetError, AssetSource};
+use core::fmt;
+
+type CreateExample = fn(&mut AppSurface, &dyn AssetSource) -> Result<Box<dyn Example>, AssetError>;
+
+/// 注册表中的一个示例
+pub struct ExampleDesc {
+    /// 宿主 App 用来切换示例的标识，不随版本改变
+    pub id: &'static str,
+    /// 显示在调试 HUD 与宿主 App 界面上的名称
+    pub name: &'static str,
+    pub description: &'static str,
+    /// 适配器缺少其中任一特性时，示例不会出现在 `WgpuCanvas::examples` 中
+    pub required_features: wgpu::Features,
+    pub required_downlevel_flags: wgpu::DownlevelFlags,
+    create: CreateExample,
+}
+
+impl ExampleDesc {
+    /// 适配器缺少的特性与降级能力，都为空时才能运行此示例
+    pub fn missing_capabilities(
+        &self,
+        app_surface: &AppSurface,
+    ) -> (wgpu::Features, wgpu::DownlevelFlags) {
+        let downlevel = app_surface.adapter.get_downlevel_capabilities();
+        (
+            self.required_features - app_surface.granted_features,
+            self.required_downlevel_flags - downlevel.flags,
+        )
+    }
+
+    pub fn is_supported(&self, app_surface: &AppSurface) -> bool {
+        let (features, downlevel_flags) = self.missing_capabilities(app_surface);
+        features.is_empty() && downlevel_flags.is_empty()
+    }
+
+    pub fn create(
+        &self,
+        app_surface: &mut AppSurface,
+        assets: &dyn AssetSource,
+    ) -> Result<Box<dyn Example>, ExampleError> {
+        let (missing_features, missing_downlevel_flags) = self.missing_capabilities(app_surface);
+        if !missing_features.is_empty() || !missing_downlevel_flags.is_empty() {
+            return Err(ExampleError::Unsupported {
+                id: self.id,
+                missing_features,
+                missing_downlevel_flags,
+            });
+        }
+        (self.create)(app_surface, assets).map_err(ExampleError::Asset)
+    }
+}
+
+/// 所有示例，包括当前适配器不支持的示例
+pub static EXAMPLES: &[ExampleDesc] = &[
+    ExampleDesc {
+        id: "boids",
+        name: "Boids",
+        description: "Flocking simulation running in a compute shader",
+        required_features: wgpu::Features::empty(),
+        required_downlevel_flags: wgpu::DownlevelFlags::COMPUTE_SHADERS,
+        create: |app_surface, assets| Ok(Box::new(Boids::new(app_surface, assets)?)),
+    },
+    ExampleDesc {
+        id: "msaa_line",
+        name: "MSAALine",
+        description: "Anti-aliased line list rendered with MSAA",
+        required_features: wgpu::Features::empty(),
+        required_downlevel_flags: wgpu::DownlevelFlags::empty(),
+        create: |app_surface, assets| Ok(Box::new(MSAALine::new(app_surface, assets)?)),
+    },
+    ExampleDesc {
+        id: "cube",
+        name: "Cube",
+        description: "Textured cube with an optional wireframe overlay",
+        required_features: wgpu::Features::empty(),
+        required_downlevel_flags: wgpu::DownlevelFlags::empty(),
+        create: |app_surface, assets| Ok(Box::new(Cube::new(app_surface, assets)?)),
+    },
+    ExampleDesc {
+        id: "water",
+        name: "Water",
+        description: "Procedural terrain with reflective water",
+        required_features: wgpu::Features::empty(),
+        required_downlevel_flags: wgpu::DownlevelFlags::READ_ONLY_DEPTH_STENCIL,
+        create: |app_surface, assets| Ok(Box::new(Water::new(app_surface, assets)?)),
+    },
+    ExampleDesc {
+        id: "shadow",
+        name: "Shadow",
+        description: "Rotating cubes lit by shadow-casting lights",
+        required_features: wgpu::Features::empty(),
+        required_downlevel_flags: wgpu::DownlevelFlags::COMPARISON_SAMPLERS,
+        create: |app_surface, assets| Ok(Box::new(Shadow::new(app_surface, assets)?)),
+    },
+    ExampleDesc {
+        id: "hdr_image_view",
+        name: "HDRImageView",
+        description: "ASTC HDR image shown on an extended range surface",
+        required_features: wgpu::Features::TEXTURE_COMPRESSION_ASTC_HDR,
+        required_downlevel_flags: wgpu::DownlevelFlags::empty(),
+        create: |app_surface, assets| Ok(Box::new(HDRImageView::new(app_surface, assets)?)),
+    },
+];
+
+pub fn find_example(id: &str) -> Option<&'static ExampleDesc> {
+    EXAMPLES.iter().find(|desc| desc.id == id)
+}
+
+/// 当前适配器能运行的示例，按注册表中的顺序排列；
+/// 宿主 App 与 `WgpuCanvas::change_example` 使用的序号即此列表中的位置
+pub fn supported_examples(app_surface: &AppSurface) -> Vec<&'static ExampleDesc> {
+    EXAMPLES
+        .iter()
+        .filter(|desc| desc.is_supported(app_surface))
+        .collect()
+}
+
+/// 创建示例时可能出现的错误
+#[derive(Debug)]
+pub enum ExampleError {
+    /// 注册表中没有此示例
+    Unknown(String),
+    /// 当前适配器无法运行此示例
+    Unsupported {
+        id: &'static str,
+        missing_features: wgpu::Features,
+        missing_downlevel_flags: wgpu::DownlevelFlags,
+    },
+    Asset(AssetError),
+}
+
+impl fmt::Display for ExampleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExampleError::Unknown(id) => write!(f, "Unknown example: {id}"),
+            ExampleError::Unsupported {
+                id,
+                missing_features,
+                missing_downlevel_flags,
+            } => write!(
+                f,
+                "Example {id} isn't supported by the adapter, missing features: \
+                 {missing_features:?}, missing downlevel flags: {missing_downlevel_flags:?}"
+            ),
+            ExampleError::Asset(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for ExampleError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ExampleError::Asset(err) => Some(err),
+            ExampleError::Unknown(_) | ExampleError::Unsupported { .. } => None,
+        }
+    }
+}
@@ -1,14 +1,33 @@
 //! copy from wgpu's example
 
 use super::Example;
-use app_surface::{AppSurface, SurfaceFrame};
+use app_surface::{
+    AppSurface, AssetError, AssetSource, Camera, FrameAcquireResult, InputEvent, ParameterError,
+    ParameterInfo, ParameterValue, SurfaceFrame,
+};
 
 use core::{f32::consts, iter, mem, ops::Range};
-use std::{borrow::Cow, rc::Rc};
+use std::rc::Rc;
 
 use bytemuck::{Pod, Zeroable};
 use wgpu::util::{DeviceExt, align_to};
 
+// 光源参数的名称为 `light{序号}_{x|y|z|color}`，序号从 1 开始
+static PARAMETERS: &[ParameterInfo] = &[
+    ParameterInfo::int("light_count", "Lights", 2, 1, 2),
+    ParameterInfo::float("rotation_speed", "Rotation Speed", 1.0, 0.0, 4.0, 0.1),
+    ParameterInfo::float("light1_x", "Light 1 X", 7.0, -10.0, 10.0, 0.5),
+    ParameterInfo::float("light1_y", "Light 1 Y", -5.0, -10.0, 10.0, 0.5),
+    ParameterInfo::float("light1_z", "Light 1 Z", 10.0, 2.0, 18.0, 0.5),
+    ParameterInfo::color("light1_color", "Light 1 Color", [0.5, 1.0, 0.5, 1.0]),
+    ParameterInfo::float("light2_x", "Light 2 X", -5.0, -10.0, 10.0, 0.5),
+    ParameterInfo::float("light2_y", "Light 2 Y", 7.0, -10.0, 10.0, 0.5),
+    ParameterInfo::float("light2_z", "Light 2 Z", 10.0, 2.0, 18.0, 0.5),
+    ParameterInfo::color("light2_color", "Light 2 Color", [1.0, 0.5, 0.5, 1.0]),
+    Camera::MODE_PARAMETER,
+    Camera::PROJECTION_PARAMETER,
+];
+
 #[repr(C)]
 #[derive(Clone, Copy, Pod, Zeroable)]
 struct Vertex {
@@ -111,7 +130,13 @@ struct LightRaw {
 
 impl Light {
     fn to_raw(&self) -> LightRaw {
-        let view = glam::Mat4::look_at_rh(self.pos, glam::Vec3::ZERO, glam::Vec3::Z);
+        // 光源可被宿主 App 移到 Z 轴正上方，此时不能再以 Z 轴为上方向
+        let up = if self.pos.x == 0.0 && self.pos.y == 0.0 {
+            glam::Vec3::Y
+        } else {
+            glam::Vec3::Z
+        };
+        let view = glam::Mat4::look_at_rh(self.pos, glam::Vec3::ZERO, up);
         let projection = glam::Mat4::perspective_rh(
             self.fov * consts::PI / 180.,
             1.0,
@@ -156,12 +181,17 @@ pub struct Shadow {
     entities: Vec<Entity>,
     lights: Vec<Light>,
     lights_are_dirty: bool,
+    /// 参与渲染的光源数，不超过 `lights.len()`
+    light_count: usize,
+    /// 立方体旋转速度的倍数
+    rotation_speed: f32,
     shadow_pass: Pass,
     forward_pass: Pass,
     forward_depth: wgpu::TextureView,
     entity_bind_group: wgpu::BindGroup,
     light_storage_buf: wgpu::Buffer,
     entity_uniform_buf: wgpu::Buffer,
+    camera: Camera,
 }
 
 impl Shadow {
@@ -174,14 +204,41 @@ impl Shadow {
     };
     const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
 
-    fn generate_matrix(aspect_ratio: f32) -> glam::Mat4 {
-        let projection = glam::Mat4::perspective_rh(consts::FRAC_PI_4, aspect_ratio, 1.0, 20.0);
-        let view = glam::Mat4::look_at_rh(
+    fn create_camera(app_surface: &AppSurface) -> Camera {
+        let mut camera = Camera::look_at(
             glam::Vec3::new(3.0f32, -10.0, 6.0),
             glam::Vec3::new(0f32, 0.0, 0.0),
             glam::Vec3::Z,
+        )
+        .with_depth_range(1.0, 40.0)
+        .with_distance_range(4.0, 24.0);
+        camera.set_viewport(app_surface.view_size().logical_size());
+        camera
+    }
+
+    fn write_camera(&self, queue: &wgpu::Queue) {
+        let mx_total = self.camera.view_projection();
+        let mx_ref: &[f32; 16] = mx_total.as_ref();
+        queue.write_buffer(
+            &self.forward_pass.uniform_buf,
+            0,
+            bytemuck::cast_slice(mx_ref),
+        );
+    }
+
+    /// 把光源参数名拆分为光源的下标与属性名
+    fn split_light_parameter(name: &str) -> Option<(usize, &str)> {
+        let (index, field) = name.strip_prefix("light")?.split_once('_')?;
+        let index = index.parse::<usize>().ok()?.checked_sub(1)?;
+        Some((index, field))
+    }
+
+    fn write_light_count(&self, queue: &wgpu::Queue) {
+        queue.write_buffer(
+            &self.forward_pass.uniform_buf,
+            mem::offset_of!(GlobalUniforms, num_lights) as wgpu::BufferAddress,
+            bytemuck::bytes_of(&[self.light_count as u32, 0, 0, 0]),
         );
-        projection * view
     }
 
     fn create_depth_texture(
@@ -206,9 +263,10 @@ impl Shadow {
         depth_texture.create_view(&wgpu::TextureViewDescriptor::default())
     }
 
-    pub fn new(app_surface: &AppSurface) -> Self {
+    pub fn new(app_surface: &AppSurface, assets: &dyn AssetSource) -> Result<Self, AssetError> {
         let config = &app_surface.config;
         let device = &app_surface.device;
+        let camera = Self::create_camera(app_surface);
 
         let supports_storage_resources = app_surface
             .adapter
@@ -449,9 +507,7 @@ impl Shadow {
 
         let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: None,
-            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(include_str!(
-                "../../wgsl_shader/shadow.wgsl"
-            ))),
+            source: wgpu::ShaderSource::Wgsl(assets.load_string("wgsl_shader/shadow.wgsl")?),
         });
 
         let shadow_pass = {
@@ -509,8 +565,8 @@ impl Shadow {
                     topology: wgpu::PrimitiveTopology::TriangleList,
                     front_face: wgpu::FrontFace::Ccw,
                     cull_mode: Some(wgpu::Face::Back),
-                    unclipped_depth: device
-                        .features()
+                    unclipped_depth: app_surface
+                        .granted_features
                         .contains(wgpu::Features::DEPTH_CLIP_CONTROL),
                     ..Default::default()
                 },
@@ -527,7 +583,7 @@ impl Shadow {
                 }),
                 multisample: wgpu::MultisampleState::default(),
                 multiview_mask: None,
-                cache: None,
+                cache: app_surface.pipeline_cache.as_ref(),
             });
 
             Pass {
@@ -589,7 +645,7 @@ impl Shadow {
                 immediate_size: 0,
             });
 
-            let mx_total = Self::generate_matrix(config.width as f32 / config.height as f32);
+            let mx_total = camera.view_projection();
             let forward_uniforms = GlobalUniforms {
                 proj: mx_total.to_cols_array_2d(),
                 num_lights: [lights.len() as u32, 0, 0, 0],
@@ -654,7 +710,7 @@ impl Shadow {
                 }),
                 multisample: wgpu::MultisampleState::default(),
                 multiview_mask: None,
-                cache: None,
+                cache: app_surface.pipeline_cache.as_ref(),
             });
 
             Pass {
@@ -666,17 +722,21 @@ impl Shadow {
 
         let forward_depth = Self::create_depth_texture(config, device);
 
-        Self {
+        let light_count = lights.len();
+        Ok(Self {
             entities,
             lights,
             lights_are_dirty: true,
+            light_count,
+            rotation_speed: 1.0,
             shadow_pass,
             forward_pass,
             forward_depth,
             light_storage_buf,
             entity_uniform_buf,
             entity_bind_group,
-        }
+            camera,
+        })
     }
 }
 
@@ -684,15 +744,10 @@ impl Example for Shadow {
     fn resize(&mut self, app_surface: &AppSurface) {
         let config = &app_surface.config;
         let device = &app_surface.device;
-        let queue = &app_surface.queue;
         // update view-projection matrix
-        let mx_total = Self::generate_matrix(config.width as f32 / config.height as f32);
-        let mx_ref: &[f32; 16] = mx_total.as_ref();
-        queue.write_buffer(
-            &self.forward_pass.uniform_buf,
-            0,
-            bytemuck::cast_slice(mx_ref),
-        );
+        self.camera
+            .set_viewport(app_surface.view_size().logical_size());
+        self.write_camera(&app_surface.queue);
 
         self.forward_depth = Self::create_depth_texture(config, device);
     }
@@ -701,10 +756,15 @@ impl Example for Shadow {
         let device = &app_surface.device;
         let queue = &app_surface.queue;
         // update uniforms
+        if self.camera.update(app_surface.frame_clock.delta_seconds()) {
+            self.write_camera(queue);
+        }
+        let rotation_speed = self.rotation_speed;
         for entity in self.entities.iter_mut() {
-            if entity.rotation_speed != 0.0 {
-                let rotation =
-                    glam::Mat4::from_rotation_x(entity.rotation_speed * consts::PI / 180.);
+            if entity.rotation_speed != 0.0 && rotation_speed != 0.0 {
+                let rotation = glam::Mat4::from_rotation_x(
+                    entity.rotation_speed * rotation_speed * consts::PI / 180.,
+                );
                 entity.mx_world *= rotation;
             }
             let data = EntityUniforms {
@@ -737,8 +797,10 @@ impl Example for Shadow {
         let mut encoder =
             device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
 
+        let shadow_scope = app_surface.profiler.scope("shadow");
         encoder.push_debug_group("shadow passes");
-        for (i, light) in self.lights.iter().enumerate() {
+        for (i, light) in self.lights.iter().take(self.light_count).enumerate() {
+            let mut light_scope = app_surface.profiler.scope(format!("light {i}"));
             encoder.push_debug_group(&format!(
                 "shadow pass {} (light at position {:?})",
                 i, light.pos
@@ -767,6 +829,7 @@ impl Example for Shadow {
                         }),
                         stencil_ops: None,
                     }),
+                    timestamp_writes: light_scope.render_pass_timestamp_writes(),
                     ..Default::default()
                 });
                 pass.set_pipeline(&self.shadow_pass.pipeline);
@@ -783,14 +846,17 @@ impl Example for Shadow {
             encoder.pop_debug_group();
         }
         encoder.pop_debug_group();
+        drop(shadow_scope);
 
-        let frame_view = app_surface.get_current_frame_view(None);
-        if frame_view.is_none() { return; }
-        let (frame, view) = frame_view.unwrap();
+        let FrameAcquireResult::Frame(frame, view) = app_surface.get_current_frame_view(None)
+        else {
+            return;
+        };
 
         // forward pass
         encoder.push_debug_group("forward rendering pass");
         {
+            let mut scope = app_surface.profiler.scope("forward");
             let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: None,
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
@@ -815,6 +881,7 @@ impl Example for Shadow {
                     }),
                     stencil_ops: None,
                 }),
+                timestamp_writes: scope.render_pass_timestamp_writes(),
                 ..Default::default()
             });
             pass.set_pipeline(&self.forward_pass.pipeline);
@@ -829,7 +896,100 @@ impl Example for Shadow {
         }
         encoder.pop_debug_group();
 
+        app_surface.profiler.resolve_queries(&mut encoder);
         queue.submit(iter::once(encoder.finish()));
         frame.present()
     }
+
+    fn rebuild(
+        &mut self,
+        app_surface: &mut AppSurface,
+        assets: &dyn AssetSource,
+    ) -> Result<(), AssetError> {
+        let lights: Vec<_> = self.lights.iter().map(|l| (l.pos, l.color)).collect();
+        let (light_count, rotation_speed) = (self.light_count, self.rotation_speed);
+        let camera = self.camera.clone();
+        *self = Self::new(app_surface, assets)?;
+        for (light, (pos, color)) in self.lights.iter_mut().zip(lights) {
+            light.pos = pos;
+            light.color = color;
+        }
+        self.light_count = light_count;
+        self.rotation_speed = rotation_speed;
+        self.write_light_count(&app_surface.queue);
+        self.camera = camera;
+        self.write_camera(&app_surface.queue);
+        Ok(())
+    }
+
+    fn input(&mut self, _app_surface: &AppSurface, event: &InputEvent) {
+        self.camera.handle_input(event);
+    }
+
+    fn parameters(&self) -> &'static [ParameterInfo] {
+        PARAMETERS
+    }
+
+    fn parameter(&self, name: &str) -> Option<ParameterValue> {
+        match name {
+            "light_count" => Some(ParameterValue::Int(self.light_count as i32)),
+            "rotation_speed" => Some(ParameterValue::Float(self.rotation_speed)),
+            "camera_mode" | "camera_projection" => self.camera.parameter(name),
+            _ => {
+                let (index, field) = Self::split_light_parameter(name)?;
+                let light = self.lights.get(index)?;
+                match field {
+                    "x" => Some(ParameterValue::Float(light.pos.x)),
+                    "y" => Some(ParameterValue::Float(light.pos.y)),
+                    "z" => Some(ParameterValue::Float(light.pos.z)),
+                    "color" => Some(ParameterValue::Color([
+                        light.color.r as f32,
+                        light.color.g as f32,
+                        light.color.b as f32,
+                        light.color.a as f32,
+                    ])),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    fn set_parameter(
+        &mut self,
+        app_surface: &AppSurface,
+        name: &str,
+        value: ParameterValue,
+    ) -> Result<(), ParameterError> {
+        let unknown = || ParameterError::Unknown(name.to_string());
+        match (name, value) {
+            ("light_count", ParameterValue::Int(count)) => {
+                self.light_count = (count.max(1) as usize).min(self.lights.len());
+                self.write_light_count(&app_surface.queue);
+            }
+            ("rotation_speed", ParameterValue::Float(speed)) => self.rotation_speed = speed,
+            ("camera_mode" | "camera_projection", _) => {
+                return self.camera.set_parameter(name, value);
+            }
+            _ => {
+                let (index, field) = Self::split_light_parameter(name).ok_or_else(unknown)?;
+                let light = self.lights.get_mut(index).ok_or_else(unknown)?;
+                match (field, value) {
+                    ("x", ParameterValue::Float(x)) => light.pos.x = x,
+                    ("y", ParameterValue::Float(y)) => light.pos.y = y,
+                    ("z", ParameterValue::Float(z)) => light.pos.z = z,
+                    ("color", ParameterValue::Color([r, g, b, a])) => {
+                        light.color = wgpu::Color {
+                            r: r as f64,
+                            g: g as f64,
+                            b: b as f64,
+                            a: a as f64,
+                        };
+                    }
+                    _ => return Err(unknown()),
+                }
+                self.lights_are_dirty = true;
+            }
+        }
+        Ok(())
+    }
 }
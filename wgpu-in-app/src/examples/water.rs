@@ -1,13 +1,15 @@
 //! copy from wgpu's example
 
 use super::{Example, point_gen};
-use app_surface::{AppSurface, SurfaceFrame};
+use app_surface::{
+    AppSurface, AssetError, AssetSource, Camera, FrameAcquireResult, InputEvent, ParameterError,
+    ParameterInfo, ParameterValue, SurfaceFrame,
+};
 
 use bytemuck::{Pod, Zeroable};
 use core::{f32::consts, iter, mem};
 use glam::Vec3;
 use rand::SeedableRng;
-use std::borrow::Cow;
 use wgpu::util::DeviceExt;
 
 ///
@@ -21,11 +23,13 @@ use wgpu::util::DeviceExt;
 const SIZE: f32 = 29.0;
 
 ///
-/// Location of the camera.
+/// Initial location of the camera.
 /// Location of light is in terrain/water shaders.
 ///
 const CAMERA: Vec3 = glam::Vec3::new(-200.0, 70.0, 200.0);
 
+static PARAMETERS: &[ParameterInfo] = &[Camera::MODE_PARAMETER, Camera::PROJECTION_PARAMETER];
+
 struct Matrices {
     view: glam::Mat4,
     flipped_view: glam::Mat4,
@@ -89,10 +93,12 @@ pub struct Water {
     /// minimizing the window.
     ///
     active: Option<usize>,
+
+    camera: Camera,
 }
 
 impl Water {
-    pub fn new(app_surface: &AppSurface) -> Self {
+    pub fn new(app_surface: &AppSurface, assets: &dyn AssetSource) -> Result<Self, AssetError> {
         let config = &app_surface.config;
         let device = &app_surface.device;
         let queue = &app_surface.queue;
@@ -287,10 +293,12 @@ impl Water {
         // Create bind group.
         // This puts values behind what was laid out in the bind group layout.
 
+        let camera = Self::create_camera(app_surface);
         let (reflect_view, depth_buffer, water_bind_group) = Self::initialize_resources(
             config,
             device,
             queue,
+            &camera,
             &water_uniform_buf,
             &terrain_normal_uniform_buf,
             &terrain_flipped_uniform_buf,
@@ -317,15 +325,11 @@ impl Water {
         // Upload/compile them to GPU code.
         let terrain_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("terrain"),
-            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(include_str!(
-                "../../wgsl_shader/terrain.wgsl"
-            ))),
+            source: wgpu::ShaderSource::Wgsl(assets.load_string("wgsl_shader/terrain.wgsl")?),
         });
         let water_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("water"),
-            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(include_str!(
-                "../../wgsl_shader/water.wgsl"
-            ))),
+            source: wgpu::ShaderSource::Wgsl(assets.load_string("wgsl_shader/water.wgsl")?),
         });
 
         // Create the render pipelines. These describe how the data will flow through the GPU, and what
@@ -400,7 +404,7 @@ impl Water {
             // No multisampling is used.
             multisample: wgpu::MultisampleState::default(),
             multiview_mask: None,
-            cache: None,
+            cache: app_surface.pipeline_cache.as_ref(),
         });
 
         // Same idea as the water pipeline.
@@ -437,10 +441,10 @@ impl Water {
             }),
             multisample: wgpu::MultisampleState::default(),
             multiview_mask: None,
-            cache: None,
+            cache: app_surface.pipeline_cache.as_ref(),
         });
 
-        Self {
+        Ok(Self {
             water_vertex_buf,
             water_vertex_count: water_vertices.len(),
             water_bind_group_layout,
@@ -463,26 +467,37 @@ impl Water {
             current_frame: 0,
 
             active: Some(0),
-        }
+
+            camera,
+        })
+    }
+
+    fn create_camera(app_surface: &AppSurface) -> Camera {
+        // 相机只能在水面以上，倒影才是正确的
+        let mut camera = Camera::look_at(CAMERA, glam::Vec3::ZERO, glam::Vec3::Y)
+            .with_depth_range(10.0, 1000.0)
+            .with_distance_range(60.0, 600.0)
+            .with_pitch_range(0.05, consts::FRAC_PI_2);
+        camera.set_viewport(app_surface.view_size().logical_size());
+        camera
     }
+
     ///
     /// Creates the view matrices, and the corrected projection matrix.
     ///
-    fn generate_matrices(aspect_ratio: f32) -> Matrices {
-        let projection = glam::Mat4::perspective_rh(consts::FRAC_PI_4, aspect_ratio, 10.0, 400.0);
-        let reg_view = glam::Mat4::look_at_rh(
-            CAMERA,
-            glam::Vec3::new(0f32, 0.0, 0.0),
-            glam::Vec3::Y, //Note that y is up. Differs from other examples.
-        );
+    fn generate_matrices(camera: &Camera) -> Matrices {
+        let projection = camera.projection_matrix();
+        //Note that y is up. Differs from other examples.
+        let reg_view = camera.view_matrix();
 
         let scale = glam::Mat4::from_scale(glam::Vec3::new(8.0, 1.5, 8.0));
 
         let reg_view = reg_view * scale;
 
+        let mirror = glam::Vec3::new(1.0, -1.0, 1.0);
         let flipped_view = glam::Mat4::look_at_rh(
-            glam::Vec3::new(CAMERA.x, -CAMERA.y, CAMERA.z),
-            glam::Vec3::ZERO,
+            camera.eye() * mirror,
+            camera.target() * mirror,
             glam::Vec3::Y,
         );
 
@@ -495,12 +510,12 @@ impl Water {
         }
     }
 
-    fn generate_uniforms(width: u32, height: u32) -> Uniforms {
+    fn generate_uniforms(camera: &Camera, width: u32, height: u32) -> Uniforms {
         let Matrices {
             view,
             flipped_view,
             projection,
-        } = Self::generate_matrices(width as f32 / height as f32);
+        } = Self::generate_matrices(camera);
 
         Uniforms {
             terrain_normal: TerrainUniforms {
@@ -520,13 +535,41 @@ impl Water {
         }
     }
 
+    ///
+    /// Updates the camera matrices, leaving the water animation untouched.
+    ///
+    fn write_camera(&self, config: &wgpu::SurfaceConfiguration, queue: &wgpu::Queue) {
+        let Uniforms {
+            terrain_normal,
+            terrain_flipped,
+            water,
+        } = Self::generate_uniforms(&self.camera, config.width, config.height);
+        queue.write_buffer(
+            &self.terrain_normal_uniform_buf,
+            0,
+            bytemuck::cast_slice(&[terrain_normal]),
+        );
+        queue.write_buffer(
+            &self.terrain_flipped_uniform_buf,
+            0,
+            bytemuck::cast_slice(&[terrain_flipped]),
+        );
+        queue.write_buffer(
+            &self.water_uniform_buf,
+            0,
+            bytemuck::cast_slice(&[water.view, water.projection]),
+        );
+    }
+
     ///
     /// Initializes Uniforms and textures.
     ///
+    #[allow(clippy::too_many_arguments)]
     fn initialize_resources(
         config: &wgpu::SurfaceConfiguration,
         device: &wgpu::Device,
         queue: &wgpu::Queue,
+        camera: &Camera,
         water_uniforms: &wgpu::Buffer,
         terrain_normal_uniforms: &wgpu::Buffer,
         terrain_flipped_uniforms: &wgpu::Buffer,
@@ -538,7 +581,7 @@ impl Water {
             terrain_normal,
             terrain_flipped,
             water,
-        } = Self::generate_uniforms(config.width, config.height);
+        } = Self::generate_uniforms(camera, config.width, config.height);
 
         // Put the uniforms into buffers on the GPU
         queue.write_buffer(
@@ -651,6 +694,8 @@ impl Example for Water {
             return;
         }
         self.active = Some(self.current_frame);
+        self.camera
+            .set_viewport(app_surface.view_size().logical_size());
 
         // Regenerate all of the buffers and textures.
 
@@ -658,6 +703,7 @@ impl Example for Water {
             config,
             device,
             queue,
+            &self.camera,
             &self.water_uniform_buf,
             &self.terrain_normal_uniform_buf,
             &self.terrain_flipped_uniform_buf,
@@ -682,8 +728,13 @@ impl Example for Water {
             a: 1.0,
         };
 
+        if self.camera.update(app_surface.frame_clock.delta_seconds()) {
+            self.write_camera(&app_surface.config, queue);
+        }
+
         // Write the sin/cos values to the uniform buffer for the water.
-        let (water_sin, water_cos) = ((self.current_frame as f32) / 600.0).sin_cos();
+        // 按时间而不是帧数推进动画，速度与帧率无关（60 帧时与原先一致）
+        let (water_sin, water_cos) = (app_surface.frame_clock.elapsed_seconds() / 10.0).sin_cos();
         queue.write_buffer(
             &self.water_uniform_buf,
             mem::size_of::<[f32; 16]>() as wgpu::BufferAddress * 2,
@@ -705,11 +756,13 @@ impl Example for Water {
             label: Some("Main Command Encoder"),
         });
 
-        let frame_view = app_surface.get_current_frame_view(None);
-        if frame_view.is_none() { return; }
-        let (frame, view) = frame_view.unwrap();
+        let FrameAcquireResult::Frame(frame, view) = app_surface.get_current_frame_view(None)
+        else {
+            return;
+        };
         // First pass: render the reflection.
         {
+            let mut scope = app_surface.profiler.scope("reflection");
             let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: None,
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
@@ -731,6 +784,7 @@ impl Example for Water {
                     }),
                     stencil_ops: None,
                 }),
+                timestamp_writes: scope.render_pass_timestamp_writes(),
                 ..Default::default()
             });
             rpass.set_pipeline(&self.terrain_pipeline);
@@ -741,6 +795,7 @@ impl Example for Water {
         // Terrain right side up. This time we need to use the
         // depth values, so we must use StoreOp::Store.
         {
+            let mut scope = app_surface.profiler.scope("terrain");
             let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: None,
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
@@ -760,6 +815,7 @@ impl Example for Water {
                     }),
                     stencil_ops: None,
                 }),
+                timestamp_writes: scope.render_pass_timestamp_writes(),
                 ..Default::default()
             });
             rpass.set_pipeline(&self.terrain_pipeline);
@@ -770,6 +826,7 @@ impl Example for Water {
         // Render the water. This reads from the depth buffer, but does not write
         // to it, so it cannot be in the same render pass.
         {
+            let mut scope = app_surface.profiler.scope("water");
             let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: None,
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
@@ -786,6 +843,7 @@ impl Example for Water {
                     depth_ops: None,
                     stencil_ops: None,
                 }),
+                timestamp_writes: scope.render_pass_timestamp_writes(),
                 ..Default::default()
             });
 
@@ -795,7 +853,41 @@ impl Example for Water {
             rpass.draw(0..self.water_vertex_count as u32, 0..1);
         }
 
+        app_surface.profiler.resolve_queries(&mut encoder);
         queue.submit(iter::once(encoder.finish()));
         frame.present()
     }
+
+    fn rebuild(
+        &mut self,
+        app_surface: &mut AppSurface,
+        assets: &dyn AssetSource,
+    ) -> Result<(), AssetError> {
+        let camera = self.camera.clone();
+        *self = Self::new(app_surface, assets)?;
+        self.camera = camera;
+        self.write_camera(&app_surface.config, &app_surface.queue);
+        Ok(())
+    }
+
+    fn input(&mut self, _app_surface: &AppSurface, event: &InputEvent) {
+        self.camera.handle_input(event);
+    }
+
+    fn parameters(&self) -> &'static [ParameterInfo] {
+        PARAMETERS
+    }
+
+    fn parameter(&self, name: &str) -> Option<ParameterValue> {
+        self.camera.parameter(name)
+    }
+
+    fn set_parameter(
+        &mut self,
+        _app_surface: &AppSurface,
+        name: &str,
+        value: ParameterValue,
+    ) -> Result<(), ParameterError> {
+        self.camera.set_parameter(name, value)
+    }
 }
@@ -1,18 +1,49 @@
 use crate::wgpu_canvas::WgpuCanvas;
-use app_surface::AppSurface;
-use jni::JNIEnv;
-use jni::objects::JClass;
-use jni::sys::{jint, jlong, jobject};
+use app_surface::{
+    AppSurface, EdgeInsets, NativeWindow, ParameterValue, ProfileEntry, RawHostEvent,
+    RawParameterValue, Touch,
+};
+use jni::objects::{GlobalRef, JByteBuffer, JClass, JFloatArray, JObject, JString, JValue};
+use jni::sys::{jboolean, jfloat, jfloatArray, jint, jlong, jobject, jstring};
+use jni::{JNIEnv, JavaVM};
 use jni_fn::jni_fn;
-use log::info;
+use log::{error, info};
 
 // Starting with the 2024 Edition, it is now required to mark these attributes as unsafe.
 #[unsafe(no_mangle)]
 #[jni_fn("name.jinleili.wgpu.RustBridge")]
-pub fn createWgpuCanvas(env: *mut JNIEnv, _: JClass, surface: jobject, idx: jint) -> jlong {
+pub fn createWgpuCanvas(
+    env: *mut JNIEnv,
+    _: JClass,
+    surface: jobject,
+    idx: jint,
+    library_dir: jstring,
+) -> jlong {
     crate::init_logger();
 
-    let canvas = WgpuCanvas::new(AppSurface::new(env as *mut _, surface), idx);
+    // 需要在 AppSurface 取用 env 之前读取字符串
+    let library_directory = unsafe { JNIEnv::from_raw(env as *mut jni::sys::JNIEnv) }
+        .ok()
+        .and_then(|mut env| {
+            let library_dir = unsafe { JString::from_raw(library_dir) };
+            env.get_string(&library_dir).ok().map(String::from)
+        })
+        .unwrap_or_default();
+
+    let mut app_surface = match AppSurface::try_new_with_options(
+        env as *mut _,
+        surface,
+        &WgpuCanvas::device_options(),
+    ) {
+        Ok(app_surface) => app_surface,
+        Err(err) => {
+            // 返回 0 让 Android 端知道创建失败，以便回退或提示用户
+            error!("Failed to create AppSurface: {err}");
+            return 0;
+        }
+    };
+    app_surface.library_directory = library_directory;
+    let canvas = WgpuCanvas::new(app_surface, idx);
     info!("WgpuCanvas created!");
 
     Box::into_raw(Box::new(canvas)) as jlong
@@ -25,6 +56,48 @@ pub fn enterFrame(_env: *mut JNIEnv, _: JClass, obj: jlong) {
     obj.enter_frame();
 }
 
+/// 在 `surfaceDestroyed` 中调用，写回管线缓存并释放 surface，device 与示例的状态保持不变
+#[unsafe(no_mangle)]
+#[jni_fn("name.jinleili.wgpu.RustBridge")]
+pub fn suspendSurface(_env: *mut JNIEnv, _: JClass, obj: jlong) {
+    let obj = unsafe { &mut *(obj as *mut WgpuCanvas) };
+    obj.suspend();
+}
+
+/// 在 `surfaceCreated` 中用新的 Surface 重建 surface，失败时返回 false
+#[unsafe(no_mangle)]
+#[jni_fn("name.jinleili.wgpu.RustBridge")]
+pub fn resumeSurface(env: *mut JNIEnv, _: JClass, obj: jlong, surface: jobject) -> jboolean {
+    let obj = unsafe { &mut *(obj as *mut WgpuCanvas) };
+    let native_window = NativeWindow::new(env as *mut _, surface);
+    obj.resume(native_window).is_ok() as jboolean
+}
+
+/// `scale_factor` 为 `DisplayMetrics.density`，SurfaceView 尺寸或屏幕密度改变后调用
+#[unsafe(no_mangle)]
+#[jni_fn("name.jinleili.wgpu.RustBridge")]
+pub fn resize(_env: *mut JNIEnv, _: JClass, obj: jlong, scale_factor: jfloat) {
+    let obj = unsafe { &mut *(obj as *mut WgpuCanvas) };
+    obj.set_scale_factor(scale_factor);
+}
+
+/// 安全区域的内边距，单位为 dp
+#[unsafe(no_mangle)]
+#[jni_fn("name.jinleili.wgpu.RustBridge")]
+pub fn setSafeArea(
+    _env: *mut JNIEnv,
+    _: JClass,
+    obj: jlong,
+    top: jfloat,
+    left: jfloat,
+    bottom: jfloat,
+    right: jfloat,
+) {
+    let obj = unsafe { &mut *(obj as *mut WgpuCanvas) };
+    obj.set_safe_area(EdgeInsets::new(top, left, bottom, right));
+}
+
+/// `idx` 为示例在 `getExamples` 列表中的位置
 #[unsafe(no_mangle)]
 #[jni_fn("name.jinleili.wgpu.RustBridge")]
 pub fn changeExample(_env: *mut JNIEnv, _: JClass, obj: jlong, idx: jint) {
@@ -33,6 +106,317 @@ pub fn changeExample(_env: *mut JNIEnv, _: JClass, obj: jlong, idx: jint) {
     obj.change_example(idx);
 }
 
+/// 示例未知、当前设备不支持或创建失败时返回 false
+#[unsafe(no_mangle)]
+#[jni_fn("name.jinleili.wgpu.RustBridge")]
+pub fn changeExampleById(env: *mut JNIEnv, _: JClass, obj: jlong, id: jstring) -> jboolean {
+    let obj = unsafe { &mut *(obj as *mut WgpuCanvas) };
+    let Ok(mut env) = (unsafe { JNIEnv::from_raw(env as *mut jni::sys::JNIEnv) }) else {
+        return 0;
+    };
+    let id = unsafe { JString::from_raw(id) };
+    let Ok(id) = env.get_string(&id).map(String::from) else {
+        return 0;
+    };
+    match obj.change_example_by_id(&id) {
+        Ok(()) => 1,
+        Err(err) => {
+            error!("{err}");
+            0
+        }
+    }
+}
+
+/// 当前设备能运行的示例列表的 JSON 描述
+#[unsafe(no_mangle)]
+#[jni_fn("name.jinleili.wgpu.RustBridge")]
+pub fn getExamples(env: *mut JNIEnv, _: JClass, obj: jlong) -> jstring {
+    let obj = unsafe { &*(obj as *mut WgpuCanvas) };
+    let Ok(env) = (unsafe { JNIEnv::from_raw(env as *mut jni::sys::JNIEnv) }) else {
+        return core::ptr::null_mut();
+    };
+    match env.new_string(obj.examples_json()) {
+        Ok(json) => json.into_raw(),
+        Err(_) => core::ptr::null_mut(),
+    }
+}
+
+/// `buffer` 为 native 字节序的 direct ByteBuffer，依次存放 `count` 个 `Touch`
+///
+/// 每个 `Touch` 占 56 字节：id(i64), timestamp(f64), phase(i32), kind(i32), x(f32), y(f32),
+/// azimuth(f32), altitude(f32), pressure(f32), major_radius(f32), interval(f32) 及 4 字节填充
+#[unsafe(no_mangle)]
+#[jni_fn("name.jinleili.wgpu.RustBridge")]
+pub fn touches(env: *mut JNIEnv, _: JClass, obj: jlong, buffer: jobject, count: jint) {
+    let obj = unsafe { &mut *(obj as *mut WgpuCanvas) };
+    let Ok(env) = (unsafe { JNIEnv::from_raw(env as *mut jni::sys::JNIEnv) }) else {
+        return;
+    };
+    let buffer = unsafe { JByteBuffer::from_raw(buffer) };
+    let (Ok(address), Ok(capacity)) = (
+        env.get_direct_buffer_address(&buffer),
+        env.get_direct_buffer_capacity(&buffer),
+    ) else {
+        error!("touches: buffer is not a direct ByteBuffer");
+        return;
+    };
+    let count = count.max(0) as usize;
+    if count * core::mem::size_of::<Touch>() > capacity {
+        error!("touches: buffer is too small for {count} touches");
+        return;
+    }
+    // Java 端不保证 direct ByteBuffer 按 8 字节对齐，需要逐个非对齐读取
+    let touches: Vec<Touch> = (0..count)
+        .map(|i| unsafe { (address as *const Touch).add(i).read_unaligned() })
+        .collect();
+    obj.touches(&touches);
+}
+
+/// `HostEventListener` 的全局引用，作为 `user_data` 注册给 `HostEventSink`
+struct JavaHostEventListener {
+    vm: JavaVM,
+    listener: GlobalRef,
+}
+
+extern "C" fn on_host_event(event: *const RawHostEvent, user_data: *mut core::ffi::c_void) {
+    let listener = unsafe { &*(user_data as *const JavaHostEventListener) };
+    let event = unsafe { &*event };
+    // 渲染线程通常已附加到 JVM，此时不会重复附加
+    let Ok(mut env) = listener.vm.attach_current_thread_permanently() else {
+        error!("on_host_event: failed to attach thread to JVM");
+        return;
+    };
+    let payload = match unsafe { event.payload_str() } {
+        Some(payload) => match env.new_string(payload) {
+            Ok(payload) => JObject::from(payload),
+            Err(_) => JObject::null(),
+        },
+        None => JObject::null(),
+    };
+    let result = env.call_method(
+        &listener.listener,
+        "onHostEvent",
+        "(IJLjava/lang/String;)V",
+        &[
+            JValue::Int(event.kind as jint),
+            JValue::Long(event.code),
+            JValue::Object(&payload),
+        ],
+    );
+    if result.is_err() && env.exception_check().unwrap_or(false) {
+        // 不能让 Kotlin 端的异常停留在渲染线程上
+        _ = env.exception_describe();
+        _ = env.exception_clear();
+    }
+    _ = env.delete_local_ref(payload);
+}
+
+extern "C" fn release_host_event_listener(user_data: *mut core::ffi::c_void) {
+    drop(unsafe { Box::from_raw(user_data as *mut JavaHostEventListener) });
+}
+
+/// 注册接收 `HostEvent` 的 `HostEventListener`，`mask` 为 `HostEventKind::mask` 的组合，
+/// 传入 null 时取消注册
+///
+/// 创建画布期间发出的事件（如 `Ready`）会在注册时补发，回调在渲染线程上调用
+#[unsafe(no_mangle)]
+#[jni_fn("name.jinleili.wgpu.RustBridge")]
+pub fn registerHostEventListener(
+    env: *mut JNIEnv,
+    _: JClass,
+    obj: jlong,
+    listener: jobject,
+    mask: jint,
+) {
+    let obj = unsafe { &mut *(obj as *mut WgpuCanvas) };
+    let listener = unsafe { JObject::from_raw(listener) };
+    if listener.is_null() {
+        obj.app_surface
+            .host_events
+            .register(None, core::ptr::null_mut(), 0, None);
+        return;
+    }
+    let Ok(env) = (unsafe { JNIEnv::from_raw(env as *mut jni::sys::JNIEnv) }) else {
+        return;
+    };
+    let (Ok(vm), Ok(listener)) = (env.get_java_vm(), env.new_global_ref(&listener)) else {
+        error!("registerHostEventListener: failed to create global reference");
+        return;
+    };
+    let user_data = Box::into_raw(Box::new(JavaHostEventListener { vm, listener }));
+    obj.app_surface.host_events.register(
+        Some(on_host_event),
+        user_data as *mut core::ffi::c_void,
+        mask as u32,
+        Some(release_host_event_listener),
+    );
+}
+
+#[unsafe(no_mangle)]
+#[jni_fn("name.jinleili.wgpu.RustBridge")]
+pub fn setHudVisible(_env: *mut JNIEnv, _: JClass, obj: jlong, visible: jboolean) {
+    let obj = unsafe { &mut *(obj as *mut WgpuCanvas) };
+    obj.set_hud_visible(visible != 0);
+}
+
+#[unsafe(no_mangle)]
+#[jni_fn("name.jinleili.wgpu.RustBridge")]
+pub fn setProfilerEnabled(_env: *mut JNIEnv, _: JClass, obj: jlong, enabled: jboolean) {
+    let obj = unsafe { &*(obj as *mut WgpuCanvas) };
+    obj.set_profiler_enabled(enabled != 0);
+}
+
+/// 把最近一帧的耗时树按深度优先的顺序写入 `buffer`，返回全部的项数，大于可写入的项数时结果被截断
+#[unsafe(no_mangle)]
+#[jni_fn("name.jinleili.wgpu.RustBridge")]
+pub fn getProfileEntries(env: *mut JNIEnv, _: JClass, obj: jlong, buffer: jobject) -> jint {
+    let obj = unsafe { &*(obj as *mut WgpuCanvas) };
+    let Some(profile) = obj.latest_profile() else {
+        return 0;
+    };
+    let Ok(env) = (unsafe { JNIEnv::from_raw(env as *mut jni::sys::JNIEnv) }) else {
+        return 0;
+    };
+    let buffer = unsafe { JByteBuffer::from_raw(buffer) };
+    let (Ok(address), Ok(capacity)) = (
+        env.get_direct_buffer_address(&buffer),
+        env.get_direct_buffer_capacity(&buffer),
+    ) else {
+        error!("getProfileEntries: buffer is not a direct ByteBuffer");
+        return 0;
+    };
+    let entries = profile.entries();
+    let count = entries
+        .len()
+        .min(capacity / core::mem::size_of::<ProfileEntry>());
+    for (i, entry) in entries.iter().take(count).enumerate() {
+        unsafe {
+            (address as *mut ProfileEntry)
+                .add(i)
+                .write_unaligned(*entry)
+        };
+    }
+    entries.len() as jint
+}
+
+/// 当前示例参数的 JSON 描述
+#[unsafe(no_mangle)]
+#[jni_fn("name.jinleili.wgpu.RustBridge")]
+pub fn getParameters(env: *mut JNIEnv, _: JClass, obj: jlong) -> jstring {
+    let obj = unsafe { &*(obj as *mut WgpuCanvas) };
+    let Ok(env) = (unsafe { JNIEnv::from_raw(env as *mut jni::sys::JNIEnv) }) else {
+        return core::ptr::null_mut();
+    };
+    match env.new_string(obj.parameters_json()) {
+        Ok(json) => json.into_raw(),
+        Err(_) => core::ptr::null_mut(),
+    }
+}
+
+/// 返回 [type, intValue, floatValues[0..4]]，没有此参数时返回 null
+///
+/// 取值的编码与 `RawParameterValue` 一致
+#[unsafe(no_mangle)]
+#[jni_fn("name.jinleili.wgpu.RustBridge")]
+pub fn getParameter(env: *mut JNIEnv, _: JClass, obj: jlong, name: jstring) -> jfloatArray {
+    let obj = unsafe { &*(obj as *mut WgpuCanvas) };
+    let Ok(mut env) = (unsafe { JNIEnv::from_raw(env as *mut jni::sys::JNIEnv) }) else {
+        return core::ptr::null_mut();
+    };
+    let name = unsafe { JString::from_raw(name) };
+    let Some(value) = env
+        .get_string(&name)
+        .ok()
+        .and_then(|name| obj.get_parameter(&String::from(name)))
+    else {
+        return core::ptr::null_mut();
+    };
+    let raw = RawParameterValue::from(value);
+    let [f0, f1, f2, f3] = raw.float_values;
+    let values = [raw.ty as f32, raw.int_value as f32, f0, f1, f2, f3];
+    let Ok(array) = env.new_float_array(values.len() as i32) else {
+        return core::ptr::null_mut();
+    };
+    if env.set_float_array_region(&array, 0, &values).is_err() {
+        return core::ptr::null_mut();
+    }
+    array.into_raw()
+}
+
+/// `ty`、`int_value` 与 `float_values` 的含义与 `RawParameterValue` 一致，`float_values` 可以为 null
+///
+/// 数值超出取值范围时会被限制在范围内，参数不存在或类型不符时返回 false
+#[unsafe(no_mangle)]
+#[jni_fn("name.jinleili.wgpu.RustBridge")]
+pub fn setParameter(
+    env: *mut JNIEnv,
+    _: JClass,
+    obj: jlong,
+    name: jstring,
+    ty: jint,
+    int_value: jint,
+    float_values: jfloatArray,
+) -> jboolean {
+    let obj = unsafe { &mut *(obj as *mut WgpuCanvas) };
+    let Ok(mut env) = (unsafe { JNIEnv::from_raw(env as *mut jni::sys::JNIEnv) }) else {
+        return 0;
+    };
+    let name = unsafe { JString::from_raw(name) };
+    let Ok(name) = env.get_string(&name).map(String::from) else {
+        return 0;
+    };
+    let mut values = [0.0; 4];
+    let float_values = unsafe { JFloatArray::from_raw(float_values) };
+    if !float_values.is_null() {
+        let len = env.get_array_length(&float_values).unwrap_or(0).clamp(0, 4);
+        _ = env.get_float_array_region(&float_values, 0, &mut values[..len as usize]);
+    }
+    let raw = RawParameterValue {
+        ty,
+        int_value,
+        float_values: values,
+    };
+    let Ok(value) = ParameterValue::try_from(raw) else {
+        error!("setParameter: unknown parameter type {ty}");
+        return 0;
+    };
+    match obj.set_parameter(&name, value) {
+        Ok(()) => 1,
+        Err(err) => {
+            error!("{err}");
+            0
+        }
+    }
+}
+
+/// 返回 [fps, 平均帧时间, p50, p95, p99, 掉帧数, 总帧数]，时间单位为毫秒
+#[unsafe(no_mangle)]
+#[jni_fn("name.jinleili.wgpu.RustBridge")]
+pub fn getFrameStats(env: *mut JNIEnv, _: JClass, obj: jlong) -> jfloatArray {
+    let obj = unsafe { &*(obj as *mut WgpuCanvas) };
+    let stats = obj.app_surface.frame_clock.stats();
+    let values = [
+        stats.average_fps,
+        stats.average_frame_time_ms,
+        stats.p50_frame_time_ms,
+        stats.p95_frame_time_ms,
+        stats.p99_frame_time_ms,
+        stats.dropped_frames as f32,
+        stats.frame_count as f32,
+    ];
+
+    let Ok(env) = (unsafe { JNIEnv::from_raw(env as *mut jni::sys::JNIEnv) }) else {
+        return core::ptr::null_mut();
+    };
+    let Ok(array) = env.new_float_array(values.len() as i32) else {
+        return core::ptr::null_mut();
+    };
+    if env.set_float_array_region(&array, 0, &values).is_err() {
+        return core::ptr::null_mut();
+    }
+    array.into_raw()
+}
+
 #[unsafe(no_mangle)]
 #[jni_fn("name.jinleili.wgpu.RustBridge")]
 pub fn dropWgpuCanvas(_env: *mut JNIEnv, _: JClass, obj: jlong) {
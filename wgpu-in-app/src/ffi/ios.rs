@@ -1,5 +1,9 @@
 use crate::wgpu_canvas::WgpuCanvas;
-use app_surface::{AppSurface, IOSViewObj};
+use app_surface::{
+    AppSurface, EdgeInsets, FrameStats, HostEventCallback, HostEventRelease, IOSViewObj,
+    ParameterValue, ProfileEntry, RawParameterValue, Touch,
+};
+use core::ffi::CStr;
 
 #[unsafe(no_mangle)]
 pub fn create_wgpu_canvas(ios_obj: IOSViewObj) -> *mut libc::c_void {
@@ -9,7 +13,16 @@ pub fn create_wgpu_canvas(ios_obj: IOSViewObj) -> *mut libc::c_void {
     );
     crate::init_logger();
 
-    let obj = WgpuCanvas::new(AppSurface::new(ios_obj), 0_i32);
+    let app_surface = match AppSurface::try_new_with_options(ios_obj, &WgpuCanvas::device_options())
+    {
+        Ok(app_surface) => app_surface,
+        Err(err) => {
+            // 返回空指针让 iOS 端知道创建失败，以便回退或提示用户
+            log::error!("Failed to create AppSurface: {err}");
+            return core::ptr::null_mut();
+        }
+    };
+    let obj = WgpuCanvas::new(app_surface, 0_i32);
     // 使用 Box 对 Rust 对象进行装箱操作。
     // 我们无法将 Rust 对象直接传递给外部语言，通过装箱来传递此对象的胖指针
     let box_obj = Box::new(obj);
@@ -33,8 +46,216 @@ pub fn enter_frame(obj: *mut libc::c_void) {
     obj.enter_frame();
 }
 
+/// App 进入后台时调用，写回管线缓存并释放 surface，device 与示例的状态保持不变
+#[unsafe(no_mangle)]
+pub extern "C" fn suspend_surface(obj: *mut libc::c_void) {
+    let obj = unsafe { &mut *(obj as *mut WgpuCanvas) };
+    obj.suspend();
+}
+
+/// 回到前台后用 `ios_obj` 中的 CAMetalLayer 重建 surface，失败时返回 false
+#[unsafe(no_mangle)]
+pub extern "C" fn resume_surface(obj: *mut libc::c_void, ios_obj: IOSViewObj) -> bool {
+    let obj = unsafe { &mut *(obj as *mut WgpuCanvas) };
+    obj.resume(ios_obj).is_ok()
+}
+
+/// 视图尺寸或所在屏幕的缩放因子改变后调用
+#[unsafe(no_mangle)]
+pub extern "C" fn resize(obj: *mut libc::c_void) {
+    let obj = unsafe { &mut *(obj as *mut WgpuCanvas) };
+    obj.resize();
+}
+
+/// `insets` 为视图的 safeAreaInsets，单位为点
+#[unsafe(no_mangle)]
+pub extern "C" fn set_safe_area(obj: *mut libc::c_void, insets: EdgeInsets) {
+    let obj = unsafe { &mut *(obj as *mut WgpuCanvas) };
+    obj.set_safe_area(insets);
+}
+
+/// `idx` 为示例在 `copy_examples_json` 列表中的位置
 #[unsafe(no_mangle)]
 pub fn change_example(obj: *mut libc::c_void, idx: i32) {
     let obj = unsafe { &mut *(obj as *mut WgpuCanvas) };
     obj.change_example(idx);
 }
+
+/// `id` 为 UTF-8 的示例标识，示例未知、当前设备不支持或创建失败时返回 false
+#[unsafe(no_mangle)]
+pub extern "C" fn change_example_by_id(obj: *mut libc::c_void, id: *const libc::c_char) -> bool {
+    let obj = unsafe { &mut *(obj as *mut WgpuCanvas) };
+    if id.is_null() {
+        return false;
+    }
+    let Ok(id) = unsafe { CStr::from_ptr(id) }.to_str() else {
+        return false;
+    };
+    match obj.change_example_by_id(id) {
+        Ok(()) => true,
+        Err(err) => {
+            log::warn!("{err}");
+            false
+        }
+    }
+}
+
+/// 把当前设备能运行的示例列表的 JSON 描述写入 `buffer`，写入规则与 `copy_parameters_json` 相同
+#[unsafe(no_mangle)]
+pub extern "C" fn copy_examples_json(
+    obj: *mut libc::c_void,
+    buffer: *mut libc::c_char,
+    capacity: usize,
+) -> usize {
+    let obj = unsafe { &*(obj as *mut WgpuCanvas) };
+    copy_json(&obj.examples_json(), buffer, capacity)
+}
+
+/// `touches` 指向 `count` 个连续的 `Touch`，只在本次调用期间被读取
+#[unsafe(no_mangle)]
+pub extern "C" fn touches(obj: *mut libc::c_void, touches: *const Touch, count: usize) {
+    if touches.is_null() || count == 0 {
+        return;
+    }
+    let obj = unsafe { &mut *(obj as *mut WgpuCanvas) };
+    let touches = unsafe { core::slice::from_raw_parts(touches, count) };
+    obj.touches(touches);
+}
+
+/// App 进入后台时调用，把管线缓存写回 Library 目录
+#[unsafe(no_mangle)]
+pub extern "C" fn save_pipeline_cache(obj: *mut libc::c_void) {
+    let obj = unsafe { &mut *(obj as *mut WgpuCanvas) };
+    obj.save_pipeline_cache();
+}
+
+/// 注册接收 `HostEvent` 的回调，`mask` 为 `HostEventKind::mask` 的组合，传入空的回调时取消注册
+///
+/// 创建画布期间发出的事件（如 `Ready`）会在注册时补发。回调在渲染线程上调用，
+/// `release` 可以为空，不为空时在回调被替换或画布被释放时用于释放 `user_data`
+#[unsafe(no_mangle)]
+pub extern "C" fn register_host_event_callback(
+    obj: *mut libc::c_void,
+    callback: Option<HostEventCallback>,
+    user_data: *mut libc::c_void,
+    mask: u32,
+    release: Option<HostEventRelease>,
+) {
+    let obj = unsafe { &mut *(obj as *mut WgpuCanvas) };
+    obj.app_surface
+        .host_events
+        .register(callback, user_data, mask, release);
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn set_hud_visible(obj: *mut libc::c_void, visible: bool) {
+    let obj = unsafe { &mut *(obj as *mut WgpuCanvas) };
+    obj.set_hud_visible(visible);
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn set_profiler_enabled(obj: *mut libc::c_void, enabled: bool) {
+    let obj = unsafe { &*(obj as *mut WgpuCanvas) };
+    obj.set_profiler_enabled(enabled);
+}
+
+/// 把最近一帧的耗时树按深度优先的顺序写入 `entries`，最多写入 `capacity` 项
+///
+/// 返回全部的项数，大于 `capacity` 时说明结果被截断
+#[unsafe(no_mangle)]
+pub extern "C" fn get_profile_entries(
+    obj: *mut libc::c_void,
+    entries: *mut ProfileEntry,
+    capacity: usize,
+) -> usize {
+    let obj = unsafe { &*(obj as *mut WgpuCanvas) };
+    let Some(profile) = obj.latest_profile() else {
+        return 0;
+    };
+    let profile_entries = profile.entries();
+    if !entries.is_null() {
+        let count = profile_entries.len().min(capacity);
+        unsafe { core::ptr::copy_nonoverlapping(profile_entries.as_ptr(), entries, count) };
+    }
+    profile_entries.len()
+}
+
+/// 把当前示例参数的 JSON 描述写入 `buffer`，最多写入 `capacity - 1` 字节并以 `\0` 结尾
+///
+/// 返回 JSON 的全部字节数（不含 `\0`），不小于 `capacity` 时说明结果被截断
+#[unsafe(no_mangle)]
+pub extern "C" fn copy_parameters_json(
+    obj: *mut libc::c_void,
+    buffer: *mut libc::c_char,
+    capacity: usize,
+) -> usize {
+    let obj = unsafe { &*(obj as *mut WgpuCanvas) };
+    copy_json(&obj.parameters_json(), buffer, capacity)
+}
+
+fn copy_json(json: &str, buffer: *mut libc::c_char, capacity: usize) -> usize {
+    if !buffer.is_null() && capacity > 0 {
+        let count = json.len().min(capacity - 1);
+        unsafe {
+            core::ptr::copy_nonoverlapping(json.as_ptr(), buffer as *mut u8, count);
+            *buffer.add(count) = 0;
+        }
+    }
+    json.len()
+}
+
+/// `name` 为 UTF-8 的参数名，没有此参数时返回 false
+#[unsafe(no_mangle)]
+pub extern "C" fn get_parameter(
+    obj: *mut libc::c_void,
+    name: *const libc::c_char,
+    value: *mut RawParameterValue,
+) -> bool {
+    let obj = unsafe { &*(obj as *mut WgpuCanvas) };
+    if name.is_null() || value.is_null() {
+        return false;
+    }
+    let Ok(name) = unsafe { CStr::from_ptr(name) }.to_str() else {
+        return false;
+    };
+    match obj.get_parameter(name) {
+        Some(parameter) => {
+            unsafe { *value = parameter.into() };
+            true
+        }
+        None => false,
+    }
+}
+
+/// 数值超出取值范围时会被限制在范围内，参数不存在、类型未知或不符时返回 false
+#[unsafe(no_mangle)]
+pub extern "C" fn set_parameter(
+    obj: *mut libc::c_void,
+    name: *const libc::c_char,
+    value: RawParameterValue,
+) -> bool {
+    let obj = unsafe { &mut *(obj as *mut WgpuCanvas) };
+    if name.is_null() {
+        return false;
+    }
+    let Ok(name) = unsafe { CStr::from_ptr(name) }.to_str() else {
+        return false;
+    };
+    let Ok(value) = ParameterValue::try_from(value) else {
+        log::warn!("set_parameter: unknown parameter type {}", value.ty);
+        return false;
+    };
+    match obj.set_parameter(name, value) {
+        Ok(()) => true,
+        Err(err) => {
+            log::warn!("{err}");
+            false
+        }
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn get_frame_stats(obj: *mut libc::c_void) -> FrameStats {
+    let obj = unsafe { &*(obj as *mut WgpuCanvas) };
+    obj.app_surface.frame_clock.stats()
+}
@@ -0,0 +1,388 @@
+//! 叠加在示例画面之上的调试信息：帧率、帧时间、适配器、surface 格式与当前示例
+
+use app_surface::{AppSurface, AssetError, AssetSource, FrameTexture, SurfaceFrame};
+use bytemuck::{Pod, Zeroable};
+use std::collections::HashMap;
+use wgpu::TextureFormat;
+
+mod font;
+
+/// 以下颜色均为 sRGB 编码
+const TEXT_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+const PANEL_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 0.6];
+const TARGET_LINE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.5];
+const GOOD_FRAME_COLOR: [f32; 4] = [0.3, 0.85, 0.4, 0.9];
+const SLOW_FRAME_COLOR: [f32; 4] = [0.95, 0.8, 0.2, 0.9];
+const DROPPED_FRAME_COLOR: [f32; 4] = [0.95, 0.3, 0.25, 0.9];
+
+/// 帧时间图纵轴的上限，单位为毫秒
+const GRAPH_MAX_MS: f32 = 50.0;
+/// 帧时间图的高度，单位为字体像素
+const GRAPH_HEIGHT: f32 = 20.0;
+/// 16.7 ms 对应 60 帧
+const TARGET_FRAME_TIME_MS: f32 = 1000.0 / 60.0;
+/// 面板的内边距与外边距，单位为字体像素
+const PADDING: f32 = 3.0;
+
+/// 与 hud.wgsl 中的 `SOLID` 一致，表示纯色矩形
+const SOLID: u32 = u32::MAX;
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct Globals {
+    target_size: [f32; 2],
+    linear_output: u32,
+    _padding: u32,
+}
+
+/// 一个矩形，位置与尺寸的单位为物理像素
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct Quad {
+    rect: [f32; 4],
+    color: [f32; 4],
+    glyph: u32,
+}
+
+pub struct DebugHud {
+    shader: wgpu::ShaderModule,
+    pipeline_layout: wgpu::PipelineLayout,
+    /// 每种渲染目标格式一条管线，切换到 HDR 示例时 surface 格式会变
+    pipelines: HashMap<TextureFormat, wgpu::RenderPipeline>,
+    globals_buf: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    instance_buf: wgpu::Buffer,
+    instance_capacity: usize,
+    quads: Vec<Quad>,
+}
+
+impl DebugHud {
+    pub fn new(app_surface: &AppSurface, assets: &dyn AssetSource) -> Result<Self, AssetError> {
+        let device = &app_surface.device;
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("hud.wgsl"),
+            source: wgpu::ShaderSource::Wgsl(assets.load_string("wgsl_shader/hud.wgsl")?),
+        });
+
+        let (atlas_width, atlas_height) = font::ATLAS_SIZE;
+        let font_texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("hud font"),
+            size: wgpu::Extent3d {
+                width: atlas_width,
+                height: atlas_height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: TextureFormat::R8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        app_surface.queue.write_texture(
+            font_texture.as_image_copy(),
+            &font::atlas_pixels(),
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(atlas_width),
+                rows_per_image: None,
+            },
+            font_texture.size(),
+        );
+
+        let globals_buf = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("hud globals"),
+            size: size_of::<Globals>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: None,
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: wgpu::BufferSize::new(size_of::<Globals>() as _),
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: globals_buf.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(
+                        &font_texture.create_view(&wgpu::TextureViewDescriptor::default()),
+                    ),
+                },
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &[Some(&bind_group_layout)],
+            immediate_size: 0,
+        });
+
+        let instance_capacity = 256;
+        Ok(Self {
+            shader,
+            pipeline_layout,
+            pipelines: HashMap::new(),
+            globals_buf,
+            bind_group,
+            instance_buf: create_instance_buffer(device, instance_capacity),
+            instance_capacity,
+            quads: vec![],
+        })
+    }
+
+    /// 在示例绘制完成、呈现之前叠加到帧上
+    pub fn draw(&mut self, app_surface: &AppSurface, example: &str, frame: &FrameTexture) {
+        let texture = frame.texture();
+        let format = texture.format();
+        self.layout(app_surface, example);
+
+        let device = &app_surface.device;
+        let queue = &app_surface.queue;
+        // sRGB 与浮点格式的渲染目标存储的是线性颜色
+        let linear_output = format != format.remove_srgb_suffix()
+            || matches!(
+                format,
+                TextureFormat::Rgba16Float | TextureFormat::Rgba32Float
+            );
+        queue.write_buffer(
+            &self.globals_buf,
+            0,
+            bytemuck::bytes_of(&Globals {
+                target_size: [texture.width() as f32, texture.height() as f32],
+                linear_output: linear_output as u32,
+                _padding: 0,
+            }),
+        );
+        if self.quads.len() > self.instance_capacity {
+            self.instance_capacity = self.quads.len().next_power_of_two();
+            self.instance_buf = create_instance_buffer(device, self.instance_capacity);
+        }
+        queue.write_buffer(&self.instance_buf, 0, bytemuck::cast_slice(&self.quads));
+
+        if !self.pipelines.contains_key(&format) {
+            let pipeline = self.create_pipeline(app_surface, format);
+            self.pipelines.insert(format, pipeline);
+        }
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor {
+            format: Some(format),
+            ..Default::default()
+        });
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("hud encoder"),
+        });
+        {
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("hud pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                    depth_slice: None,
+                })],
+                ..Default::default()
+            });
+            pass.set_pipeline(&self.pipelines[&format]);
+            pass.set_bind_group(0, &self.bind_group, &[]);
+            pass.set_vertex_buffer(0, self.instance_buf.slice(..));
+            pass.draw(0..4, 0..self.quads.len() as u32);
+        }
+        queue.submit(Some(encoder.finish()));
+    }
+
+    /// 生成本帧的面板、文字与帧时间图
+    fn layout(&mut self, app_surface: &AppSurface, example: &str) {
+        let stats = app_surface.frame_clock.stats();
+        let info = app_surface.adapter.get_info();
+        let mut lines = vec![
+            format!(
+                "{:.1} FPS  {:.2} MS",
+                stats.average_fps, stats.average_frame_time_ms
+            ),
+            format!(
+                "P50 {:.1}  P95 {:.1}  P99 {:.1}  DROPPED {}",
+                stats.p50_frame_time_ms,
+                stats.p95_frame_time_ms,
+                stats.p99_frame_time_ms,
+                stats.dropped_frames
+            ),
+        ];
+        if let Some(gpu_ms) = app_surface
+            .profiler
+            .latest()
+            .and_then(|profile| profile.total_gpu_ms())
+        {
+            lines.push(format!("GPU {gpu_ms:.2} MS"));
+        }
+        lines.push(format!("{} ({:?})", info.name, info.backend));
+        lines.push(format!("FORMAT {:?}", app_surface.config.format));
+        lines.push(format!("EXAMPLE {example}"));
+
+        // 字体像素到物理像素的缩放，逻辑像素下每个字体像素约为 2 点
+        let scale = (app_surface.scale_factor * 2.0).round().max(1.0);
+        let cell = [
+            font::CELL_WIDTH as f32 * scale,
+            font::CELL_HEIGHT as f32 * scale,
+        ];
+        let (safe_origin, _) = app_surface.view_size().safe_rect_physical();
+        let origin = [
+            safe_origin.x + PADDING * scale,
+            safe_origin.y + PADDING * scale,
+        ];
+        let samples: Vec<f32> = app_surface.frame_clock.samples().collect();
+        let text_width = lines.iter().map(|line| line.len()).max().unwrap_or(0) as f32 * cell[0];
+        let graph_width = samples.len().max(1) as f32 * scale;
+        let graph_height = GRAPH_HEIGHT * scale;
+        let padding = PADDING * scale;
+
+        self.quads.clear();
+        self.quads.push(Quad {
+            rect: [
+                origin[0],
+                origin[1],
+                text_width.max(graph_width) + padding * 2.0,
+                lines.len() as f32 * cell[1] + graph_height + padding * 3.0,
+            ],
+            color: PANEL_COLOR,
+            glyph: SOLID,
+        });
+
+        let mut y = origin[1] + padding;
+        for line in &lines {
+            let mut x = origin[0] + padding;
+            for c in line.chars() {
+                if c != ' ' {
+                    self.quads.push(Quad {
+                        rect: [
+                            x,
+                            y,
+                            font::GLYPH_WIDTH as f32 * scale,
+                            font::GLYPH_HEIGHT as f32 * scale,
+                        ],
+                        color: TEXT_COLOR,
+                        glyph: font::glyph_index(c),
+                    });
+                }
+                x += cell[0];
+            }
+            y += cell[1];
+        }
+
+        // 帧时间图：每帧一根竖条，从下往上画
+        let graph_bottom = y + padding + graph_height;
+        for (i, &frame_time_ms) in samples.iter().enumerate() {
+            let height = (frame_time_ms / GRAPH_MAX_MS).min(1.0) * graph_height;
+            let color = if frame_time_ms > TARGET_FRAME_TIME_MS * 1.5 {
+                DROPPED_FRAME_COLOR
+            } else if frame_time_ms > TARGET_FRAME_TIME_MS {
+                SLOW_FRAME_COLOR
+            } else {
+                GOOD_FRAME_COLOR
+            };
+            self.quads.push(Quad {
+                rect: [
+                    origin[0] + padding + i as f32 * scale,
+                    graph_bottom - height,
+                    scale,
+                    height,
+                ],
+                color,
+                glyph: SOLID,
+            });
+        }
+        let target_height = TARGET_FRAME_TIME_MS / GRAPH_MAX_MS * graph_height;
+        self.quads.push(Quad {
+            rect: [
+                origin[0] + padding,
+                graph_bottom - target_height,
+                graph_width,
+                scale.min(2.0),
+            ],
+            color: TARGET_LINE_COLOR,
+            glyph: SOLID,
+        });
+    }
+
+    fn create_pipeline(
+        &self,
+        app_surface: &AppSurface,
+        format: TextureFormat,
+    ) -> wgpu::RenderPipeline {
+        app_surface
+            .device
+            .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("hud pipeline"),
+                layout: Some(&self.pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &self.shader,
+                    entry_point: Some("vs_main"),
+                    compilation_options: Default::default(),
+                    buffers: &[wgpu::VertexBufferLayout {
+                        array_stride: size_of::<Quad>() as wgpu::BufferAddress,
+                        step_mode: wgpu::VertexStepMode::Instance,
+                        attributes: &wgpu::vertex_attr_array![
+                            0 => Float32x4,
+                            1 => Float32x4,
+                            2 => Uint32,
+                        ],
+                    }],
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &self.shader,
+                    entry_point: Some("fs_main"),
+                    compilation_options: Default::default(),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format,
+                        blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleStrip,
+                    ..Default::default()
+                },
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                multiview_mask: None,
+                cache: app_surface.pipeline_cache.as_ref(),
+            })
+    }
+}
+
+fn create_instance_buffer(device: &wgpu::Device, capacity: usize) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("hud instances"),
+        size: (capacity * size_of::<Quad>()) as wgpu::BufferAddress,
+        usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
@@ -1,4 +1,5 @@
 mod examples;
+mod hud;
 mod wgpu_canvas;
 pub use wgpu_canvas::WgpuCanvas;
 
@@ -4,6 +4,6 @@
 fn main() {}
 
 #[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
-fn main() -> Result<(), impl std::error::Error> {
+fn main() -> Result<(), Box<dyn std::error::Error>> {
     wgpu_in_app::desktop::run()
 }
@@ -1,34 +1,215 @@
 use crate::examples::*;
-use app_surface::{AppSurface, SurfaceFrame};
+use crate::hud::DebugHud;
+use app_surface::{
+    AppSurface, AppSurfaceError, AssetChain, DeviceOptions, EdgeInsets, HostEvent, HostEventKind,
+    InputEvent, NativeHandle, ParameterError, ParameterInfo, ParameterValue, ProfileFrame,
+    SurfaceFrame, Touch, TouchTracker, write_json_string,
+};
 
 pub struct WgpuCanvas {
     pub app_surface: AppSurface,
     example: Box<dyn Example>,
+    /// 当前示例在注册表中的描述，回退到空示例时为 `None`
+    example_desc: Option<&'static ExampleDesc>,
+    /// 当前按下的所有指针
+    pub touch_tracker: TouchTracker,
+    /// 示例的着色器与纹理
+    assets: AssetChain,
+    /// 显示时为 `Some`
+    hud: Option<DebugHud>,
+    /// 已通知过宿主 App surface 丢失，重新获取到帧纹理之前不再重复通知
+    surface_lost_reported: bool,
 }
 
 #[allow(dead_code)]
 impl WgpuCanvas {
-    pub fn new(app_surface: AppSurface, idx: i32) -> Self {
+    /// 示例用到的设备特性，均为可选：适配器不支持时由示例自行降级
+    pub fn device_options() -> DeviceOptions {
+        DeviceOptions::default().with_optional_features(
+            wgpu::Features::POLYGON_MODE_LINE
+                | wgpu::Features::DEPTH_CLIP_CONTROL
+                | wgpu::Features::TEXTURE_COMPRESSION_ASTC
+                | wgpu::Features::TEXTURE_COMPRESSION_ASTC_HDR
+                | wgpu::Features::PIPELINE_CACHE
+                | wgpu::Features::TIMESTAMP_QUERY,
+        )
+    }
+
+    /// 示例创建之前先加载管线缓存，切换示例时就不必重新编译已缓存的管线
+    ///
+    /// 与其它窗口共享 device 时沿用已有的管线缓存
+    pub fn new(mut app_surface: AppSurface, idx: i32) -> Self {
+        if app_surface.pipeline_cache.is_none() && !app_surface.library_directory.is_empty() {
+            let directory = app_surface.library_directory.clone();
+            match app_surface.ctx.load_pipeline_cache(directory) {
+                Ok(true) => log::info!("Pipeline cache loaded"),
+                Ok(false) => log::info!("Pipeline cache is not supported on this device"),
+                Err(err) => {
+                    let message = format!("Failed to load pipeline cache: {err}");
+                    log::warn!("{message}");
+                    app_surface.host_events.emit(&HostEvent::Warning(message));
+                }
+            }
+        }
         let example = Box::new(Empty::new(&app_surface));
+        let assets = example_assets(&app_surface.library_directory);
 
         let mut instance = WgpuCanvas {
             app_surface,
             example,
+            example_desc: None,
+            touch_tracker: TouchTracker::new(),
+            assets,
+            hud: None,
+            surface_lost_reported: false,
         };
         instance.change_example(idx);
+        instance.emit(HostEvent::Ready);
+        instance
+    }
 
-        if let Some(callback) = instance.app_surface.callback_to_app {
-            callback(0);
+    /// 把管线缓存写回磁盘，App 进入后台时调用，因为之后进程可能被系统直接终止
+    pub fn save_pipeline_cache(&mut self) {
+        if let Err(err) = self.app_surface.save_pipeline_cache() {
+            self.warn(format!("Failed to save pipeline cache: {err}"));
         }
-        instance
+    }
+
+    pub fn is_hud_visible(&self) -> bool {
+        self.hud.is_some()
+    }
+
+    /// 显示或隐藏调试 HUD，隐藏时释放 HUD 的 GPU 资源
+    pub fn set_hud_visible(&mut self, visible: bool) {
+        self.hud = if visible {
+            match DebugHud::new(&self.app_surface, &self.assets) {
+                Ok(hud) => Some(hud),
+                Err(err) => {
+                    self.report_error(format!("Failed to create HUD: {err}"));
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        self.app_surface.ctx.defer_present = self.hud.is_some();
+    }
+
+    pub fn set_profiler_enabled(&self, enabled: bool) {
+        self.app_surface.profiler.set_enabled(enabled);
+    }
+
+    /// 最近一个已读回 GPU 时间的帧，未启用分析器时为 `None`
+    pub fn latest_profile(&self) -> Option<ProfileFrame> {
+        self.app_surface.profiler.latest()
     }
 
     pub fn enter_frame(&mut self) {
+        if self.app_surface.is_suspended() {
+            return;
+        }
+        if self.app_surface.is_device_lost() && !self.recover_device() {
+            return;
+        }
+        self.app_surface.enter_frame();
         self.example.enter_frame(&self.app_surface);
+        // 开启 HUD 时示例的帧被推迟呈现，在这里叠加 HUD 后再呈现
+        if let Some(frame) = self.app_surface.take_deferred_frame() {
+            let name = self.example_name();
+            if let Some(ref mut hud) = self.hud {
+                hud.draw(&self.app_surface, name, &frame);
+            }
+            frame.present();
+        }
+
+        if self.app_surface.is_surface_lost() {
+            if !self.surface_lost_reported {
+                self.surface_lost_reported = true;
+                self.emit(HostEvent::SurfaceLost);
+            }
+        } else {
+            self.surface_lost_reported = false;
+        }
+        // 统计百分位需要排序，没有宿主 App 接收时跳过
+        if self
+            .app_surface
+            .host_events
+            .wants(HostEventKind::FrameRendered)
+        {
+            let stats = self.app_surface.frame_clock.stats();
+            self.emit(HostEvent::FrameRendered(stats));
+        }
+    }
+
+    /// App 进入后台时调用：先写回管线缓存，再让示例释放临时资源，最后释放 surface
+    ///
+    /// device 与示例的状态都保持不变，`resume` 之前 `enter_frame` 不做任何事
+    pub fn suspend(&mut self) {
+        if self.app_surface.is_suspended() {
+            return;
+        }
+        self.save_pipeline_cache();
+        self.example.suspend(&self.app_surface);
+        self.app_surface.suspend();
+        // 在后台期间抬起的指针不会再收到事件
+        self.touch_tracker.clear();
+    }
 
-        if let Some(_callback) = self.app_surface.callback_to_app {
-            // callback(1);
+    /// 回到前台后用新的原生视图重建 surface，失败时同时通过 `HostEvent::Error` 通知宿主 App
+    ///
+    /// 没有挂起时不做任何事
+    pub fn resume(&mut self, handle: NativeHandle) -> Result<(), AppSurfaceError> {
+        if !self.app_surface.is_suspended() {
+            return Ok(());
         }
+        if let Err(err) = self.app_surface.resume(handle) {
+            self.report_error(format!("Failed to resume surface: {err}"));
+            return Err(err);
+        }
+        self.surface_lost_reported = false;
+        self.example.resume(&self.app_surface);
+        // 新视图的尺寸可能与挂起前不同
+        self.example.resize(&self.app_surface);
+        Ok(())
+    }
+
+    /// 重建 GPU 上下文及当前示例的 GPU 资源，返回是否恢复成功
+    ///
+    /// 恢复失败时下一帧会再次尝试
+    fn recover_device(&mut self) -> bool {
+        self.emit(HostEvent::DeviceLost);
+        match futures_lite::future::block_on(self.app_surface.ctx.recover_device()) {
+            Ok(_) => {
+                if let Err(err) = self.example.rebuild(&mut self.app_surface, &self.assets) {
+                    self.report_error(format!("Failed to rebuild example: {err}"));
+                    self.example = Box::new(Empty::new(&self.app_surface));
+                    self.example_desc = None;
+                }
+                if self.hud.is_some() {
+                    self.set_hud_visible(true);
+                }
+                self.emit(HostEvent::DeviceRecovered);
+                true
+            }
+            Err(err) => {
+                self.report_error(format!("Failed to recover device: {err}"));
+                false
+            }
+        }
+    }
+
+    fn emit(&mut self, event: HostEvent) {
+        self.app_surface.host_events.emit(&event);
+    }
+
+    fn warn(&mut self, message: String) {
+        log::warn!("{message}");
+        self.emit(HostEvent::Warning(message));
+    }
+
+    fn report_error(&mut self, message: String) {
+        log::error!("{message}");
+        self.emit(HostEvent::Error(message));
     }
 
     pub fn resize(&mut self) {
@@ -36,23 +217,145 @@ impl WgpuCanvas {
         self.example.resize(&self.app_surface);
     }
 
+    /// 宿主 App 一次传入同一时刻所有变化了的指针
+    pub fn touches(&mut self, touches: &[Touch]) {
+        self.touch_tracker.update_batch(touches);
+        self.app_surface.touches(touches);
+        self.example.touches(&self.app_surface, touches);
+    }
+
+    pub fn input(&mut self, event: &InputEvent) {
+        if let InputEvent::Pointer(touch) = event {
+            self.touch_tracker.update(touch);
+            self.app_surface.touch(*touch);
+        }
+        self.example.input(&self.app_surface, event);
+    }
+
+    /// 视图移到 DPI 不同的屏幕上后调用
+    pub fn set_scale_factor(&mut self, scale_factor: f32) {
+        self.app_surface.set_scale_factor(scale_factor);
+        self.resize();
+    }
+
+    /// 安全区域改变后，示例在 `resize` 中重新布局
+    pub fn set_safe_area(&mut self, insets: EdgeInsets) {
+        self.app_surface.set_safe_area(insets);
+        self.example.resize(&self.app_surface);
+    }
+
+    /// 当前适配器能运行的示例，按注册表中的顺序排列
+    pub fn examples(&self) -> Vec<&'static ExampleDesc> {
+        supported_examples(&self.app_surface)
+    }
+
+    /// 当前适配器能运行的示例，编码为 JSON 数组，供宿主 App 生成示例列表
+    pub fn examples_json(&self) -> String {
+        let examples: Vec<String> = self
+            .examples()
+            .iter()
+            .map(|desc| {
+                let mut json = String::from("{\"id\":");
+                write_json_string(&mut json, desc.id);
+                json.push_str(",\"name\":");
+                write_json_string(&mut json, desc.name);
+                json.push_str(",\"description\":");
+                write_json_string(&mut json, desc.description);
+                json.push('}');
+                json
+            })
+            .collect();
+        format!("[{}]", examples.join(","))
+    }
+
+    pub fn current_example(&self) -> Option<&'static ExampleDesc> {
+        self.example_desc
+    }
+
+    fn example_name(&self) -> &'static str {
+        self.example_desc.map_or("Empty", |desc| desc.name)
+    }
+
+    /// 按序号切换示例，序号即 `examples()`（也就是 `examples_json`）中的位置，
+    /// 失败时通过 `HostEvent::Error` 通知宿主 App
     pub fn change_example(&mut self, index: i32) {
-        self.example = Self::create_a_example(&mut self.app_surface, index);
-    }
-
-    fn create_a_example(app_surface: &mut AppSurface, index: i32) -> Box<dyn Example> {
-        if index == 0 {
-            Box::new(Boids::new(app_surface))
-        } else if index == 1 {
-            Box::new(MSAALine::new(app_surface))
-        } else if index == 2 {
-            Box::new(Cube::new(app_surface))
-        } else if index == 3 {
-            Box::new(Water::new(app_surface))
-        } else if index == 4 {
-            Box::new(Shadow::new(app_surface))
-        } else {
-            Box::new(HDRImageView::new(app_surface))
+        let examples = self.examples();
+        let Some(desc) = usize::try_from(index).ok().and_then(|i| examples.get(i)) else {
+            self.report_error(format!(
+                "Failed to change example: no example at index {index}"
+            ));
+            return;
+        };
+        if let Err(err) = self.change_example_by_id(desc.id) {
+            self.report_error(format!("Failed to change example: {err}"));
         }
     }
+
+    /// 未知或当前适配器不支持的示例不会替换当前示例；
+    /// 创建失败时回退到空示例
+    pub fn change_example_by_id(&mut self, id: &str) -> Result<(), ExampleError> {
+        let desc = find_example(id).ok_or_else(|| ExampleError::Unknown(id.to_string()))?;
+        let result = match desc.create(&mut self.app_surface, &self.assets) {
+            Ok(example) => {
+                self.example = example;
+                self.example_desc = Some(desc);
+                Ok(())
+            }
+            Err(ExampleError::Asset(err)) => {
+                self.example = Box::new(Empty::new(&self.app_surface));
+                self.example_desc = None;
+                Err(ExampleError::Asset(err))
+            }
+            Err(err) => return Err(err),
+        };
+        // 能走到这里说明示例受支持，一定在 `examples()` 中
+        let index = self
+            .examples()
+            .iter()
+            .position(|supported| supported.id == desc.id)
+            .map_or(-1, |index| index as i32);
+        let name = self.example_name().to_string();
+        self.emit(HostEvent::ExampleChanged { index, name });
+        result
+    }
+
+    /// 当前示例可调节的参数，切换示例后需重新获取
+    pub fn list_parameters(&self) -> &'static [ParameterInfo] {
+        self.example.parameters()
+    }
+
+    /// 当前示例的参数及其当前值，编码为 JSON 数组，供宿主 App 生成设置界面
+    pub fn parameters_json(&self) -> String {
+        let parameters: Vec<String> = self
+            .list_parameters()
+            .iter()
+            .map(|info| info.to_json(self.get_parameter(info.name).unwrap_or(info.default)))
+            .collect();
+        format!("[{}]", parameters.join(","))
+    }
+
+    pub fn get_parameter(&self, name: &str) -> Option<ParameterValue> {
+        self.example.parameter(name)
+    }
+
+    /// 数值超出取值范围时会被限制在范围内
+    pub fn set_parameter(
+        &mut self,
+        name: &str,
+        value: ParameterValue,
+    ) -> Result<(), ParameterError> {
+        let info = self
+            .list_parameters()
+            .iter()
+            .find(|info| info.name == name)
+            .ok_or_else(|| ParameterError::Unknown(name.to_string()))?;
+        let value = info.validate(value)?;
+        self.example.set_parameter(&self.app_surface, name, value)
+    }
+}
+
+impl Drop for WgpuCanvas {
+    fn drop(&mut self) {
+        self.save_pipeline_cache();
+    }
 }
@@ -0,0 +1,66 @@
+struct Globals {
+    // 渲染目标的物理像素尺寸
+    target_size: vec2f,
+    // 为 1 时把 sRGB 颜色转换为线性颜色后输出
+    linear_output: u32,
+    _padding: u32,
+};
+
+struct VertexOutput {
+    @builtin(position) position: vec4f,
+    @location(0) color: vec4f,
+    // 字形内的像素坐标
+    @location(1) glyph_uv: vec2f,
+    @location(2) @interpolate(flat) glyph: u32,
+};
+
+// 纯色矩形的字形索引
+const SOLID: u32 = 0xffffffffu;
+// 字形为 5x7 像素，在字体纹理中占 6x8 的格子
+const GLYPH_SIZE: vec2f = vec2f(5.0, 7.0);
+const GLYPH_CELL_WIDTH: i32 = 6;
+
+@group(0) @binding(0) var<uniform> globals: Globals;
+@group(0) @binding(1) var font: texture_2d<f32>;
+
+@vertex
+fn vs_main(
+    @builtin(vertex_index) vertex_index: u32,
+    @location(0) rect: vec4f,
+    @location(1) color: vec4f,
+    @location(2) glyph: u32,
+) -> VertexOutput {
+    // 三角形带的 4 个顶点
+    let corner = vec2f(f32(vertex_index & 1u), f32(vertex_index >> 1u));
+    let pixel = rect.xy + corner * rect.zw;
+    let ndc = pixel / globals.target_size * 2.0 - 1.0;
+
+    var result: VertexOutput;
+    result.position = vec4f(ndc.x, -ndc.y, 0.0, 1.0);
+    result.color = color;
+    result.glyph_uv = corner * GLYPH_SIZE;
+    result.glyph = glyph;
+    return result;
+}
+
+fn srgb_to_linear(value: vec3f) -> vec3f {
+    let low = value / 12.92;
+    let high = pow((value + 0.055) / 1.055, vec3f(2.4));
+    return select(high, low, value <= vec3f(0.04045));
+}
+
+@fragment
+fn fs_main(vertex: VertexOutput) -> @location(0) vec4f {
+    var color = vertex.color;
+    if (vertex.glyph != SOLID) {
+        let texel = vec2i(
+            i32(vertex.glyph) * GLYPH_CELL_WIDTH + min(i32(vertex.glyph_uv.x), 4),
+            min(i32(vertex.glyph_uv.y), 6),
+        );
+        color.a *= textureLoad(font, texel, 0).r;
+    }
+    if (globals.linear_output == 1u) {
+        color = vec4f(srgb_to_linear(color.rgb), color.a);
+    }
+    return color;
+}